@@ -1,4 +1,5 @@
 use std::env;
+use std::process::Command;
 
 #[cfg(target_os = "windows")]
 extern crate winres;
@@ -9,6 +10,33 @@ fn main() {
     let is_android = cfg!(target_os = "android") || (cfg!(target_os = "linux") && target.contains("android")); // hack
 
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    // HEAD only changes when switching branches, new commits update the branch ref instead
+    if let Some(head_ref) = std::fs::read_to_string(".git/HEAD")
+        .ok()
+        .and_then(|head| head.strip_prefix("ref: ").map(|head_ref| head_ref.trim().to_owned()))
+    {
+        for path in [format!(".git/{}", head_ref), ".git/packed-refs".to_owned()] {
+            // missing files would make cargo rerun the script on every build
+            if std::path::Path::new(&path).exists() {
+                println!("cargo:rerun-if-changed={}", path);
+            }
+        }
+    }
+    println!("cargo:rerun-if-env-changed=DRS_BUILD_HASH");
+
+    let build_hash = env::var("DRS_BUILD_HASH").ok().or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "--short", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|hash| hash.trim().to_owned())
+    });
+
+    println!("cargo:rustc-env=DRS_BUILD_HASH={}", build_hash.as_deref().unwrap_or("unknown"));
+    println!("cargo:rustc-env=DRS_BUILD_TARGET={}", target);
 
     #[cfg(target_os = "windows")]
     {
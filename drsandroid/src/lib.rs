@@ -1,7 +1,14 @@
 #[cfg(target_os = "android")]
 #[cfg_attr(target_os = "android", ndk_glue::main())]
 pub fn android_main() {
-    let options = doukutsu_rs::game::LaunchOptions { server_mode: false, editor: false };
+    let options = doukutsu_rs::game::LaunchOptions {
+        server_mode: false,
+        editor: false,
+        gl_version: None,
+        no_opengl: false,
+        validate: false,
+        benchmark_ticks: None,
+    };
 
     doukutsu_rs::init(options).unwrap();
 }
@@ -19,6 +19,16 @@ lazy_static! {
         let version = option_env!("DRS_BUILD_VERSION_OVERRIDE").unwrap_or(env!("CARGO_PKG_VERSION"));
         format!("doukutsu-rs {}", version)
     };
+    pub static ref BUILD_INFO: String = {
+        let profile = if cfg!(debug_assertions) { "debug" } else { "release" };
+        format!(
+            "{} (build {}, {} {})",
+            *VERSION_BANNER,
+            option_env!("DRS_BUILD_HASH").unwrap_or("unknown"),
+            option_env!("DRS_BUILD_TARGET").unwrap_or("unknown"),
+            profile
+        )
+    };
 }
 
 bitfield! {
@@ -194,6 +204,13 @@ pub enum FadeState {
     FadeOut(i8, FadeDirection),
 }
 
+impl FadeState {
+    #[inline]
+    pub fn is_fading_out(&self) -> bool {
+        matches!(self, FadeState::FadeOut(_, _))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Direction {
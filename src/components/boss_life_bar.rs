@@ -7,6 +7,9 @@ use crate::game::shared_game_state::SharedGameState;
 use crate::game::npc::boss::BossNPC;
 use crate::game::npc::list::NPCList;
 
+/// How long the bar stays on screen after the boss dies, in ticks.
+const HIDE_DELAY: u16 = 40;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 enum BossLifeTarget {
@@ -20,12 +23,28 @@ pub struct BossLifeBar {
     life: u16,
     max_life: u16,
     prev_life: u16,
+    /// Life shown by the bar, follows `life` over a few ticks instead of snapping to it.
+    shown_life: u16,
     counter: u16,
+    hide_counter: u16,
 }
 
 impl BossLifeBar {
     pub fn new() -> BossLifeBar {
-        BossLifeBar { target: BossLifeTarget::None, life: 0, max_life: 0, prev_life: 0, counter: 0 }
+        BossLifeBar {
+            target: BossLifeTarget::None,
+            life: 0,
+            max_life: 0,
+            prev_life: 0,
+            shown_life: 0,
+            counter: 0,
+            hide_counter: 0,
+        }
+    }
+
+    /// Whether the bar follows an NPC or boss, it's hidden otherwise.
+    pub fn has_target(&self) -> bool {
+        !matches!(self.target, BossLifeTarget::None)
     }
 
     pub fn set_npc_target(&mut self, npc_id: u16, npc_list: &NPCList) {
@@ -34,6 +53,8 @@ impl BossLifeBar {
             self.life = npc.life;
             self.max_life = self.life;
             self.prev_life = self.life;
+            self.shown_life = self.life;
+            self.hide_counter = 0;
         }
     }
 
@@ -42,6 +63,8 @@ impl BossLifeBar {
         self.life = boss.parts[0].life;
         self.max_life = self.life;
         self.prev_life = self.life;
+        self.shown_life = self.life;
+        self.hide_counter = 0;
     }
 
     fn draw_regular(&self, state: &mut SharedGameState, ctx: &mut Context, _frame: &Frame) -> GameResult {
@@ -57,7 +80,7 @@ impl BossLifeBar {
         let mut rect_life_bar = Rect::new_size(0, 24, 232, 8);
 
         rect_prev_bar.right = ((self.prev_life as u32 * bar_length) / self.max_life as u32).min(bar_length) as u16;
-        rect_life_bar.right = ((self.life as u32 * bar_length) / self.max_life as u32).min(bar_length) as u16;
+        rect_life_bar.right = ((self.shown_life as u32 * bar_length) / self.max_life as u32).min(bar_length) as u16;
 
         batch.add_rect(
             ((state.canvas_size.0 - box_length as f32) / 2.0).floor(),
@@ -110,7 +133,7 @@ impl BossLifeBar {
         let mut rect_life_bar = Rect::new_size(0, 24, 124, 8);
 
         rect_prev_bar.right = ((self.prev_life as u32 * bar_length) / self.max_life as u32).min(bar_length) as u16;
-        rect_life_bar.right = ((self.life as u32 * bar_length) / self.max_life as u32).min(bar_length) as u16;
+        rect_life_bar.right = ((self.shown_life as u32 * bar_length) / self.max_life as u32).min(bar_length) as u16;
 
         let base_x = state.canvas_size.0 - box_length as f32;
 
@@ -133,22 +156,29 @@ impl BossLifeBar {
 impl GameEntity<(&NPCList, &BossNPC)> for BossLifeBar {
     fn tick(&mut self, _state: &mut SharedGameState, (npc_list, boss): (&NPCList, &BossNPC)) -> GameResult<()> {
         match self.target {
+            // targets removed without their life running out (e.g. by <DNP) count as dead,
+            // otherwise the bar would stay on screen for the rest of the stage
             BossLifeTarget::NPC(npc_id) => {
                 if let Some(npc) = npc_list.get_npc(npc_id as usize) {
-                    self.life = npc.life;
+                    self.life = if npc.cond.alive() { npc.life } else { 0 };
                 }
             }
             BossLifeTarget::Boss => {
-                self.life = boss.parts[0].life;
+                let part = &boss.parts[0];
+                self.life = if part.cond.alive() { part.life } else { 0 };
             }
             _ => {
                 return Ok(());
             }
         }
 
-        if self.life == 0 {
-            self.target = BossLifeTarget::None;
-        } else if self.prev_life > self.life {
+        if self.shown_life > self.life {
+            self.shown_life -= ((self.shown_life - self.life) / 4).max(1);
+        } else if self.shown_life < self.life {
+            self.shown_life += ((self.life - self.shown_life) / 4).max(1);
+        }
+
+        if self.prev_life > self.life {
             self.counter += 1;
             if self.counter > 30 {
                 self.prev_life = self.prev_life.saturating_sub(1);
@@ -157,6 +187,15 @@ impl GameEntity<(&NPCList, &BossNPC)> for BossLifeBar {
             self.counter = 0;
         }
 
+        if self.life == 0 {
+            self.hide_counter += 1;
+            if self.hide_counter > HIDE_DELAY {
+                self.target = BossLifeTarget::None;
+            }
+        } else {
+            self.hide_counter = 0;
+        }
+
         Ok(())
     }
 
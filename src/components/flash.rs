@@ -10,6 +10,8 @@ pub enum FlashState {
     None,
     Cross(i32, i32, u16),
     Blink(u16),
+    /// Single flash fading out, (tick, duration).
+    Fade(u16, u16),
 }
 
 pub struct Flash {
@@ -31,9 +33,30 @@ impl Flash {
         self.state = FlashState::Blink(0);
     }
 
+    pub fn set_fade(&mut self, duration: u16) {
+        self.state = FlashState::Fade(0, duration.max(1));
+    }
+
     pub fn stop(&mut self) {
         self.state = FlashState::None;
     }
+
+    pub fn is_active(&self) -> bool {
+        !matches!(self.state, FlashState::None)
+    }
+
+    fn draw_fade(state: &SharedGameState, ctx: &mut Context, tick: u16, duration: u16, intensity: f32) -> GameResult {
+        let progress = ((tick as f32 + state.frame_time as f32) / duration as f32).min(1.0);
+        let alpha = (1.0 - progress) * intensity;
+        let rect = Rect {
+            left: 0,
+            top: 0,
+            right: (state.canvas_size.0 * state.scale) as isize,
+            bottom: (state.canvas_size.1 * state.scale) as isize,
+        };
+
+        graphics::draw_rect(ctx, rect, Color::new(1.0, 1.0, 1.0, alpha))
+    }
 }
 
 impl GameEntity<()> for Flash {
@@ -54,6 +77,13 @@ impl GameEntity<()> for Flash {
                     FlashState::Blink(tick + 1)
                 };
             }
+            FlashState::Fade(tick, duration) => {
+                self.state = if tick >= duration {
+                    FlashState::None
+                } else {
+                    FlashState::Fade(tick + 1, duration)
+                };
+            }
         }
         Ok(())
     }
@@ -99,11 +129,19 @@ impl GameEntity<()> for Flash {
                     graphics::draw_rect(ctx, rect, WHITE)?;
                 }
             }
+            FlashState::Blink(tick) if state.settings.reduced_flashing => {
+                // a single dim flash instead of strobing
+                Self::draw_fade(state, ctx, tick, 21, 0.5)?;
+            }
             FlashState::Blink(tick) => {
                 if tick / 2 % 2 != 0 {
                     graphics::clear(ctx, WHITE);
                 }
             }
+            FlashState::Fade(tick, duration) => {
+                let intensity = if state.settings.reduced_flashing { 0.5 } else { 1.0 };
+                Self::draw_fade(state, ctx, tick, duration, intensity)?;
+            }
         }
 
         Ok(())
@@ -165,7 +165,7 @@ impl GameEntity<(&Player, &mut Inventory)> for HUD {
 
                     if state.touch_controls.consume_click_in(rect) {
                         state.sound_manager.play_sfx(4);
-                        inventory.current_weapon = a as u16;
+                        inventory.select_weapon(a as u16);
                         self.weapon_x_pos = 32;
                     }
                 }
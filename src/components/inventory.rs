@@ -132,7 +132,7 @@ impl GameEntity<(&mut Context, &mut Player, &mut Inventory, &mut HUD)> for Inven
         }
 
         self.selected_item = inventory.current_item;
-        self.selected_weapon = inventory.current_weapon;
+        self.selected_weapon = inventory.get_current_weapon_idx();
 
         let count_x = state.constants.textscript.inventory_item_count_x as u16;
 
@@ -212,12 +212,12 @@ impl GameEntity<(&mut Context, &mut Player, &mut Inventory, &mut HUD)> for Inven
                 }
 
                 if player.controller.trigger_down() {
-                    if self.selected_item / 6 == self.item_count.saturating_sub(1) / 6 {
+                    if self.selected_item / count_x == (self.item_count - 1) / count_x {
                         self.focus = InventoryFocus::Weapons;
 
                         state.sound_manager.play_sfx(4);
                         state.control_flags.set_ok_button_disabled(false);
-                        moved_cursor = true;
+                        state.textscript_vm.start_script(self.get_weapon_event_number(inventory));
                     } else {
                         self.selected_item += count_x;
 
@@ -233,12 +233,25 @@ impl GameEntity<(&mut Context, &mut Player, &mut Inventory, &mut HUD)> for Inven
                     state.textscript_vm.start_script(self.get_item_event_number(inventory));
                 }
 
-                if !state.control_flags.ok_button_disabled() && player.controller.trigger_menu_ok() {
+                if self.focus == InventoryFocus::Items
+                    && !state.control_flags.ok_button_disabled()
+                    && player.controller.trigger_menu_ok()
+                {
                     state.textscript_vm.start_script(self.get_item_event_number_action(inventory));
                 }
 
                 inventory.current_item = self.selected_item;
             }
+            InventoryFocus::Items if state.control_flags.control_enabled() => {
+                // nothing to select, let the cursor go back to weapons
+                if player.controller.trigger_up() || player.controller.trigger_down() {
+                    self.focus = InventoryFocus::Weapons;
+
+                    state.sound_manager.play_sfx(4);
+                    state.control_flags.set_ok_button_disabled(false);
+                    state.textscript_vm.start_script(self.get_weapon_event_number(inventory));
+                }
+            }
             _ => {}
         }
 
@@ -253,7 +266,7 @@ impl GameEntity<(&mut Context, &mut Player, &mut Inventory, &mut HUD)> for Inven
                     self.focus = InventoryFocus::Weapons;
                     state.sound_manager.play_sfx(4);
                     self.selected_weapon = i;
-                    inventory.current_weapon = i;
+                    inventory.select_weapon(i);
                     state.textscript_vm.start_script(self.get_weapon_event_number(inventory));
                     self.exit(state, player, inventory, hud);
                 }
@@ -437,3 +450,54 @@ impl GameEntity<(&mut Context, &mut Player, &mut Inventory, &mut HUD)> for Inven
         Ok(())
     }
 }
+
+#[test]
+fn test_inventory_cursor() {
+    use crate::game::HeadlessRunner;
+    use crate::input::replay_player_controller::{KeyState, ReplayController};
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    state.control_flags.set_control_enabled(true);
+    let mut player = Player::new(&mut state, &mut ctx);
+    let mut hud = HUD::new(Alignment::Left);
+
+    // ticks with `keys` just pressed down, returns the selected item or `None` if the weapons are focused
+    let mut press = |ui: &mut InventoryUI, inventory: &mut Inventory, keys: fn(&mut KeyState)| {
+        let mut controller = ReplayController::new();
+        keys(&mut controller.state);
+        controller.update_trigger();
+        player.controller = Box::new(controller);
+
+        ui.tick(&mut state, (&mut ctx, &mut player, inventory, &mut hud)).unwrap();
+        (ui.focus == InventoryFocus::Items).then_some(inventory.current_item)
+    };
+
+    // two rows of 6 and 2 items
+    let mut inventory = Inventory::new();
+    for id in 1..=8 {
+        inventory.add_item(id);
+    }
+
+    let mut ui = InventoryUI::new();
+    assert_eq!(press(&mut ui, &mut inventory, |_| {}), None);
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_down(true)), Some(0));
+    // wraps around within the row
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_left(true)), Some(5));
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_right(true)), Some(0));
+    // the last row is shorter
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_down(true)), Some(6));
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_right(true)), Some(7));
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_right(true)), Some(6));
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_left(true)), Some(7));
+    // leaving the grid goes back to the weapons
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_down(true)), None);
+
+    // with nothing in it, there's nowhere to move to
+    let mut inventory = Inventory::new();
+    let mut ui = InventoryUI::new();
+    assert_eq!(press(&mut ui, &mut inventory, |_| {}), None);
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_down(true)), Some(0));
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_left(true)), Some(0));
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_right(true)), Some(0));
+    assert_eq!(press(&mut ui, &mut inventory, |k| k.set_up(true)), None);
+}
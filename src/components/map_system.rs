@@ -143,9 +143,11 @@ impl MapSystem {
                     self.state = MapSystemState::Visible;
                 }
 
+                // the map key that opened the map also closes it, like the inventory key does for the inventory
                 for player in &players {
                     if player.controller.trigger_jump()
                         || player.controller.trigger_shoot()
+                        || player.controller.trigger_map()
                         || state.touch_controls.consume_click_in(touch_rect)
                     {
                         self.state = MapSystemState::FadeOutBox(8);
@@ -157,6 +159,7 @@ impl MapSystem {
                 for player in &players {
                     if player.controller.trigger_jump()
                         || player.controller.trigger_shoot()
+                        || player.controller.trigger_map()
                         || state.touch_controls.consume_click_in(touch_rect)
                     {
                         self.state = MapSystemState::FadeOutBox(8);
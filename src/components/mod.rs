@@ -1,3 +1,4 @@
+pub mod ambient_emitters;
 pub mod background;
 pub mod boss_life_bar;
 pub mod credits;
@@ -14,5 +15,6 @@ pub mod replay;
 pub mod stage_select;
 pub mod text_boxes;
 pub mod tilemap;
+pub mod toast;
 pub mod water_renderer;
 pub mod whimsical_star;
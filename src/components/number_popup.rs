@@ -30,8 +30,10 @@ impl NumberPopup {
         self.value = value;
     }
 
+    /// Adds to the currently shown value, so quick successive hits are shown as one growing number
+    /// instead of several overlapping ones, same as the original.
     pub fn add_value(&mut self, value: i16) {
-        self.set_value(self.value + value);
+        self.set_value(self.value.saturating_add(value));
     }
 
     pub fn set_value_throttled(&mut self, value: i16) {
@@ -40,7 +42,7 @@ impl NumberPopup {
     }
 
     pub fn add_value_throttled(&mut self, value: i16) {
-        self.set_value_throttled(self.value + value);
+        self.set_value_throttled(self.value.saturating_add(value));
     }
 }
 
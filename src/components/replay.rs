@@ -4,7 +4,7 @@ use byteorder::{LE, ReadBytesExt, WriteBytesExt};
 
 use crate::entity::GameEntity;
 use crate::framework::context::Context;
-use crate::framework::error::GameResult;
+use crate::framework::error::{GameError, GameResult};
 use crate::framework::filesystem;
 use crate::framework::keyboard::ScanCode;
 use crate::framework::vfs::OpenOptions;
@@ -14,9 +14,11 @@ use crate::input::replay_player_controller::{KeyState, ReplayController};
 use crate::game::player::Player;
 use crate::graphics::font::Font;
 
+/// Version of the replay file format written by this build, replays with a different version aren't played back.
+const REPLAY_VERSION: u16 = 0;
+
 #[derive(Clone)]
 pub struct Replay {
-    replay_version: u16,
     keylist: Vec<u16>,
     last_input: KeyState,
     rng_seed: u64,
@@ -29,7 +31,6 @@ pub struct Replay {
 impl Replay {
     pub fn new() -> Replay {
         Replay {
-            replay_version: 0,
             keylist: Vec::new(),
             last_input: KeyState(0),
             rng_seed: 0,
@@ -83,9 +84,11 @@ impl Replay {
         if let Ok(mut file) = filesystem::open_options(
             ctx,
             [state.get_rec_filename(), replay_kind.get_suffix()].join(""),
-            OpenOptions::new().write(true).create(true),
+            // truncate, otherwise a shorter run leaves stale inputs from the previous replay at the end of the file
+            OpenOptions::new().write(true).create(true).truncate(true),
         ) {
-            file.write_u16::<LE>(0)?; // Space for versioning replay files
+            file.write_u16::<LE>(REPLAY_VERSION)?;
+            // effect_rng doesn't need to be stored, it's reseeded from the map name on every stage load
             file.write_u64::<LE>(self.rng_seed)?;
             for input in &self.keylist {
                 file.write_u16::<LE>(*input)?;
@@ -97,24 +100,53 @@ impl Replay {
     fn read_replay(&mut self, state: &mut SharedGameState, ctx: &mut Context, replay_kind: ReplayKind) -> GameResult {
         if let Ok(mut file) = filesystem::user_open(ctx, [state.get_rec_filename(), replay_kind.get_suffix()].join(""))
         {
-            self.replay_version = file.read_u16::<LE>()?;
-            self.rng_seed = file.read_u64::<LE>()?;
-
             let mut data = Vec::new();
             file.read_to_end(&mut data)?;
 
-            let count = data.len() / 2;
-            let mut inputs = Vec::new();
-            let mut f = Cursor::new(data);
-
-            for _ in 0..count {
-                inputs.push(f.read_u16::<LE>()?);
-            }
-
+            let (rng_seed, inputs) = parse_replay(&data)?;
+            self.rng_seed = rng_seed;
             self.keylist = inputs;
         }
         Ok(())
     }
+
+    /// Whether the stored replay exists and was recorded in a format this build can play back.
+    pub fn can_play_back(state: &SharedGameState, ctx: &mut Context, replay_kind: ReplayKind) -> bool {
+        let mut data = Vec::new();
+
+        let mut file = match filesystem::user_open(ctx, [state.get_rec_filename(), replay_kind.get_suffix()].join("")) {
+            Ok(file) => file,
+            Err(_) => return false,
+        };
+
+        match file.read_to_end(&mut data).map_err(GameError::from).and_then(|_| parse_replay(&data)) {
+            Ok(_) => true,
+            Err(err) => {
+                log::warn!("Cannot play back the replay: {}", err);
+                false
+            }
+        }
+    }
+}
+
+/// Splits the contents of a replay file into the RNG seed and the recorded inputs.
+fn parse_replay(data: &[u8]) -> GameResult<(u64, Vec<u16>)> {
+    let mut f = Cursor::new(data);
+
+    let version = f.read_u16::<LE>()?;
+    if version != REPLAY_VERSION {
+        return Err(GameError::ParseError(format!("Unsupported replay version: {}", version)));
+    }
+
+    let rng_seed = f.read_u64::<LE>()?;
+
+    let count = data.len().saturating_sub(f.position() as usize) / 2;
+    let mut inputs = Vec::with_capacity(count);
+    for _ in 0..count {
+        inputs.push(f.read_u16::<LE>()?);
+    }
+
+    Ok((rng_seed, inputs))
 }
 
 impl GameEntity<(&mut Context, &mut Player)> for Replay {
@@ -167,7 +199,12 @@ impl GameEntity<(&mut Context, &mut Player)> for Replay {
 
     fn draw(&self, state: &mut SharedGameState, ctx: &mut Context, _frame: &Frame) -> GameResult {
         let x = state.canvas_size.0 - 32.0;
-        let y = 8.0 + if state.settings.fps_counter { 12.0 } else { 0.0 };
+        let y = 8.0
+            + match (state.settings.fps_counter, state.settings.debug_outlines) {
+                (true, true) => 20.0,
+                (true, false) => 12.0,
+                (false, _) => 0.0,
+            };
 
         match state.replay_state {
             ReplayState::None => {}
@@ -186,3 +223,20 @@ impl GameEntity<(&mut Context, &mut Player)> for Replay {
         Ok(())
     }
 }
+
+#[test]
+fn test_parse_replay() {
+    let mut data = Vec::new();
+    data.write_u16::<LE>(REPLAY_VERSION).unwrap();
+    data.write_u64::<LE>(0x1234).unwrap();
+    data.write_u16::<LE>(3).unwrap();
+    data.write_u16::<LE>(5).unwrap();
+
+    let (rng_seed, inputs) = parse_replay(&data).unwrap();
+    assert_eq!(rng_seed, 0x1234);
+    assert_eq!(inputs, vec![3, 5]);
+
+    // replays from other builds are refused instead of playing back garbage
+    data[0..2].copy_from_slice(&(REPLAY_VERSION + 1).to_le_bytes());
+    assert!(parse_replay(&data).is_err());
+}
@@ -162,7 +162,8 @@ impl GameEntity<()> for TextBoxes {
             };
             let batch = state.texture_set.get_or_load_batch(ctx, &state.constants, tex_name)?;
 
-            let face_x = (4.0 + (6 - self.slide_in) as f32 * 8.0) - 52.0;
+            // slide_in is reset to 7 by <FAC, which can be observed here before the next tick decrements it
+            let face_x = (4.0 + (6 - self.slide_in.min(6)) as f32 * 8.0) - 52.0;
 
             let final_x = left_pos + 14.0 + face_x;
             let final_y = top_pos + 8.0;
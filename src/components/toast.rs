@@ -0,0 +1,53 @@
+use crate::entity::GameEntity;
+use crate::framework::context::Context;
+use crate::framework::error::GameResult;
+use crate::game::frame::Frame;
+use crate::game::shared_game_state::SharedGameState;
+
+const TOAST_DURATION: u16 = 100;
+const TOAST_FADE_TICKS: u16 = 20;
+
+/// Short message shown in the corner of the screen to confirm actions like quicksaving.
+pub struct Toast {
+    text: String,
+    counter: u16,
+}
+
+impl Toast {
+    pub fn new() -> Toast {
+        Toast { text: String::new(), counter: 0 }
+    }
+
+    pub fn show(&mut self, text: &str) {
+        self.text.clear();
+        self.text.push_str(text);
+        self.counter = TOAST_DURATION;
+    }
+}
+
+impl GameEntity<()> for Toast {
+    fn tick(&mut self, _state: &mut SharedGameState, _custom: ()) -> GameResult {
+        self.counter = self.counter.saturating_sub(1);
+
+        Ok(())
+    }
+
+    fn draw(&self, state: &mut SharedGameState, ctx: &mut Context, _frame: &Frame) -> GameResult {
+        if self.counter == 0 {
+            return Ok(());
+        }
+
+        let alpha = (self.counter.min(TOAST_FADE_TICKS) as u32 * 255 / TOAST_FADE_TICKS as u32) as u8;
+        let width = state.font.builder().compute_width(&self.text);
+
+        state
+            .font
+            .builder()
+            .position(state.canvas_size.0 - width - 8.0, state.canvas_size.1 - state.font.line_height() - 8.0)
+            .shadow(true)
+            .color((255, 255, 255, alpha))
+            .draw(&self.text, ctx, &state.constants, &mut state.texture_set)?;
+
+        Ok(())
+    }
+}
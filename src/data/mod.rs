@@ -1,3 +1,4 @@
 pub mod builtin_fs;
 pub mod exe_parser;
+pub mod sif;
 pub mod vanilla;
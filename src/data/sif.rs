@@ -0,0 +1,327 @@
+use std::io::{Cursor, Read};
+
+use byteorder::{ReadBytesExt, LE};
+
+use crate::common::Rect;
+use crate::framework::error::{GameError::ParseError, GameResult};
+
+/// `'SIF2'` multi-character constant, as NXEngine-evo writes it.
+const SIF_MAGIC: u32 = 0x5349_4632;
+
+const SECTION_SHEETS: u8 = 0;
+const SECTION_SPRITES: u8 = 1;
+const SECTION_NAMES: u8 = 2;
+
+// sprite and frame fields, a sprite's field list and each of its frames' field lists end with a zero
+const S_END: u8 = 0;
+const S_NUM_FRAMES: u8 = 1;
+const S_NUM_DIRS: u8 = 2;
+const S_SIZE: u8 = 3;
+const S_SPRITESHEET: u8 = 4;
+const S_BBOX: u8 = 5;
+const S_SOLIDBOX: u8 = 6;
+const S_SPAWN_POINT: u8 = 7;
+const S_BLOCKL: u8 = 8;
+const S_BLOCKR: u8 = 9;
+const S_BLOCKU: u8 = 10;
+const S_BLOCKD: u8 = 11;
+const S_DIR_SHEET_OFFSET: u8 = 12;
+const S_DIR_DRAW_POINT: u8 = 13;
+const S_DIR_ACTION_POINT: u8 = 14;
+const S_DIR_ACTION_POINT_2: u8 = 15;
+const S_DIR_PF_BBOX: u8 = 16;
+
+/// Maximum number of directions a sprite can have frames for.
+const MAX_DIRS: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct SifSprite {
+    pub name: String,
+    pub width: u16,
+    pub height: u16,
+    /// Index into [`SifFile::sheets`].
+    pub spritesheet: usize,
+    /// Number of directions the sprite has its own frames for.
+    pub num_dirs: usize,
+    /// Top left corner of every frame in the sprite sheet, per direction. Directions past `num_dirs` repeat
+    /// the first one, like NXEngine-evo draws them.
+    pub frames: Vec<[(u16, u16); MAX_DIRS]>,
+}
+
+impl SifSprite {
+    /// Returns the sheet rect of given frame, facing the given direction.
+    pub fn frame_rect(&self, frame: usize, dir: usize) -> Option<Rect<u16>> {
+        let &(x, y) = self.frames.get(frame)?.get(dir)?;
+
+        Some(Rect::new(x, y, x + self.width, y + self.height))
+    }
+}
+
+/// Sprite definitions read from NXEngine-evo's `sprites.sif`.
+#[derive(Debug, Clone)]
+pub struct SifFile {
+    /// File names of the sprite sheets, relative to the data directory.
+    pub sheets: Vec<String>,
+    pub sprites: Vec<SifSprite>,
+}
+
+impl SifFile {
+    pub fn load<R: Read>(mut reader: R) -> GameResult<SifFile> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        SifFile::parse(&data)
+    }
+
+    pub fn parse(data: &[u8]) -> GameResult<SifFile> {
+        let mut f = Cursor::new(data);
+
+        if f.read_u32::<LE>()? != SIF_MAGIC {
+            return Err(ParseError("Invalid sprites.sif magic.".to_string()));
+        }
+
+        let mut sheets = Vec::new();
+        let mut names = Vec::new();
+        let mut sprites = Vec::new();
+
+        let section_count = f.read_u8()?;
+        for _ in 0..section_count {
+            let section_type = f.read_u8()?;
+            let offset = f.read_u32::<LE>()? as usize;
+            let length = f.read_u32::<LE>()? as usize;
+
+            let section = offset
+                .checked_add(length)
+                .and_then(|end| data.get(offset..end))
+                .ok_or_else(|| ParseError(format!("sprites.sif section {} is out of bounds.", section_type)))?;
+
+            match section_type {
+                SECTION_SHEETS => sheets = read_string_array(section)?,
+                SECTION_SPRITES => sprites = read_sprites(section)?,
+                SECTION_NAMES => names = read_string_array(section)?,
+                _ => {}
+            }
+        }
+
+        for (sprite, name) in sprites.iter_mut().zip(names.into_iter()) {
+            sprite.name = name;
+        }
+
+        Ok(SifFile { sheets, sprites })
+    }
+
+    pub fn sprite(&self, name: &str) -> Option<&SifSprite> {
+        self.sprites.iter().find(|sprite| sprite.name == name)
+    }
+
+    /// Returns the name of the sprite sheet a sprite is drawn from, without the directory and extension.
+    pub fn sheet_name(&self, sprite: &SifSprite) -> Option<&str> {
+        let path = self.sheets.get(sprite.spritesheet)?;
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+
+        Some(file_name.split('.').next().unwrap_or(file_name))
+    }
+}
+
+fn read_string(f: &mut Cursor<&[u8]>) -> GameResult<String> {
+    let mut length = f.read_u8()? as usize;
+    // lengths past 127 take up two bytes
+    if length & 0x80 != 0 {
+        length = ((length & 0x7f) << 8) | f.read_u8()? as usize;
+    }
+
+    let mut buf = vec![0u8; length];
+    f.read_exact(&mut buf)?;
+
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+fn read_string_array(data: &[u8]) -> GameResult<Vec<String>> {
+    let mut f = Cursor::new(data);
+    let count = f.read_u16::<LE>()?;
+
+    (0..count).map(|_| read_string(&mut f)).collect()
+}
+
+fn read_point(f: &mut Cursor<&[u8]>) -> GameResult<(i16, i16)> {
+    Ok((f.read_i16::<LE>()?, f.read_i16::<LE>()?))
+}
+
+fn read_sprites(data: &[u8]) -> GameResult<Vec<SifSprite>> {
+    let mut f = Cursor::new(data);
+    let count = f.read_u16::<LE>()?;
+    let mut sprites = Vec::with_capacity(count as usize);
+
+    for _ in 0..count {
+        let mut sprite =
+            SifSprite { name: String::new(), width: 0, height: 0, spritesheet: 0, num_dirs: 1, frames: Vec::new() };
+        let mut frame_count = 1;
+
+        loop {
+            match f.read_u8()? {
+                S_END => break,
+                S_NUM_FRAMES => frame_count = f.read_u8()? as usize,
+                S_NUM_DIRS => sprite.num_dirs = (f.read_u8()? as usize).clamp(1, MAX_DIRS),
+                S_SIZE => {
+                    sprite.width = f.read_u8()? as u16;
+                    sprite.height = f.read_u8()? as u16;
+                }
+                S_SPRITESHEET => sprite.spritesheet = f.read_u8()? as usize,
+                S_BBOX | S_SOLIDBOX => {
+                    read_point(&mut f)?;
+                    read_point(&mut f)?;
+                }
+                S_SPAWN_POINT => {
+                    read_point(&mut f)?;
+                }
+                S_BLOCKL | S_BLOCKR | S_BLOCKU | S_BLOCKD => {
+                    for _ in 0..f.read_u8()? {
+                        read_point(&mut f)?;
+                    }
+                }
+                field => return Err(ParseError(format!("Unknown sprites.sif sprite field {}.", field))),
+            }
+        }
+
+        for _ in 0..frame_count {
+            let mut frame = [(0, 0); MAX_DIRS];
+
+            loop {
+                let field = f.read_u8()?;
+                if field == S_END {
+                    break;
+                }
+
+                let dir = f.read_u8()? as usize;
+                if dir >= MAX_DIRS {
+                    return Err(ParseError(format!("Invalid sprites.sif frame direction {}.", dir)));
+                }
+
+                match field {
+                    S_DIR_SHEET_OFFSET => {
+                        let (x, y) = read_point(&mut f)?;
+                        frame[dir] = (x.max(0) as u16, y.max(0) as u16);
+                    }
+                    S_DIR_DRAW_POINT | S_DIR_ACTION_POINT | S_DIR_ACTION_POINT_2 => {
+                        read_point(&mut f)?;
+                    }
+                    S_DIR_PF_BBOX => {
+                        read_point(&mut f)?;
+                        read_point(&mut f)?;
+                    }
+                    field => return Err(ParseError(format!("Unknown sprites.sif frame field {}.", field))),
+                }
+            }
+
+            let first = frame[0];
+            frame[sprite.num_dirs..].fill(first);
+
+            sprite.frames.push(frame);
+        }
+
+        sprites.push(sprite);
+    }
+
+    Ok(sprites)
+}
+
+#[cfg(test)]
+fn write_strings(strings: &[&str]) -> Vec<u8> {
+    let mut out = (strings.len() as u16).to_le_bytes().to_vec();
+    for string in strings {
+        out.push(string.len() as u8);
+        out.extend_from_slice(string.as_bytes());
+    }
+    out
+}
+
+/// Builds a sprites.sif with one two frame sprite with two directions and two single frame sprites.
+#[cfg(test)]
+fn test_sif() -> Vec<u8> {
+    let sheets = write_strings(&["Npc/NpcSym.pbm", "Caret.pbm"]);
+    let names = write_strings(&["SPR_TEST", "SPR_ZZZZ", "SPR_QMARK"]);
+
+    let mut sprites = vec![3, 0];
+    // SPR_TEST: 16x16, 2 frames, sheet 0, with a bounding box and a block point to skip over
+    sprites.extend_from_slice(&[S_SIZE, 16, 16, S_NUM_FRAMES, 2, S_NUM_DIRS, 2, S_SPRITESHEET, 0]);
+    sprites.extend_from_slice(&[S_BBOX, 2, 0, 3, 0, 14, 0, 16, 0, S_BLOCKD, 1, 8, 0, 16, 0, S_END]);
+    for frame in 0..2u8 {
+        sprites.extend_from_slice(&[S_DIR_SHEET_OFFSET, 0, frame * 16, 0, 32, 0]);
+        sprites.extend_from_slice(&[S_DIR_DRAW_POINT, 1, 8, 0, 8, 0]);
+        sprites.extend_from_slice(&[S_DIR_SHEET_OFFSET, 1, frame * 16, 0, 48, 0, S_END]);
+    }
+    // SPR_ZZZZ: 8x8, 1 frame, sheet 1
+    sprites.extend_from_slice(&[S_SIZE, 8, 8, S_SPRITESHEET, 1, S_END]);
+    sprites.extend_from_slice(&[S_DIR_SHEET_OFFSET, 0, 40, 0, 48, 0, S_END]);
+    // SPR_QMARK: 8x8, 1 frame facing only one direction, sheet 1
+    sprites.extend_from_slice(&[S_SIZE, 8, 8, S_NUM_DIRS, 1, S_SPRITESHEET, 1, S_END]);
+    sprites.extend_from_slice(&[S_DIR_SHEET_OFFSET, 0, 0, 0, 80, 0, S_END]);
+
+    let mut data = SIF_MAGIC.to_le_bytes().to_vec();
+    data.push(3);
+    let mut offset = 4 + 1 + 3 * 9;
+    for (section_type, section) in [(SECTION_SHEETS, &sheets), (SECTION_SPRITES, &sprites), (SECTION_NAMES, &names)] {
+        data.push(section_type);
+        data.extend_from_slice(&(offset as u32).to_le_bytes());
+        data.extend_from_slice(&(section.len() as u32).to_le_bytes());
+        offset += section.len();
+    }
+    data.extend_from_slice(&sheets);
+    data.extend_from_slice(&sprites);
+    data.extend_from_slice(&names);
+    data
+}
+
+#[cfg(test)]
+fn corners(rect: Rect<u16>) -> (u16, u16, u16, u16) {
+    (rect.left, rect.top, rect.right, rect.bottom)
+}
+
+#[test]
+fn test_parse_sif() {
+    let sif = SifFile::parse(&test_sif()).unwrap();
+
+    assert_eq!(sif.sheets, vec!["Npc/NpcSym.pbm".to_string(), "Caret.pbm".to_string()]);
+    assert_eq!(sif.sprites.len(), 3);
+
+    let sprite = sif.sprite("SPR_TEST").unwrap();
+    assert_eq!(sif.sheet_name(sprite), Some("NpcSym"));
+    assert_eq!(sprite.num_dirs, 2);
+    assert_eq!(sprite.frames.len(), 2);
+    assert_eq!(sprite.frame_rect(0, 0).map(corners), Some((0, 32, 16, 48)));
+    assert_eq!(sprite.frame_rect(1, 1).map(corners), Some((16, 48, 32, 64)));
+    assert!(sprite.frame_rect(2, 0).is_none());
+
+    let sprite = sif.sprite("SPR_ZZZZ").unwrap();
+    assert_eq!(sif.sheet_name(sprite), Some("Caret"));
+    assert_eq!(sprite.frame_rect(0, 0).map(corners), Some((40, 48, 48, 56)));
+    // without a direction count only the first direction is defined, the others repeat it
+    assert_eq!(sprite.num_dirs, 1);
+    assert_eq!(sprite.frame_rect(0, 2).map(corners), Some((40, 48, 48, 56)));
+    assert!(sif.sprite("SPR_MISSING").is_none());
+
+    assert!(SifFile::parse(b"SIF1\x00").is_err());
+    // truncated section data
+    let data = test_sif();
+    assert!(SifFile::parse(&data[..data.len() - 4]).is_err());
+}
+
+#[test]
+fn test_apply_sif_patches() {
+    use crate::engine_constants::EngineConstants;
+
+    let sif = SifFile::parse(&test_sif()).unwrap();
+    let mut constants = EngineConstants::defaults();
+    let level_up_rects: Vec<_> = constants.caret.level_up_rects.iter().copied().map(corners).collect();
+    let question_right_rect = corners(constants.caret.question_right_rect);
+    constants.apply_sif_patches(&sif);
+
+    // only the frames the sprite defines get replaced
+    assert_eq!(corners(constants.caret.zzz_rects[0]), (40, 48, 48, 56));
+    assert_ne!(corners(constants.caret.zzz_rects[1]), (40, 48, 48, 56));
+    // sprites missing from the file keep the original rects
+    assert_eq!(constants.caret.level_up_rects.iter().copied().map(corners).collect::<Vec<_>>(), level_up_rects);
+    // a question mark with a single direction only replaces the left facing one
+    assert_eq!(corners(constants.caret.question_left_rect), (0, 80, 8, 88));
+    assert_eq!(corners(constants.caret.question_right_rect), question_right_rect);
+}
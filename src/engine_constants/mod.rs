@@ -7,6 +7,7 @@ use xmltree::Element;
 
 use crate::case_insensitive_hashmap;
 use crate::common::{BulletFlag, Color, Rect};
+use crate::data::sif::SifFile;
 use crate::engine_constants::npcs::NPCConsts;
 use crate::framework::context::Context;
 use crate::framework::error::GameResult;
@@ -51,6 +52,14 @@ pub struct PlayerConsts {
     pub control_mode: ControlMode,
     pub air_physics: PhysicsConsts,
     pub water_physics: PhysicsConsts,
+    /// Extra upward velocity given when jumping out of water, if enabled in settings.
+    pub water_exit_boost: i32,
+    /// For how many ticks a jump pressed in mid-air is remembered and performed on landing. 0 matches the original.
+    pub jump_buffer_ticks: u8,
+    /// For how many ticks after walking off a ledge the player can still jump. 0 matches the original.
+    pub coyote_ticks: u8,
+    /// For how many ticks the player can't be hurt again after taking damage, the sprite blinks during that time.
+    pub invulnerability_ticks: u8,
     pub frames_left: [Rect<u16>; 12],
     pub frames_right: [Rect<u16>; 12],
     pub frames_bubble: [Rect<u16>; 2],
@@ -138,6 +147,127 @@ pub struct BulletData {
     pub display_bounds: Rect<u8>,
 }
 
+/// Movement and animation of a bullet type that has no hardcoded behavior, defined in `bullets.json`.
+#[derive(Debug, Clone)]
+pub struct CustomBulletData {
+    pub speed: i32,
+    pub frames: Vec<Rect<u16>>,
+    pub anim_speed: u16,
+}
+
+/// Distance based damage falloff of a bullet type, defined in `bullets.json`.
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DamageFalloff {
+    /// Distance in pixels the bullet can travel at full damage.
+    pub start: u16,
+    /// Distance in pixels over which the bullet loses one point of damage after that.
+    pub step: u16,
+    /// Damage never drops below this value.
+    #[serde(default = "default_falloff_min_damage")]
+    pub min_damage: u8,
+}
+
+#[inline(always)]
+fn default_falloff_min_damage() -> u8 {
+    1
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BulletDefinition {
+    damage: u8,
+    /// Number of enemies the bullet can hit before it's gone.
+    #[serde(alias = "pierce")]
+    life: u8,
+    lifetime: u16,
+    #[serde(default)]
+    flags: u8,
+    /// (width, height)
+    enemy_hit: (u16, u16),
+    /// (width, height)
+    block_hit: (u16, u16),
+    /// (left, top, right, bottom)
+    display_bounds: (u8, u8, u8, u8),
+    #[serde(default)]
+    speed: i32,
+    /// (left, top, right, bottom) rects on the Bullet spritesheet
+    #[serde(default)]
+    frames: Vec<(u16, u16, u16, u16)>,
+    #[serde(default)]
+    anim_speed: u16,
+    #[serde(default)]
+    falloff: Option<DamageFalloff>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BulletDefinitionTable {
+    bullets: HashMap<u16, BulletDefinition>,
+}
+
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct PhysicsOverrides {
+    max_dash: Option<i32>,
+    max_move: Option<i32>,
+    gravity_ground: Option<i32>,
+    gravity_air: Option<i32>,
+    dash_ground: Option<i32>,
+    dash_air: Option<i32>,
+    resist: Option<i32>,
+    jump: Option<i32>,
+}
+
+impl PhysicsOverrides {
+    fn apply(&self, physics: &mut PhysicsConsts, name: &str) {
+        let fields = [
+            ("max_dash", self.max_dash, &mut physics.max_dash),
+            ("max_move", self.max_move, &mut physics.max_move),
+            ("gravity_ground", self.gravity_ground, &mut physics.gravity_ground),
+            ("gravity_air", self.gravity_air, &mut physics.gravity_air),
+            ("dash_ground", self.dash_ground, &mut physics.dash_ground),
+            ("dash_air", self.dash_air, &mut physics.dash_air),
+            ("resist", self.resist, &mut physics.resist),
+            ("jump", self.jump, &mut physics.jump),
+        ];
+
+        for (field, value, target) in fields {
+            match value {
+                Some(value) if value < 0 => {
+                    log::warn!("constants.json: {}.{} can't be negative, ignoring.", name, field);
+                }
+                Some(value) => *target = value,
+                None => {}
+            }
+        }
+    }
+}
+
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct PlayerOverrides {
+    life: Option<u16>,
+    max_life: Option<u16>,
+    air_physics: PhysicsOverrides,
+    water_physics: PhysicsOverrides,
+    water_exit_boost: Option<i32>,
+    jump_buffer_ticks: Option<u8>,
+    coyote_ticks: Option<u8>,
+    invulnerability_ticks: Option<u8>,
+}
+
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct BoosterOverrides {
+    fuel: Option<u32>,
+}
+
+/// Partial overrides of engine constants, read from `constants.json`. Omitted fields keep their defaults.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct ConstantOverrides {
+    player: PlayerOverrides,
+    booster: BoosterOverrides,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct BulletRects {
     pub b001_snake_l1: [Rect<u16>; 8],
@@ -180,6 +310,8 @@ pub struct WeaponConsts {
     pub bullet_table: Vec<BulletData>,
     pub bullet_rects: BulletRects,
     pub level_table: [[u16; 3]; 14],
+    pub custom_bullets: HashMap<u16, CustomBulletData>,
+    pub damage_falloff: HashMap<u16, DamageFalloff>,
 }
 
 impl Clone for WeaponConsts {
@@ -188,6 +320,8 @@ impl Clone for WeaponConsts {
             bullet_table: self.bullet_table.clone(),
             bullet_rects: self.bullet_rects,
             level_table: self.level_table,
+            custom_bullets: self.custom_bullets.clone(),
+            damage_falloff: self.damage_falloff.clone(),
         }
     }
 }
@@ -218,6 +352,13 @@ pub struct TextScriptConsts {
     pub encrypted: bool,
     pub reset_invicibility_on_any_script: bool,
     pub animated_face_pics: bool,
+    /// `<DNP` with this event number removes every enemy in the room instead, see `GameScene::clear_room`.
+    pub clear_room_event: Option<u16>,
+    /// Length of the `<FLA` flash in ticks, the script waits for it to finish.
+    pub flash_duration: u16,
+    /// `<MYB0000` in an event started by an NPC knocks the players away from that NPC instead of always to the right.
+    /// Off by default, original scripts rely on the fixed direction.
+    pub myb_away_from_executor: bool,
     pub textbox_rect_top: Rect<u16>,
     pub textbox_rect_middle: Rect<u16>,
     pub textbox_rect_bottom: Rect<u16>,
@@ -341,6 +482,8 @@ pub struct EngineConstants {
     pub animated_face_table: Vec<AnimatedFace>,
     pub string_table: HashMap<String, String>,
     pub missile_flags: Vec<u16>,
+    /// Maximum number of simultaneously alive dynamically spawned NPCs, by NPC type.
+    pub npc_spawn_limits: HashMap<u16, u16>,
     pub locales: Vec<Locale>,
     pub gamepad: GamepadConsts,
 }
@@ -374,6 +517,7 @@ impl Clone for EngineConstants {
             animated_face_table: self.animated_face_table.clone(),
             string_table: self.string_table.clone(),
             missile_flags: self.missile_flags.clone(),
+            npc_spawn_limits: self.npc_spawn_limits.clone(),
             locales: self.locales.clone(),
             gamepad: self.gamepad.clone(),
         }
@@ -421,6 +565,10 @@ impl EngineConstants {
                     resist: 0x19,
                     jump: 0x280,
                 },
+                water_exit_boost: 0x200,
+                jump_buffer_ticks: 0,
+                coyote_ticks: 0,
+                invulnerability_ticks: 128,
                 frames_left: [
                     Rect { left: 0, top: 0, right: 16, bottom: 16 },
                     Rect { left: 16, top: 0, right: 32, bottom: 16 },
@@ -1365,6 +1513,8 @@ impl EngineConstants {
                     [1, 1, 1],
                     [40, 60, 200],
                 ],
+                custom_bullets: HashMap::new(),
+                damage_falloff: HashMap::new(),
             },
             tex_sizes: case_insensitive_hashmap! {
                 "ArmsImage" => (256, 16),
@@ -1524,6 +1674,9 @@ impl EngineConstants {
                 encrypted: true,
                 reset_invicibility_on_any_script: true,
                 animated_face_pics: false,
+                clear_room_event: None,
+                flash_duration: 20,
+                myb_away_from_executor: false,
                 textbox_rect_top: Rect { left: 0, top: 0, right: 244, bottom: 8 },
                 textbox_rect_middle: Rect { left: 0, top: 8, right: 244, bottom: 16 },
                 textbox_rect_bottom: Rect { left: 0, top: 16, right: 244, bottom: 24 },
@@ -1672,6 +1825,7 @@ impl EngineConstants {
             animated_face_table: vec![AnimatedFace { face_id: 0, anim_id: 0, anim_frames: vec![(0, 0)] }],
             string_table: HashMap::new(),
             missile_flags: vec![200, 201, 202, 218, 550, 766, 880, 920, 1551],
+            npc_spawn_limits: HashMap::new(),
             locales: Vec::new(),
             gamepad: GamepadConsts {
                 button_rects: HashMap::from([
@@ -1702,6 +1856,46 @@ impl EngineConstants {
         }
     }
 
+    /// Replaces caret rects with the frames NXEngine-evo's `sprites.sif` defines for them. Sprites missing
+    /// from the file or moved to another sheet keep the original rects, so do directions a sprite has no
+    /// frames for.
+    pub fn apply_sif_patches(&mut self, sif: &SifFile) {
+        log::info!("Applying sprites.sif constants patches...");
+
+        let caret = &mut self.caret;
+        let rect_lists: [(&str, &mut Vec<Rect<u16>>); 4] = [
+            ("SPR_ZZZZ", &mut caret.zzz_rects),
+            ("SPR_LEVELUP", &mut caret.level_up_rects),
+            ("SPR_LEVELDOWN", &mut caret.level_down_rects),
+            ("SPR_EMPTY", &mut caret.empty_text),
+        ];
+
+        let caret_sprite = |name: &str| {
+            sif.sprite(name).filter(|sprite| sif.sheet_name(sprite).map_or(false, |s| s.eq_ignore_ascii_case("Caret")))
+        };
+
+        for (name, rects) in rect_lists {
+            if let Some(sprite) = caret_sprite(name) {
+                for (frame, rect) in rects.iter_mut().enumerate() {
+                    if let Some(sif_rect) = sprite.frame_rect(frame, 0) {
+                        *rect = sif_rect;
+                    }
+                }
+            }
+        }
+
+        if let Some(sprite) = caret_sprite("SPR_QMARK") {
+            if let Some(rect) = sprite.frame_rect(0, 0) {
+                caret.question_left_rect = rect;
+            }
+            if sprite.num_dirs > 1 {
+                if let Some(rect) = sprite.frame_rect(0, 1) {
+                    caret.question_right_rect = rect;
+                }
+            }
+        }
+    }
+
     pub fn apply_csplus_patches(&mut self, sound_manager: &mut SoundManager) {
         log::info!("Applying Cave Story+ constants patches...");
 
@@ -1904,7 +2098,81 @@ impl EngineConstants {
         Ok(())
     }
 
-    pub fn apply_constant_json_files(&mut self) {}
+    /// Overlays player and booster constants with the values present in `constants.json`.
+    /// Values that are out of range are ignored with a warning, same as a malformed file.
+    pub fn apply_constant_json_files(&mut self, ctx: &mut Context) -> GameResult {
+        // start from defaults, so overrides of a previously loaded mod don't carry over
+        let defaults = EngineConstants::defaults();
+        self.player.life = defaults.player.life;
+        self.player.max_life = defaults.player.max_life;
+        self.player.air_physics = defaults.player.air_physics;
+        self.player.water_physics = defaults.player.water_physics;
+        self.player.water_exit_boost = defaults.player.water_exit_boost;
+        self.player.jump_buffer_ticks = defaults.player.jump_buffer_ticks;
+        self.player.coyote_ticks = defaults.player.coyote_ticks;
+        self.player.invulnerability_ticks = defaults.player.invulnerability_ticks;
+        self.booster = defaults.booster;
+
+        let file = match filesystem::open_find(ctx, &self.base_paths, "constants.json") {
+            Ok(file) => file,
+            Err(_) => return Ok(()),
+        };
+
+        let overrides = match serde_json::from_reader::<_, ConstantOverrides>(file) {
+            Ok(overrides) => overrides,
+            Err(err) => {
+                log::warn!("Failed to deserialize constant overrides: {}", err);
+                return Ok(());
+            }
+        };
+
+        let player = &overrides.player;
+        if let Some(max_life) = player.max_life {
+            if max_life == 0 {
+                log::warn!("constants.json: player.max_life must be greater than 0, ignoring.");
+            } else {
+                self.player.max_life = max_life;
+            }
+        }
+
+        let life = player.life.unwrap_or(self.player.life.min(self.player.max_life));
+        if life == 0 || life > self.player.max_life {
+            log::warn!("constants.json: player.life must be between 1 and player.max_life, ignoring.");
+        } else {
+            self.player.life = life;
+        }
+
+        player.air_physics.apply(&mut self.player.air_physics, "player.air_physics");
+        player.water_physics.apply(&mut self.player.water_physics, "player.water_physics");
+
+        if let Some(boost) = player.water_exit_boost {
+            self.player.water_exit_boost = boost;
+        }
+
+        if let Some(ticks) = player.jump_buffer_ticks {
+            self.player.jump_buffer_ticks = ticks;
+        }
+
+        if let Some(ticks) = player.coyote_ticks {
+            self.player.coyote_ticks = ticks;
+        }
+
+        if let Some(ticks) = player.invulnerability_ticks {
+            if ticks == 0 {
+                log::warn!("constants.json: player.invulnerability_ticks must be greater than 0, ignoring.");
+            } else {
+                self.player.invulnerability_ticks = ticks;
+            }
+        }
+
+        if let Some(fuel) = overrides.booster.fuel {
+            self.booster.fuel = fuel;
+        }
+
+        log::info!("Loaded constants.json.");
+
+        Ok(())
+    }
 
     pub fn load_texture_size_hints(&mut self, ctx: &mut Context) -> GameResult {
         if let Ok(file) = filesystem::open_find(ctx, &self.base_paths, "texture_sizes.json") {
@@ -1920,10 +2188,30 @@ impl EngineConstants {
         Ok(())
     }
 
-    /// Loads bullet.tbl and arms_level.tbl from CS+ files,
-    /// even though they match vanilla 1:1, we should load them for completeness
-    /// or if any crazy person uses it for a CS+ mod...
-    pub fn load_csplus_tables(&mut self, ctx: &mut Context) -> GameResult {
+    /// Loads per-type NPC spawn limits from `npc_limits.json`, a map of NPC type to maximum alive count.
+    pub fn load_npc_spawn_limits(&mut self, ctx: &mut Context) -> GameResult {
+        self.npc_spawn_limits.clear();
+
+        if let Ok(file) = filesystem::open_find(ctx, &self.base_paths, "npc_limits.json") {
+            match serde_json::from_reader::<_, HashMap<u16, u16>>(file) {
+                Ok(limits) => self.npc_spawn_limits = limits,
+                Err(err) => log::warn!("Failed to deserialize NPC spawn limits: {}", err),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads bullet definitions from `bullets.json`, overriding entries of the bullet table and
+    /// defining the sprites of bullet types that aren't handled by the engine.
+    ///
+    /// The table is rebuilt from the built-in one (or CS+ bullet.tbl, which matches vanilla 1:1) first,
+    /// so overrides of a previously loaded mod don't stick around.
+    pub fn load_bullet_definitions(&mut self, ctx: &mut Context) -> GameResult {
+        self.weapon.bullet_table = EngineConstants::defaults().weapon.bullet_table;
+        self.weapon.custom_bullets.clear();
+        self.weapon.damage_falloff.clear();
+
         if let Ok(mut file) = filesystem::open_find(ctx, &self.base_paths, "bullet.tbl") {
             let mut data = Vec::new();
             file.read_to_end(&mut data)?;
@@ -1955,6 +2243,72 @@ impl EngineConstants {
             log::info!("Loaded bullet.tbl.");
         }
 
+        if let Ok(file) = filesystem::open_find(ctx, &self.base_paths, "bullets.json") {
+            match serde_json::from_reader::<_, BulletDefinitionTable>(file) {
+                Ok(table) => {
+                    // unused slots get zeroed data, same as unknown bullet types
+                    let empty = BulletData {
+                        damage: 0,
+                        life: 0,
+                        lifetime: 0,
+                        flags: BulletFlag(0),
+                        enemy_hit_width: 0,
+                        enemy_hit_height: 0,
+                        block_hit_width: 0,
+                        block_hit_height: 0,
+                        display_bounds: Rect { left: 0, top: 0, right: 0, bottom: 0 },
+                    };
+
+                    for (id, def) in table.bullets {
+                        let data = BulletData {
+                            damage: def.damage,
+                            life: def.life,
+                            lifetime: def.lifetime,
+                            flags: BulletFlag(def.flags),
+                            enemy_hit_width: def.enemy_hit.0,
+                            enemy_hit_height: def.enemy_hit.1,
+                            block_hit_width: def.block_hit.0,
+                            block_hit_height: def.block_hit.1,
+                            display_bounds: Rect {
+                                left: def.display_bounds.0,
+                                top: def.display_bounds.1,
+                                right: def.display_bounds.2,
+                                bottom: def.display_bounds.3,
+                            },
+                        };
+
+                        let id = id as usize;
+                        if id >= self.weapon.bullet_table.len() {
+                            self.weapon.bullet_table.resize(id + 1, empty);
+                        }
+                        self.weapon.bullet_table[id] = data;
+
+                        if !def.frames.is_empty() {
+                            let frames = def.frames.iter().map(|&(l, t, r, b)| Rect::new(l, t, r, b)).collect();
+                            self.weapon.custom_bullets.insert(
+                                id as u16,
+                                CustomBulletData { speed: def.speed, frames, anim_speed: def.anim_speed },
+                            );
+                        }
+
+                        if let Some(falloff) = def.falloff {
+                            self.weapon.damage_falloff.insert(id as u16, falloff);
+                        }
+                    }
+
+                    log::info!("Loaded bullets.json.");
+                }
+                Err(err) => log::warn!("Failed to deserialize bullet definitions: {}", err),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads arms_level.tbl from CS+ files,
+    /// even though it matches vanilla 1:1, we should load it for completeness
+    /// or if any crazy person uses it for a CS+ mod...
+    pub fn load_csplus_tables(&mut self, ctx: &mut Context) -> GameResult {
         if let Ok(mut file) = filesystem::open_find(ctx, &self.base_paths, "arms_level.tbl") {
             let mut data = Vec::new();
             file.read_to_end(&mut data)?;
@@ -2023,3 +2377,62 @@ impl EngineConstants {
         Ok(())
     }
 }
+
+#[test]
+fn test_bullet_definitions_reset() {
+    use crate::framework::filesystem::TempDataDir;
+
+    let dir = TempDataDir::new("bullets");
+    dir.write(
+        "bullets.json",
+        r#"{"bullets": {"4": {"damage": 99, "life": 1, "lifetime": 10, "enemy_hit": [1, 1], "block_hit": [1, 1], "display_bounds": [1, 1, 1, 1]}}}"#,
+    );
+
+    let mut ctx = Context::new();
+    dir.mount(&mut ctx);
+    let mut constants = EngineConstants::defaults();
+    constants.base_paths = vec!["/".to_owned()];
+    let default_damage = constants.weapon.bullet_table[4].damage;
+
+    constants.load_bullet_definitions(&mut ctx).unwrap();
+    assert_eq!(constants.weapon.bullet_table[4].damage, 99);
+
+    // switching to data without the override brings back the original bullet
+    std::fs::remove_file(dir.root.join("bullets.json")).unwrap();
+    constants.load_bullet_definitions(&mut ctx).unwrap();
+    assert_eq!(constants.weapon.bullet_table[4].damage, default_damage);
+}
+
+#[test]
+fn test_constant_overrides() {
+    use crate::framework::filesystem::TempDataDir;
+
+    let dir = TempDataDir::new("constants");
+    dir.write(
+        "constants.json",
+        r#"{"player": {"max_life": 10, "air_physics": {"jump": 1536, "max_dash": -5}}, "booster": {"fuel": 100}}"#,
+    );
+
+    let mut ctx = Context::new();
+    dir.mount(&mut ctx);
+    let mut constants = EngineConstants::defaults();
+    constants.base_paths = vec!["/".to_owned()];
+    let defaults = EngineConstants::defaults();
+
+    constants.apply_constant_json_files(&mut ctx).unwrap();
+    assert_eq!(constants.player.max_life, 10);
+    assert_eq!(constants.player.life, defaults.player.life);
+    assert_eq!(constants.player.air_physics.jump, 1536);
+    assert_eq!(constants.booster.fuel, 100);
+
+    // fields left out or out of range keep their defaults
+    assert_eq!(constants.player.air_physics.max_dash, defaults.player.air_physics.max_dash);
+    assert_eq!(constants.player.air_physics.gravity_air, defaults.player.air_physics.gravity_air);
+    assert_eq!(constants.player.water_physics.jump, defaults.player.water_physics.jump);
+
+    // a malformed file is only warned about and drops the previous overrides
+    dir.write("constants.json", r#"{"player": {"max_life": "#);
+    constants.apply_constant_json_files(&mut ctx).unwrap();
+    assert_eq!(constants.player.max_life, defaults.player.max_life);
+    assert_eq!(constants.booster.fuel, defaults.booster.fuel);
+}
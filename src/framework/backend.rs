@@ -4,8 +4,8 @@ use imgui::DrawData;
 
 use crate::common::{Color, Rect};
 use crate::framework::context::Context;
-use crate::framework::error::GameResult;
-use crate::framework::graphics::{BlendMode, VSyncMode};
+use crate::framework::error::{GameError, GameResult};
+use crate::framework::graphics::{BlendMode, FilterMode, VSyncMode};
 use crate::game::Game;
 
 #[repr(C)]
@@ -45,10 +45,20 @@ pub trait BackendRenderer {
         Ok(())
     }
 
+    /// Reads back the contents of the last drawn frame as RGBA pixels, top row first.
+    fn read_screen_pixels(&mut self) -> GameResult<(u32, u32, Vec<u8>)> {
+        Err(GameError::RenderError("Reading back the screen is not supported by this renderer.".to_owned()))
+    }
+
     fn prepare_draw(&mut self, _width: f32, _height: f32) -> GameResult {
         Ok(())
     }
 
+    /// Shifts everything drawn to the screen by given amount of pixels, render targets are not affected.
+    fn set_draw_offset(&mut self, _x: f32, _y: f32) -> GameResult {
+        Ok(())
+    }
+
     fn create_texture_mutable(&mut self, width: u16, height: u16) -> GameResult<Box<dyn BackendTexture>>;
 
     fn create_texture(&mut self, width: u16, height: u16, data: &[u8]) -> GameResult<Box<dyn BackendTexture>>;
@@ -92,6 +102,9 @@ pub trait BackendTexture {
 
     fn draw(&mut self) -> GameResult;
 
+    /// Sets how the texture is sampled when drawn at a size different from its own.
+    fn set_filter(&mut self, _filter: FilterMode) {}
+
     fn as_any(&self) -> &dyn Any;
 }
 
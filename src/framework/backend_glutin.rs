@@ -1,23 +1,26 @@
 use std::cell::{RefCell, UnsafeCell};
 use std::ffi::c_void;
-use std::mem;
 use std::rc::Rc;
 use std::sync::Arc;
 
 use glutin::{Api, ContextBuilder, GlProfile, GlRequest, PossiblyCurrent, WindowedContext};
-use glutin::event::{ElementState, Event, TouchPhase, VirtualKeyCode, WindowEvent};
+use glutin::event::{ElementState, Event, MouseButton, TouchPhase, VirtualKeyCode, WindowEvent};
 use glutin::event_loop::{ControlFlow, EventLoop};
 use glutin::window::WindowBuilder;
 use imgui::{DrawCmdParams, DrawData, DrawIdx, DrawVert};
 
 use crate::{Game, GAME_SUSPENDED};
 use crate::common::Rect;
-use crate::framework::backend::{Backend, BackendEventLoop, BackendRenderer, BackendTexture, SpriteBatchCommand};
+use crate::framework::backend::{
+    Backend, BackendEventLoop, BackendGamepad, BackendRenderer, BackendTexture, SpriteBatchCommand,
+};
 use crate::framework::context::Context;
 use crate::framework::error::GameResult;
+use crate::framework::gamepad::{Axis, Button};
 use crate::framework::gl;
 use crate::framework::keyboard::ScanCode;
 use crate::framework::render_opengl::{GLContext, OpenGLRenderer};
+use crate::game::shared_game_state::{SharedGameState, WindowMode};
 use crate::input::touch_controls::TouchPoint;
 
 pub struct GlutinBackend;
@@ -29,7 +32,7 @@ impl GlutinBackend {
 }
 
 impl Backend for GlutinBackend {
-    fn create_event_loop(&self, _ctx: &Context) -> GameResult<Box<dyn BackendEventLoop>> {
+    fn create_event_loop(&self, ctx: &Context) -> GameResult<Box<dyn BackendEventLoop>> {
         #[cfg(target_os = "android")]
         loop {
             match ndk_glue::native_window().as_ref() {
@@ -41,12 +44,20 @@ impl Backend for GlutinBackend {
             }
         }
 
-        Ok(Box::new(GlutinEventLoop { refs: Rc::new(UnsafeCell::new(None)) }))
+        if ctx.no_opengl {
+            log::warn!("--no-opengl is not supported by the glutin backend, it can only render with OpenGL.");
+        }
+
+        Ok(Box::new(GlutinEventLoop {
+            refs: Rc::new(UnsafeCell::new(None)),
+            gl_version: ctx.gl_version.unwrap_or((3, 0)),
+        }))
     }
 }
 
 pub struct GlutinEventLoop {
     refs: Rc<UnsafeCell<Option<WindowedContext<PossiblyCurrent>>>>,
+    gl_version: (u8, u8),
 }
 
 impl GlutinEventLoop {
@@ -56,7 +67,7 @@ impl GlutinEventLoop {
         if refs.is_none() {
             let mut window = WindowBuilder::new();
             let windowed_context = ContextBuilder::new();
-            let windowed_context = windowed_context.with_gl(GlRequest::Specific(Api::OpenGl, (3, 0)));
+            let windowed_context = windowed_context.with_gl(GlRequest::Specific(Api::OpenGl, self.gl_version));
             #[cfg(target_os = "android")]
                 let windowed_context = windowed_context.with_gl(GlRequest::Specific(Api::OpenGlEs, (2, 0)));
 
@@ -64,6 +75,7 @@ impl GlutinEventLoop {
                 .with_gl_profile(GlProfile::Core)
                 .with_gl_debug_flag(false)
                 .with_pixel_format(24, 8)
+                .with_multisampling(0)
                 .with_vsync(true);
 
             #[cfg(target_os = "windows")]
@@ -150,10 +162,30 @@ impl BackendEventLoop for GlutinEventLoop {
             state_ref.handle_resize(ctx).unwrap();
         }
 
+        // winit doesn't handle gamepads, so they're polled with gilrs before every update
+        let mut gilrs = match gilrs::Gilrs::new() {
+            Ok(gilrs) => {
+                for (id, gamepad) in gilrs.gamepads() {
+                    add_gilrs_gamepad(state_ref, ctx, id, gamepad.name());
+                }
+
+                Some(gilrs)
+            }
+            Err(err) => {
+                log::warn!("Failed to initialize gamepad support: {}", err);
+                None
+            }
+        };
+
+        // the window is always created windowed, settings are applied on the first update
+        let mut window_mode = WindowMode::Windowed;
+
         // it won't ever return
         let (game, ctx): (&'static mut Game, &'static mut Context) =
             unsafe { (std::mem::transmute(game), std::mem::transmute(ctx)) };
 
+        let mut cursor_position = (0.0, 0.0);
+
         event_loop.run(move |event, _, control_flow| {
             *control_flow = ControlFlow::Wait;
 
@@ -161,7 +193,7 @@ impl BackendEventLoop for GlutinEventLoop {
                 Event::WindowEvent { event: WindowEvent::CloseRequested, window_id }
                 if window_id == window.window().id() =>
                     {
-                        state_ref.shutdown();
+                        state_ref.request_quit();
                     }
                 Event::Resumed => {
                     {
@@ -179,6 +211,7 @@ impl BackendEventLoop for GlutinEventLoop {
                     }
 
                     state_ref.sound_manager.resume();
+                    game.resync_timing();
                 }
                 Event::Suspended => {
                     {
@@ -193,6 +226,25 @@ impl BackendEventLoop for GlutinEventLoop {
 
                     state_ref.sound_manager.pause();
                 }
+                // android reports focus changes around suspend/resume as well, those are handled above
+                #[cfg(not(target_os = "android"))]
+                Event::WindowEvent { event: WindowEvent::Focused(focused), window_id }
+                if window_id == window.window().id() =>
+                    {
+                        if state_ref.settings.pause_on_focus_loss {
+                            {
+                                let mut mutex = GAME_SUSPENDED.lock().unwrap();
+                                *mutex = !focused;
+                            }
+
+                            if focused {
+                                state_ref.sound_manager.resume();
+                                game.resync_timing();
+                            } else {
+                                state_ref.sound_manager.pause();
+                            }
+                        }
+                    }
                 Event::WindowEvent { event: WindowEvent::Resized(size), window_id }
                 if window_id == window.window().id() =>
                     {
@@ -209,10 +261,8 @@ impl BackendEventLoop for GlutinEventLoop {
                 Event::WindowEvent { event: WindowEvent::Touch(touch), window_id }
                 if window_id == window.window().id() =>
                     {
+                        let (loc_x, loc_y) = state_ref.window_to_canvas(ctx, touch.location.x, touch.location.y);
                         let mut controls = &mut state_ref.touch_controls;
-                        let scale = state_ref.scale as f64;
-                        let loc_x = (touch.location.x * ctx.screen_size.0 as f64 / ctx.real_screen_size.0 as f64) / scale;
-                        let loc_y = (touch.location.y * ctx.screen_size.1 as f64 / ctx.real_screen_size.1 as f64) / scale;
 
                         match touch.phase {
                             TouchPhase::Started | TouchPhase::Moved => {
@@ -241,6 +291,26 @@ impl BackendEventLoop for GlutinEventLoop {
                             }
                         }
                     }
+                Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, window_id }
+                if window_id == window.window().id() =>
+                    {
+                        cursor_position = (position.x, position.y);
+                    }
+                Event::WindowEvent { event: WindowEvent::MouseInput { state, button: MouseButton::Left, .. }, window_id }
+                if window_id == window.window().id() =>
+                    {
+                        // clicks on the debugger windows shouldn't go through to the menus below
+                        let captured = ctx
+                            .renderer
+                            .as_ref()
+                            .and_then(|renderer| renderer.imgui().ok())
+                            .map_or(false, |imgui| imgui.io().want_capture_mouse);
+
+                        if state == ElementState::Pressed && !captured {
+                            let position = state_ref.window_to_canvas(ctx, cursor_position.0, cursor_position.1);
+                            state_ref.touch_controls.mouse_press(position);
+                        }
+                    }
                 Event::WindowEvent { event: WindowEvent::KeyboardInput { input, .. }, window_id }
                 if window_id == window.window().id() =>
                     {
@@ -252,6 +322,14 @@ impl BackendEventLoop for GlutinEventLoop {
                                 };
 
                                 ctx.keyboard_context.set_key(drs_scan, key_state);
+
+                                let alt = ctx.keyboard_context.active_mods().alt();
+                                if key_state && alt && drs_scan == ScanCode::Return {
+                                    state_ref.settings.window_mode = match state_ref.settings.window_mode {
+                                        WindowMode::Windowed => WindowMode::Fullscreen,
+                                        WindowMode::Fullscreen => WindowMode::Windowed,
+                                    };
+                                }
                             }
                         }
                     }
@@ -289,6 +367,18 @@ impl BackendEventLoop for GlutinEventLoop {
                         }
                     }
 
+                    if state_ref.settings.window_mode != window_mode {
+                        window_mode = state_ref.settings.window_mode;
+
+                        // the resulting resize event takes care of updating the canvas
+                        window.window().set_fullscreen(window_mode.get_glutin_fullscreen_type());
+                        window.window().set_cursor_visible(window_mode.should_display_mouse_cursor());
+                    }
+
+                    if let Some(gilrs) = &mut gilrs {
+                        poll_gamepads(gilrs, state_ref, ctx);
+                    }
+
                     game.update(ctx).unwrap();
 
                     #[cfg(target_os = "android")]
@@ -307,12 +397,8 @@ impl BackendEventLoop for GlutinEventLoop {
                             }
                         }
 
-                    if state_ref.next_scene.is_some() {
-                        mem::swap(&mut game.scene, &mut state_ref.next_scene);
-                        state_ref.next_scene = None;
-                        game.scene.as_mut().unwrap().init(state_ref, ctx).unwrap();
-                        game.loops = 0;
-                        state_ref.frame_time = 0.0;
+                    if state_ref.should_switch_scene() {
+                        game.switch_scene(ctx).unwrap();
                     }
                 }
                 _ => (),
@@ -365,6 +451,118 @@ impl BackendEventLoop for GlutinEventLoop {
     }
 }
 
+struct GilrsGamepad {
+    id: gilrs::GamepadId,
+}
+
+impl GilrsGamepad {
+    pub fn new(id: gilrs::GamepadId) -> Box<dyn BackendGamepad> {
+        Box::new(GilrsGamepad { id })
+    }
+}
+
+impl BackendGamepad for GilrsGamepad {
+    /// Rumble isn't supported on this backend. gilrs force feedback effects have to be built
+    /// against the `Gilrs` instance owned by the event loop, which gamepads don't have access to.
+    fn set_rumble(&mut self, _low_freq: u16, _high_freq: u16, _duration_ms: u32) -> GameResult {
+        Ok(())
+    }
+
+    fn instance_id(&self) -> u32 {
+        usize::from(self.id) as u32
+    }
+}
+
+fn add_gilrs_gamepad(state: &SharedGameState, ctx: &mut Context, gamepad_id: gilrs::GamepadId, name: &str) {
+    let id = usize::from(gamepad_id) as u32;
+    log::info!("Connected gamepad: {} (ID: {})", name, id);
+
+    let axis_sensitivity = state.settings.get_gamepad_axis_sensitivity(id);
+    ctx.gamepad_context.add_gamepad(GilrsGamepad::new(gamepad_id), axis_sensitivity);
+}
+
+fn poll_gamepads(gilrs: &mut gilrs::Gilrs, state: &SharedGameState, ctx: &mut Context) {
+    use gilrs::EventType;
+
+    while let Some(gilrs::Event { id: gamepad_id, event, .. }) = gilrs.next_event() {
+        let id = usize::from(gamepad_id) as u32;
+
+        match event {
+            EventType::Connected => {
+                let name = gilrs.gamepad(gamepad_id).name().to_owned();
+                add_gilrs_gamepad(state, ctx, gamepad_id, &name);
+            }
+            EventType::Disconnected => {
+                log::info!("Disconnected gamepad with ID {}", id);
+                ctx.gamepad_context.remove_gamepad(id);
+            }
+            EventType::ButtonPressed(button, _) => {
+                if let Some(drs_button) = conv_gamepad_button(button) {
+                    ctx.gamepad_context.set_button(id, drs_button, true);
+                }
+            }
+            EventType::ButtonReleased(button, _) => {
+                if let Some(drs_button) = conv_gamepad_button(button) {
+                    ctx.gamepad_context.set_button(id, drs_button, false);
+                }
+            }
+            // analog triggers are reported as buttons with a value
+            EventType::ButtonChanged(gilrs::Button::LeftTrigger2, value, _) => {
+                ctx.gamepad_context.set_axis_value(id, Axis::TriggerLeft, value as f64);
+                ctx.gamepad_context.update_axes(id);
+            }
+            EventType::ButtonChanged(gilrs::Button::RightTrigger2, value, _) => {
+                ctx.gamepad_context.set_axis_value(id, Axis::TriggerRight, value as f64);
+                ctx.gamepad_context.update_axes(id);
+            }
+            EventType::AxisChanged(axis, value, _) => {
+                if let Some(drs_axis) = conv_gamepad_axis(axis) {
+                    // gilrs has the Y axis pointing up, the rest of the engine expects SDL's convention
+                    let value = match drs_axis {
+                        Axis::LeftY | Axis::RightY => -value,
+                        _ => value,
+                    };
+
+                    ctx.gamepad_context.set_axis_value(id, drs_axis, value as f64);
+                    ctx.gamepad_context.update_axes(id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn conv_gamepad_button(code: gilrs::Button) -> Option<Button> {
+    match code {
+        gilrs::Button::South => Some(Button::South),
+        gilrs::Button::East => Some(Button::East),
+        gilrs::Button::West => Some(Button::West),
+        gilrs::Button::North => Some(Button::North),
+        gilrs::Button::Select => Some(Button::Back),
+        gilrs::Button::Mode => Some(Button::Guide),
+        gilrs::Button::Start => Some(Button::Start),
+        gilrs::Button::LeftThumb => Some(Button::LeftStick),
+        gilrs::Button::RightThumb => Some(Button::RightStick),
+        gilrs::Button::LeftTrigger => Some(Button::LeftShoulder),
+        gilrs::Button::RightTrigger => Some(Button::RightShoulder),
+        gilrs::Button::DPadUp => Some(Button::DPadUp),
+        gilrs::Button::DPadDown => Some(Button::DPadDown),
+        gilrs::Button::DPadLeft => Some(Button::DPadLeft),
+        gilrs::Button::DPadRight => Some(Button::DPadRight),
+        _ => None,
+    }
+}
+
+fn conv_gamepad_axis(code: gilrs::Axis) -> Option<Axis> {
+    match code {
+        gilrs::Axis::LeftStickX => Some(Axis::LeftX),
+        gilrs::Axis::LeftStickY => Some(Axis::LeftY),
+        gilrs::Axis::RightStickX => Some(Axis::RightX),
+        gilrs::Axis::RightStickY => Some(Axis::RightY),
+        _ => None,
+    }
+}
+
 fn conv_keycode(code: VirtualKeyCode) -> Option<ScanCode> {
     match code {
         VirtualKeyCode::Key1 => Some(ScanCode::Key1),
@@ -389,12 +389,8 @@ impl BackendEventLoop for HorizonEventLoop {
                 break;
             }
 
-            if state_ref.next_scene.is_some() {
-                mem::swap(&mut game.scene, &mut state_ref.next_scene);
-                state_ref.next_scene = None;
-                game.scene.as_mut().unwrap().init(state_ref, ctx).unwrap();
-                game.loops = 0;
-                state_ref.frame_time = 0.0;
+            if state_ref.should_switch_scene() {
+                game.switch_scene(ctx).unwrap();
             }
 
             game.draw(ctx).unwrap();
@@ -836,6 +832,8 @@ pub struct Deko3DRenderer {
     fb_width: u32,
     fb_height: u32,
     slot: i32,
+    draw_offset: (f32, f32),
+    screen_target: bool,
 
     imgui: UnsafeCell<imgui::Context>,
 }
@@ -943,9 +941,22 @@ impl Deko3DRenderer {
             fb_width,
             fb_height,
             slot: 0,
+            draw_offset: (0.0, 0.0),
+            screen_target: true,
             imgui: UnsafeCell::new(imgui),
         }))
     }
+
+    fn screen_mtx(&self, width: f32, height: f32) -> [[f32; 4]; 4] {
+        let (offset_x, offset_y) = self.draw_offset;
+
+        [
+            [2.0 / width, 0.0, 0.0, 0.0],
+            [0.0, 2.0 / -height, 0.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0],
+            [-1.0 + 2.0 * offset_x / width, 1.0 - 2.0 * offset_y / height, 0.0, 1.0],
+        ]
+    }
 }
 
 impl BackendRenderer for Deko3DRenderer {
@@ -990,12 +1001,18 @@ impl BackendRenderer for Deko3DRenderer {
         cmdbuf.bind_depth_stencil_state(&DepthStencilState::new().set_depth_test_enable(false));
         cmdbuf.bind_blend_states(0, &[BlendState::new()]);
 
-        self.curr_mtx = [
-            [2.0 / width, 0.0, 0.0, 0.0],
-            [0.0, 2.0 / -height, 0.0, 0.0],
-            [0.0, 0.0, -1.0, 0.0],
-            [-1.0, 1.0, 0.0, 1.0],
-        ];
+        self.curr_mtx = self.screen_mtx(width, height);
+        self.screen_target = true;
+
+        Ok(())
+    }
+
+    fn set_draw_offset(&mut self, x: f32, y: f32) -> GameResult {
+        self.draw_offset = (x, y);
+
+        if self.screen_target {
+            self.curr_mtx = self.screen_mtx(self.width as f32, self.height as f32);
+        }
 
         Ok(())
     }
@@ -1187,6 +1204,7 @@ impl BackendRenderer for Deko3DRenderer {
 
             self.width = width as u32;
             self.height = height as u32;
+            self.screen_target = false;
             self.curr_mtx = [
                 [2.0 / width, 0.0, 0.0, 0.0],
                 [0.0, 2.0 / -height, 0.0, 0.0],
@@ -1206,12 +1224,8 @@ impl BackendRenderer for Deko3DRenderer {
 
             self.width = self.fb_width;
             self.height = self.fb_height;
-            self.curr_mtx = [
-                [2.0 / width, 0.0, 0.0, 0.0],
-                [0.0, 2.0 / -height, 0.0, 0.0],
-                [0.0, 0.0, -1.0, 0.0],
-                [-1.0, 1.0, 0.0, 1.0],
-            ];
+            self.screen_target = true;
+            self.curr_mtx = self.screen_mtx(width, height);
         }
         Ok(())
     }
@@ -1262,6 +1276,11 @@ impl BackendRenderer for Deko3DRenderer {
             let width = (rect.right - x).min(width as isize);
             let height = (rect.bottom - y).min(height as isize);
 
+            let (x, y) = if self.screen_target {
+                (x + self.draw_offset.0 as isize, y + self.draw_offset.1 as isize)
+            } else {
+                (x, y)
+            };
             let (x, y, width, height) = (x as u32, y as u32, width as u32, height as u32);
 
             cmdbuf.set_scissors(0, &[Scissor { x, y, width, height }]);
@@ -1,6 +1,5 @@
 use std::any::Any;
 use std::cell::RefCell;
-use std::mem;
 
 use imgui::{DrawData, TextureId, Ui};
 
@@ -44,12 +43,8 @@ impl BackendEventLoop for NullEventLoop {
                 break;
             }
 
-            if state_ref.next_scene.is_some() {
-                mem::swap(&mut game.scene, &mut state_ref.next_scene);
-                state_ref.next_scene = None;
-                game.scene.as_mut().unwrap().init(state_ref, ctx).unwrap();
-                game.loops = 0;
-                state_ref.frame_time = 0.0;
+            if state_ref.should_switch_scene() {
+                game.switch_scene(ctx).unwrap();
             }
             std::thread::sleep(std::time::Duration::from_millis(10));
 
@@ -46,6 +46,7 @@ pub struct SDL2Backend {
 impl SDL2Backend {
     pub fn new(size_hint: (u16, u16)) -> GameResult<Box<dyn Backend>> {
         sdl2::hint::set("SDL_JOYSTICK_THREAD", "1");
+        sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "0");
 
         let context = sdl2::init().map_err(GameError::WindowError)?;
 
@@ -160,18 +161,43 @@ impl SDL2EventLoop {
 
         let gl_attr = video.gl_attr();
 
+        let (gl_major, gl_minor) = ctx.gl_version.unwrap_or((2, 1));
         gl_attr.set_context_profile(GLProfile::Compatibility);
-        gl_attr.set_context_version(2, 1);
+        gl_attr.set_context_version(gl_major, gl_minor);
+        // some drivers default to multisampling, which blurs the pixel art
+        gl_attr.set_multisample_buffers(0);
+        gl_attr.set_multisample_samples(0);
+
+        let opengl_available =
+            !ctx.no_opengl && if let Ok(v) = std::env::var("CAVESTORY_NO_OPENGL") { v != "1" } else { true };
 
         let mut window = video.window("Cave Story (doukutsu-rs)", size_hint.0 as _, size_hint.1 as _);
         window.position_centered();
         window.resizable();
 
         #[cfg(feature = "render-opengl")]
-        window.opengl();
+        if opengl_available {
+            window.opengl();
+        }
 
-        let window = window.build().map_err(|e| GameError::WindowError(e.to_string()))?;
-        let opengl_available = if let Ok(v) = std::env::var("CAVESTORY_NO_OPENGL") { v != "1" } else { true };
+        let window = match window.build() {
+            Ok(window) => window,
+            Err(err) => {
+                let message = format!(
+                    "Failed to create the game window: {}\n\n\
+                     Try launching with --no-opengl or requesting another OpenGL version with --gl-version=<major>.<minor>.",
+                    err
+                );
+                let _ = sdl2::messagebox::show_simple_message_box(
+                    sdl2::messagebox::MessageBoxFlag::ERROR,
+                    "doukutsu-rs",
+                    &message,
+                    None,
+                );
+
+                return Err(GameError::WindowError(message));
+            }
+        };
 
         let event_loop = SDL2EventLoop {
             event_pump,
@@ -202,6 +228,7 @@ impl BackendEventLoop for SDL2EventLoop {
 
         {
             let (width, height) = self.refs.deref().borrow().window.window().size();
+            ctx.real_screen_size = (width, height);
             ctx.screen_size = (width.max(1) as f32, height.max(1) as f32);
 
             imgui.io_mut().display_size = [ctx.screen_size.0, ctx.screen_size.1];
@@ -238,7 +265,7 @@ impl BackendEventLoop for SDL2EventLoop {
 
                 match event {
                     Event::Quit { .. } => {
-                        state.shutdown();
+                        state.request_quit();
                     }
                     Event::Window { win_event, .. } => match win_event {
                         WindowEvent::FocusGained | WindowEvent::Shown => {
@@ -249,7 +276,7 @@ impl BackendEventLoop for SDL2EventLoop {
                                 }
 
                                 state.sound_manager.resume();
-                                game.loops = 0;
+                                game.resync_timing();
                             }
                         }
                         WindowEvent::FocusLost | WindowEvent::Hidden => {
@@ -261,6 +288,7 @@ impl BackendEventLoop for SDL2EventLoop {
                             }
                         }
                         WindowEvent::SizeChanged(width, height) => {
+                            ctx.real_screen_size = (width.max(1) as u32, height.max(1) as u32);
                             ctx.screen_size = (width.max(1) as f32, height.max(1) as f32);
 
                             if let Some(renderer) = &ctx.renderer {
@@ -311,6 +339,13 @@ impl BackendEventLoop for SDL2EventLoop {
                             ctx.keyboard_context.set_key(drs_scan, false);
                         }
                     }
+                    Event::MouseButtonDown { mouse_btn: sdl2::mouse::MouseButton::Left, x, y, .. } => {
+                        // clicks on the debugger windows shouldn't go through to the menus below
+                        if !imgui.io().want_capture_mouse {
+                            let position = state.window_to_canvas(ctx, x as f64, y as f64);
+                            state.touch_controls.mouse_press(position);
+                        }
+                    }
                     Event::ControllerDeviceAdded { which, .. } => {
                         let game_controller = &self.refs.borrow().game_controller;
 
@@ -389,11 +424,8 @@ impl BackendEventLoop for SDL2EventLoop {
 
             game.update(ctx).unwrap();
 
-            if let Some(_) = &state.next_scene {
-                game.scene = mem::take(&mut state.next_scene);
-                game.scene.as_mut().unwrap().init(state, ctx).unwrap();
-                game.loops = 0;
-                state.frame_time = 0.0;
+            if state.should_switch_scene() {
+                game.switch_scene(ctx).unwrap();
             }
 
             imgui_sdl2.prepare_frame(
@@ -408,7 +440,7 @@ impl BackendEventLoop for SDL2EventLoop {
 
     fn new_renderer(&self, ctx: *mut Context) -> GameResult<Box<dyn BackendRenderer>> {
         #[cfg(feature = "render-opengl")]
-        {
+        if *self.opengl_available.borrow() {
             let mut refs = self.refs.borrow_mut();
             match refs.window.window().gl_create_context() {
                 Ok(gl_ctx) => {
@@ -519,6 +551,7 @@ struct SDL2Renderer {
     imgui_event: Rc<RefCell<ImguiSdl2>>,
     #[allow(unused)] // the rendering pipeline uses pointers to SDL_Texture, and we manually manage the lifetimes
     imgui_font_tex: SDL2Texture,
+    draw_offset: (i32, i32),
 }
 
 impl SDL2Renderer {
@@ -576,6 +609,7 @@ impl SDL2Renderer {
             imgui: Rc::new(RefCell::new(imgui)),
             imgui_event: Rc::new(RefCell::new(imgui_sdl2)),
             imgui_font_tex,
+            draw_offset: (0, 0),
         }))
     }
 }
@@ -596,6 +630,23 @@ unsafe fn set_raw_target(
     }
 }
 
+// SDL resets the viewport every time the render target changes, so this has to be reapplied after switching back to the screen.
+fn apply_draw_offset(canvas: &mut WindowCanvas, offset: (i32, i32)) -> GameResult {
+    if offset == (0, 0) {
+        canvas.set_viewport(None);
+    } else {
+        let (width, height) = canvas.output_size().map_err(GameError::RenderError)?;
+        canvas.set_viewport(Some(sdl2::rect::Rect::new(
+            offset.0,
+            offset.1,
+            width.saturating_sub(offset.0 as u32),
+            height.saturating_sub(offset.1 as u32),
+        )));
+    }
+
+    Ok(())
+}
+
 fn min3(x: f32, y: f32, z: f32) -> f32 {
     if x < y && x < z {
         x
@@ -648,6 +699,12 @@ impl BackendRenderer for SDL2Renderer {
         Ok(())
     }
 
+    fn set_draw_offset(&mut self, x: f32, y: f32) -> GameResult {
+        self.draw_offset = (x as i32, y as i32);
+
+        apply_draw_offset(self.refs.borrow_mut().window.canvas(), self.draw_offset)
+    }
+
     fn create_texture_mutable(&mut self, width: u16, height: u16) -> GameResult<Box<dyn BackendTexture>> {
         let mut refs = self.refs.borrow_mut();
 
@@ -722,6 +779,10 @@ impl BackendRenderer for SDL2Renderer {
             }
             None => unsafe {
                 set_raw_target(renderer, std::ptr::null_mut())?;
+
+                if self.draw_offset != (0, 0) {
+                    apply_draw_offset(self.refs.borrow_mut().window.canvas(), self.draw_offset)?;
+                }
             },
         }
 
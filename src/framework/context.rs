@@ -1,4 +1,5 @@
 use crate::framework::backend::{BackendRenderer, init_backend};
+use crate::framework::backend_null::NullBackend;
 use crate::framework::error::GameResult;
 use crate::framework::filesystem::Filesystem;
 use crate::framework::gamepad::GamepadContext;
@@ -9,6 +10,10 @@ use crate::game::Game;
 pub struct Context {
     pub headless: bool,
     pub size_hint: (u16, u16),
+    /// OpenGL context version to request instead of the backend default.
+    pub gl_version: Option<(u8, u8)>,
+    /// Skips OpenGL entirely and uses the backend's compatibility renderer.
+    pub no_opengl: bool,
     pub(crate) filesystem: Filesystem,
     pub(crate) renderer: Option<Box<dyn BackendRenderer>>,
     pub(crate) gamepad_context: GamepadContext,
@@ -17,6 +22,8 @@ pub struct Context {
     pub(crate) screen_size: (f32, f32),
     pub(crate) screen_insets: (f32, f32, f32, f32),
     pub(crate) vsync_mode: VSyncMode,
+    /// Number of sprite batches submitted so far, for checking how well drawing gets batched.
+    pub(crate) batch_draws: usize,
 }
 
 impl Context {
@@ -24,6 +31,8 @@ impl Context {
         Context {
             headless: false,
             size_hint: (640, 480),
+            gl_version: None,
+            no_opengl: false,
             filesystem: Filesystem::new(),
             renderer: None,
             gamepad_context: GamepadContext::new(),
@@ -32,6 +41,7 @@ impl Context {
             screen_size: (320.0, 240.0),
             screen_insets: (0.0, 0.0, 0.0, 0.0),
             vsync_mode: VSyncMode::Uncapped,
+            batch_draws: 0,
         }
     }
 
@@ -44,4 +54,13 @@ impl Context {
 
         Ok(())
     }
+
+    /// Sets up the null renderer without starting an event loop, for driving the game by hand.
+    pub(crate) fn init_headless_renderer(&mut self) -> GameResult {
+        let event_loop = NullBackend::new()?.create_event_loop(self)?;
+        self.renderer = Some(event_loop.new_renderer(self as *mut Context)?);
+        self.screen_size = (640.0, 480.0);
+
+        Ok(())
+    }
 }
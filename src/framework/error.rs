@@ -106,6 +106,13 @@ impl From<jni::errors::Error> for GameError {
     }
 }
 
+#[cfg(feature = "zip-archives")]
+impl From<zip::result::ZipError> for GameError {
+    fn from(e: zip::result::ZipError) -> GameError {
+        GameError::FilesystemError(format!("Zip archive error: {}", e))
+    }
+}
+
 impl From<strum::ParseError> for GameError {
     fn from(s: strum::ParseError) -> GameError {
         let errstr = format!("Strum parse error: {}", s);
@@ -117,6 +117,16 @@ impl Filesystem {
         self.user_vfs.rm(path.as_ref())
     }
 
+    /// Renames the specified file in the user dir, replacing the destination if it exists.
+    pub(crate) fn user_rename<P: AsRef<path::Path>, Q: AsRef<path::Path>>(&self, from: P, to: Q) -> GameResult<()> {
+        self.user_vfs.rename(from.as_ref(), to.as_ref())
+    }
+
+    /// Flushes the contents of the specified file in the user dir to the disk.
+    pub(crate) fn user_sync<P: AsRef<path::Path>>(&self, path: P) -> GameResult<()> {
+        self.user_vfs.sync(path.as_ref())
+    }
+
     /// Deletes the specified directory in the user dir,
     /// and all its contents!
     pub(crate) fn user_delete_dir<P: AsRef<path::Path>>(&self, path: P) -> GameResult<()> {
@@ -275,6 +285,16 @@ pub fn user_delete<P: AsRef<path::Path>>(ctx: &Context, path: P) -> GameResult {
     ctx.filesystem.user_delete(path.as_ref())
 }
 
+/// Renames the specified file in the user dir, replacing the destination if it exists.
+pub fn user_rename<P: AsRef<path::Path>, Q: AsRef<path::Path>>(ctx: &Context, from: P, to: Q) -> GameResult {
+    ctx.filesystem.user_rename(from.as_ref(), to.as_ref())
+}
+
+/// Flushes the contents of the specified file in the user dir to the disk.
+pub fn user_sync<P: AsRef<path::Path>>(ctx: &Context, path: P) -> GameResult {
+    ctx.filesystem.user_sync(path.as_ref())
+}
+
 /// Deletes the specified directory in the user dir,
 /// and all its contents!
 pub fn user_delete_dir<P: AsRef<path::Path>>(ctx: &Context, path: P) -> GameResult {
@@ -383,3 +403,45 @@ pub fn mount_vfs(ctx: &mut Context, vfs: Box<dyn vfs::VFS>) {
 pub fn mount_user_vfs(ctx: &mut Context, vfs: Box<dyn vfs::VFS>) {
     ctx.filesystem.mount_user_vfs(vfs)
 }
+
+/// Scratch directory for tests that need game data on disk, removed again once dropped.
+#[cfg(test)]
+pub(crate) struct TempDataDir {
+    pub root: PathBuf,
+}
+
+#[cfg(test)]
+impl TempDataDir {
+    /// Creates an empty directory, `name` keeps tests running in parallel apart.
+    pub(crate) fn new(name: &str) -> TempDataDir {
+        let root = std::env::temp_dir().join(format!("doukutsu-rs-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+
+        TempDataDir { root }
+    }
+
+    /// Writes a file at `path` relative to the directory, creating its parent directories as needed.
+    pub(crate) fn write(&self, path: &str, contents: impl AsRef<[u8]>) {
+        let path = self.root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    /// Mounts the directory as game data, read-only like the regular data directories.
+    pub(crate) fn mount(&self, ctx: &mut Context) {
+        mount(ctx, &self.root, true);
+    }
+
+    /// Mounts the directory as the user directory, where saves and settings get written to.
+    pub(crate) fn mount_user(&self, ctx: &mut Context) {
+        mount_user_vfs(ctx, Box::new(vfs::PhysicalFS::new(&self.root, false)));
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDataDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
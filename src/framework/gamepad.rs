@@ -215,6 +215,13 @@ impl GamepadData {
     }
 
     pub fn set_rumble(&mut self, state: &SharedGameState, low_freq: u16, hi_freq: u16, ticks: u32) -> GameResult {
+        let intensity = state.settings.rumble_intensity.min(100) as u32;
+        if intensity == 0 {
+            return Ok(());
+        }
+
+        let low_freq = (low_freq as u32 * intensity / 100) as u16;
+        let hi_freq = (hi_freq as u32 * intensity / 100) as u16;
         let duration_ms = (ticks as f32 / state.settings.timing_mode.get_tps() as f32 * 1000.0) as u32;
         self.controller.set_rumble(low_freq, hi_freq, duration_ms)
     }
@@ -222,11 +229,13 @@ impl GamepadData {
 
 pub struct GamepadContext {
     gamepads: Vec<GamepadData>,
+    /// Indices of gamepads that were unplugged since the game last checked.
+    disconnected: Vec<u32>,
 }
 
 impl GamepadContext {
     pub(crate) fn new() -> Self {
-        Self { gamepads: Vec::new() }
+        Self { gamepads: Vec::new(), disconnected: Vec::new() }
     }
 
     fn get_gamepad(&self, gamepad_id: u32) -> Option<&GamepadData> {
@@ -250,7 +259,14 @@ impl GamepadContext {
     }
 
     pub(crate) fn remove_gamepad(&mut self, gamepad_id: u32) {
-        self.gamepads.retain(|data| data.controller.instance_id() != gamepad_id);
+        if let Some(index) = self.gamepads.iter().position(|data| data.controller.instance_id() == gamepad_id) {
+            self.gamepads.remove(index);
+            self.disconnected.push(index as u32);
+        }
+    }
+
+    pub(crate) fn take_disconnected(&mut self) -> Vec<u32> {
+        std::mem::take(&mut self.disconnected)
     }
 
     pub(crate) fn set_gamepad_type(&mut self, gamepad_id: u32, controller_type: GamepadType) {
@@ -404,6 +420,11 @@ pub fn remove_gamepad(context: &mut Context, gamepad_id: u32) {
     context.gamepad_context.remove_gamepad(gamepad_id);
 }
 
+/// Returns the indices of gamepads unplugged since the last call, so the game can react to losing a controller.
+pub fn take_disconnected_gamepads(context: &mut Context) -> Vec<u32> {
+    context.gamepad_context.take_disconnected()
+}
+
 pub fn set_gamepad_type(context: &mut Context, gamepad_id: u32, controller_type: GamepadType) {
     context.gamepad_context.set_gamepad_type(gamepad_id, controller_type);
 }
@@ -3,6 +3,7 @@ use crate::framework::backend::{BackendShader, BackendTexture, VertexData};
 use crate::framework::context::Context;
 use crate::framework::error::{GameError, GameResult};
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum FilterMode {
     Nearest,
     Linear,
@@ -51,6 +52,15 @@ pub fn present(ctx: &mut Context) -> GameResult {
     Ok(())
 }
 
+/// Returns (width, height, RGBA pixels) of the frame drawn so far, must be called before `present`.
+pub fn read_screen_pixels(ctx: &mut Context) -> GameResult<(u32, u32, Vec<u8>)> {
+    if let Some(renderer) = &mut ctx.renderer {
+        return renderer.read_screen_pixels();
+    }
+
+    Err(GameError::RenderError("Rendering backend hasn't been initialized yet.".to_string()))
+}
+
 pub fn set_vsync_mode(ctx: &mut Context, mode: VSyncMode) -> GameResult {
     if let Some(renderer) = &mut ctx.renderer {
         ctx.vsync_mode = mode;
@@ -102,6 +112,14 @@ pub fn set_render_target(ctx: &mut Context, texture: Option<&Box<dyn BackendText
     Err(GameError::RenderError("Rendering backend hasn't been initialized yet.".to_string()))
 }
 
+pub fn set_draw_offset(ctx: &mut Context, x: f32, y: f32) -> GameResult {
+    if let Some(renderer) = &mut ctx.renderer {
+        return renderer.set_draw_offset(x, y);
+    }
+
+    Err(GameError::RenderError("Rendering backend hasn't been initialized yet.".to_string()))
+}
+
 pub fn set_blend_mode(ctx: &mut Context, blend: BlendMode) -> GameResult {
     if let Some(renderer) = &mut ctx.renderer {
         return renderer.set_blend_mode(blend);
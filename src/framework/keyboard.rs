@@ -234,6 +234,8 @@ bitfield! {
 pub struct KeyboardContext {
     active_modifiers: KeyMods,
     pressed_keys_set: HashSet<ScanCode>,
+    /// Keys pressed since the last game tick, kept even if they were released in the meantime.
+    tapped_keys_set: HashSet<ScanCode>,
     last_pressed: Option<ScanCode>,
     current_pressed: Option<ScanCode>,
 }
@@ -243,6 +245,7 @@ impl KeyboardContext {
         Self {
             active_modifiers: KeyMods(0),
             pressed_keys_set: HashSet::with_capacity(256),
+            tapped_keys_set: HashSet::with_capacity(16),
             last_pressed: None,
             current_pressed: None,
         }
@@ -251,6 +254,7 @@ impl KeyboardContext {
     pub(crate) fn set_key(&mut self, key: ScanCode, pressed: bool) {
         if pressed {
             let _ = self.pressed_keys_set.insert(key);
+            let _ = self.tapped_keys_set.insert(key);
             self.last_pressed = self.current_pressed;
             self.current_pressed = Some(key);
         } else {
@@ -298,6 +302,14 @@ impl KeyboardContext {
         self.pressed_keys_set.contains(&key)
     }
 
+    pub(crate) fn was_key_pressed(&self, key: ScanCode) -> bool {
+        self.pressed_keys_set.contains(&key) || self.tapped_keys_set.contains(&key)
+    }
+
+    pub(crate) fn clear_tapped_keys(&mut self) {
+        self.tapped_keys_set.clear();
+    }
+
     pub(crate) fn is_key_repeated(&self) -> bool {
         if self.last_pressed.is_some() {
             self.last_pressed == self.current_pressed
@@ -326,6 +338,12 @@ pub fn is_key_pressed(ctx: &Context, key: ScanCode) -> bool {
     ctx.keyboard_context.is_key_pressed(key)
 }
 
+/// Checks if a key is pressed down or has been pressed since the last game tick,
+/// so taps shorter than a tick still register.
+pub fn was_key_pressed(ctx: &Context, key: ScanCode) -> bool {
+    ctx.keyboard_context.was_key_pressed(key)
+}
+
 /// Checks if the last keystroke sent by the system is repeated,
 /// like when a key is held down for a period of time.
 pub fn is_key_repeated(ctx: &Context) -> bool {
@@ -21,3 +21,5 @@ pub mod render_opengl;
 pub mod ui;
 pub mod util;
 pub mod vfs;
+#[cfg(feature = "zip-archives")]
+pub mod zip_fs;
@@ -17,7 +17,7 @@ use crate::framework::error::GameError::RenderError;
 use crate::framework::error::GameResult;
 use crate::framework::gl;
 use crate::framework::gl::types::*;
-use crate::framework::graphics::{BlendMode, VSyncMode};
+use crate::framework::graphics::{BlendMode, FilterMode, VSyncMode};
 use crate::framework::util::{field_offset, return_param};
 use crate::game::GAME_SUSPENDED;
 
@@ -39,6 +39,7 @@ pub struct OpenGLTexture {
     vbo: GLuint,
     vertices: Vec<VertexData>,
     context_active: Arc<RefCell<bool>>,
+    filter: FilterMode,
 }
 
 impl BackendTexture for OpenGLTexture {
@@ -253,6 +254,29 @@ impl BackendTexture for OpenGLTexture {
         }
     }
 
+    fn set_filter(&mut self, filter: FilterMode) {
+        if self.filter == filter || self.texture_id == 0 {
+            return;
+        }
+
+        unsafe {
+            if let Some(gl) = &GL_PROC {
+                let mode = match filter {
+                    FilterMode::Nearest => gl::NEAREST,
+                    FilterMode::Linear => gl::LINEAR,
+                };
+
+                let current_texture_id = return_param(|x| gl.gl.GetIntegerv(gl::TEXTURE_BINDING_2D, x)) as u32;
+                gl.gl.BindTexture(gl::TEXTURE_2D, self.texture_id);
+                gl.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, mode as _);
+                gl.gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, mode as _);
+                gl.gl.BindTexture(gl::TEXTURE_2D, current_texture_id);
+
+                self.filter = filter;
+            }
+        }
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -591,6 +615,8 @@ pub struct OpenGLRenderer {
     context_active: Arc<RefCell<bool>>,
     def_matrix: [[f32; 4]; 4],
     curr_matrix: [[f32; 4]; 4],
+    draw_offset: (f32, f32),
+    screen_target: bool,
 }
 
 impl OpenGLRenderer {
@@ -602,6 +628,8 @@ impl OpenGLRenderer {
             context_active: Arc::new(RefCell::new(true)),
             def_matrix: [[0.0; 4]; 4],
             curr_matrix: [[0.0; 4]; 4],
+            draw_offset: (0.0, 0.0),
+            screen_target: true,
         }
     }
 
@@ -617,6 +645,18 @@ impl OpenGLRenderer {
 
         Some((&mut self.refs, gl))
     }
+
+    fn screen_matrix(&self) -> [[f32; 4]; 4] {
+        let (width, height) = (self.render_data.last_size.0 as f32, self.render_data.last_size.1 as f32);
+        let (offset_x, offset_y) = self.draw_offset;
+
+        [
+            [2.0 / width, 0.0, 0.0, 0.0],
+            [0.0, 2.0 / -height, 0.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0],
+            [-1.0 + 2.0 * offset_x / width, 1.0 - 2.0 * offset_y / height, 0.0, 1.0],
+        ]
+    }
 }
 
 impl BackendRenderer for OpenGLRenderer {
@@ -713,6 +753,46 @@ impl BackendRenderer for OpenGLRenderer {
         Ok(())
     }
 
+    fn read_screen_pixels(&mut self) -> GameResult<(u32, u32, Vec<u8>)> {
+        let (width, height) = self.render_data.last_size;
+        if width == 0 || height == 0 {
+            return Err(RenderError("Nothing has been drawn yet.".to_string()));
+        }
+
+        if let Some((_, gl)) = self.get_context() {
+            let mut pixels = vec![0u8; width as usize * height as usize * 4];
+
+            unsafe {
+                gl.gl.BindFramebuffer(gl::FRAMEBUFFER, self.render_data.surf_framebuffer);
+                gl.gl.ReadPixels(
+                    0,
+                    0,
+                    width as _,
+                    height as _,
+                    gl::RGBA,
+                    gl::UNSIGNED_BYTE,
+                    pixels.as_mut_ptr() as _,
+                );
+            }
+
+            // GL returns the bottom row first
+            let stride = width as usize * 4;
+            let mut flipped = Vec::with_capacity(pixels.len());
+            for row in pixels.chunks_exact(stride).rev() {
+                flipped.extend_from_slice(row);
+            }
+
+            // the surface is cleared to transparent black, make the screenshot opaque
+            for alpha in flipped.iter_mut().skip(3).step_by(4) {
+                *alpha = 255;
+            }
+
+            Ok((width, height, flipped))
+        } else {
+            Err(RenderError("No OpenGL context available!".to_string()))
+        }
+    }
+
     fn prepare_draw(&mut self, width: f32, height: f32) -> GameResult {
         if let Some((_, gl)) = self.get_context() {
             unsafe {
@@ -747,13 +827,9 @@ impl BackendRenderer for OpenGLRenderer {
 
                 gl.gl.Viewport(0, 0, width_u as _, height_u as _);
 
-                self.def_matrix = [
-                    [2.0 / width, 0.0, 0.0, 0.0],
-                    [0.0, 2.0 / -height, 0.0, 0.0],
-                    [0.0, 0.0, -1.0, 0.0],
-                    [-1.0, 1.0, 0.0, 1.0],
-                ];
+                self.def_matrix = self.screen_matrix();
                 self.curr_matrix = self.def_matrix;
+                self.screen_target = true;
 
                 gl.gl.BindBuffer(gl::ARRAY_BUFFER, 0);
                 gl.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
@@ -788,6 +864,45 @@ impl BackendRenderer for OpenGLRenderer {
         }
     }
 
+    fn set_draw_offset(&mut self, x: f32, y: f32) -> GameResult {
+        if let Some((_, gl)) = self.get_context() {
+            self.draw_offset = (x, y);
+            self.def_matrix = self.screen_matrix();
+
+            if self.screen_target {
+                self.curr_matrix = self.def_matrix;
+
+                unsafe {
+                    gl.gl.UseProgram(self.render_data.fill_shader.program_id);
+                    gl.gl.UniformMatrix4fv(
+                        self.render_data.fill_shader.proj_mtx,
+                        1,
+                        gl::FALSE,
+                        self.curr_matrix.as_ptr() as _,
+                    );
+                    gl.gl.UseProgram(self.render_data.fill_water_shader.program_id);
+                    gl.gl.UniformMatrix4fv(
+                        self.render_data.fill_water_shader.proj_mtx,
+                        1,
+                        gl::FALSE,
+                        self.curr_matrix.as_ptr() as _,
+                    );
+                    gl.gl.UseProgram(self.render_data.tex_shader.program_id);
+                    gl.gl.UniformMatrix4fv(
+                        self.render_data.tex_shader.proj_mtx,
+                        1,
+                        gl::FALSE,
+                        self.curr_matrix.as_ptr() as _,
+                    );
+                }
+            }
+
+            Ok(())
+        } else {
+            Err(RenderError("No OpenGL context available!".to_string()))
+        }
+    }
+
     fn create_texture_mutable(&mut self, width: u16, height: u16) -> GameResult<Box<dyn BackendTexture>> {
         if let Some((_, gl)) = self.get_context() {
             unsafe {
@@ -835,6 +950,7 @@ impl BackendRenderer for OpenGLRenderer {
                     shader: self.render_data.tex_shader,
                     vbo: self.render_data.vbo,
                     context_active: self.context_active.clone(),
+                    filter: FilterMode::Nearest,
                 }))
             }
         } else {
@@ -874,6 +990,7 @@ impl BackendRenderer for OpenGLRenderer {
                     shader: self.render_data.tex_shader,
                     vbo: self.render_data.vbo,
                     context_active: self.context_active.clone(),
+                    filter: FilterMode::Nearest,
                 }))
             }
         } else {
@@ -951,8 +1068,10 @@ impl BackendRenderer for OpenGLRenderer {
 
                     gl.gl.BindFramebuffer(gl::FRAMEBUFFER, gl_texture.framebuffer_id);
                     gl.gl.Viewport(0, 0, gl_texture.width as _, gl_texture.height as _);
+                    self.screen_target = false;
                 } else {
                     self.curr_matrix = self.def_matrix;
+                    self.screen_target = true;
 
                     gl.gl.UseProgram(self.render_data.fill_shader.program_id);
                     gl.gl.UniformMatrix4fv(
@@ -1035,10 +1154,16 @@ impl BackendRenderer for OpenGLRenderer {
         if let Some((_, gl)) = self.get_context() {
             unsafe {
                 if let Some(rect) = &rect {
+                    let (offset_x, offset_y) = if self.screen_target {
+                        (self.draw_offset.0 as GLint, self.draw_offset.1 as GLint)
+                    } else {
+                        (0, 0)
+                    };
+
                     gl.gl.Enable(gl::SCISSOR_TEST);
                     gl.gl.Scissor(
-                        rect.left as GLint,
-                        self.render_data.last_size.1 as GLint - rect.bottom as GLint,
+                        rect.left as GLint + offset_x,
+                        self.render_data.last_size.1 as GLint - rect.bottom as GLint - offset_y,
                         rect.width() as GLint,
                         rect.height() as GLint,
                     );
@@ -9,12 +9,13 @@
 //! as a trait object, and its path abstraction is not the most
 //! convenient.
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::ffi::OsStr;
 use std::fmt::{self, Debug};
 use std::fs;
 use std::io::{Read, Seek, Write};
 use std::path::{self, Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use crate::framework::error::{GameError, GameResult};
 
@@ -121,6 +122,17 @@ pub trait VFS: Debug {
     /// Remove a file or directory and all its contents
     fn rmrf(&self, path: &Path) -> GameResult;
 
+    /// Rename a file, replacing the destination if it exists.
+    fn rename(&self, from: &Path, _to: &Path) -> GameResult {
+        Err(GameError::FilesystemError(format!("Tried to rename file {:?} but FS is read-only", from)))
+    }
+
+    /// Flushes the contents of a file to the disk, like `File::sync_all`. Does nothing on filesystems that
+    /// aren't backed by one.
+    fn sync(&self, _path: &Path) -> GameResult {
+        Ok(())
+    }
+
     /// Check if the file exists
     fn exists(&self, path: &Path) -> bool;
 
@@ -157,6 +169,8 @@ pub struct PhysicalFS {
     root: PathBuf,
     readonly: bool,
     lowercase: bool,
+    /// Maps requested paths to the paths which matched them case-insensitively.
+    resolved: Arc<Mutex<HashMap<PathBuf, PathBuf>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -215,11 +229,16 @@ fn sanitize_path(path: &path::Path) -> Option<PathBuf> {
 impl PhysicalFS {
     /// Creates a new PhysicalFS
     pub fn new(root: &Path, readonly: bool) -> Self {
-        PhysicalFS { root: root.into(), readonly, lowercase: false }
+        PhysicalFS { root: root.into(), readonly, lowercase: false, resolved: Arc::new(Mutex::new(HashMap::new())) }
     }
 
     pub fn new_lowercase(root: &Path) -> Self {
-        PhysicalFS { root: root.into(), readonly: true, lowercase: true }
+        PhysicalFS {
+            root: root.into(),
+            readonly: true,
+            lowercase: true,
+            resolved: Arc::new(Mutex::new(HashMap::new())),
+        }
     }
 
     /// Takes a given path (&str) and returns
@@ -229,7 +248,7 @@ impl PhysicalFS {
     fn to_absolute(&self, p: &Path) -> GameResult<PathBuf> {
         if let Some(mut safe_path) = sanitize_path(p) {
             if self.lowercase {
-                safe_path = PathBuf::from(p.to_string_lossy().to_lowercase())
+                safe_path = PathBuf::from(safe_path.to_string_lossy().to_lowercase())
             }
 
             let mut root_path = self.root.clone();
@@ -238,41 +257,8 @@ impl PhysicalFS {
             // emulate case insensitive paths on systems with case sensitive filesystems.
             #[cfg(not(any(target_os = "windows", target_os = "macos")))]
             if !root_path.exists() {
-                let mut root_path2 = self.root.clone();
-                let mut ok = true;
-
-                let components: Vec<&OsStr> = safe_path
-                    .components()
-                    .filter_map(|c| if let Component::Normal(s) = c { Some(s) } else { None })
-                    .collect();
-
-                'citer: for node in components {
-                    let mut tmp = root_path2.clone();
-                    tmp.push(node);
-                    if tmp.exists() {
-                        root_path2 = tmp;
-                        continue;
-                    }
-
-                    if let Ok(entries) = root_path2.read_dir() {
-                        for entry in entries.flatten() {
-                            let name = entry.file_name();
-                            if name.to_ascii_lowercase() != node.to_ascii_lowercase() {
-                                continue;
-                            }
-
-                            root_path2.push(name);
-                            continue 'citer;
-                        }
-                    }
-
-                    ok = false;
-                    break;
-                }
-
-                if ok {
-                    // log::info!("resolved case insensitive path {:?} -> {:?}", root_path, root_path2);
-                    root_path = root_path2;
+                if let Some(resolved) = self.resolve_case_insensitive(&safe_path) {
+                    root_path = resolved;
                 }
             }
 
@@ -287,6 +273,54 @@ impl PhysicalFS {
         }
     }
 
+    /// Looks up a relative path component by component, ignoring case. Successful lookups are cached,
+    /// as walking the directories is quite slow compared to a regular `exists()` check.
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn resolve_case_insensitive(&self, safe_path: &Path) -> Option<PathBuf> {
+        if let Ok(resolved) = self.resolved.lock() {
+            if let Some(path) = resolved.get(safe_path) {
+                // the file could've been removed or renamed since then
+                if path.exists() {
+                    return Some(path.clone());
+                }
+            }
+        }
+
+        let mut root_path = self.root.clone();
+
+        let components: Vec<&OsStr> =
+            safe_path.components().filter_map(|c| if let Component::Normal(s) = c { Some(s) } else { None }).collect();
+
+        'citer: for node in components {
+            let mut tmp = root_path.clone();
+            tmp.push(node);
+            if tmp.exists() {
+                root_path = tmp;
+                continue;
+            }
+
+            if let Ok(entries) = root_path.read_dir() {
+                for entry in entries.flatten() {
+                    let name = entry.file_name();
+                    if name.to_ascii_lowercase() != node.to_ascii_lowercase() {
+                        continue;
+                    }
+
+                    root_path.push(name);
+                    continue 'citer;
+                }
+            }
+
+            return None;
+        }
+
+        if let Ok(mut resolved) = self.resolved.lock() {
+            resolved.insert(safe_path.to_path_buf(), root_path.clone());
+        }
+
+        Some(root_path)
+    }
+
     /// Creates the PhysicalFS's root directory if necessary.
     /// Idempotent.
     /// This way we can not create the directory until it's
@@ -369,6 +403,29 @@ impl VFS for PhysicalFS {
         }
     }
 
+    /// Rename a file, replacing the destination if it exists
+    fn rename(&self, from: &Path, to: &Path) -> GameResult {
+        if self.readonly {
+            return Err(GameError::FilesystemError(format!("Tried to rename file {:?} but FS is read-only", from)));
+        }
+
+        self.create_root()?;
+        let from = self.to_absolute(from)?;
+        let to = self.to_absolute(to)?;
+        fs::rename(from, to).map_err(GameError::from)
+    }
+
+    /// Flushes the contents of a file to the disk
+    fn sync(&self, path: &Path) -> GameResult {
+        if self.readonly {
+            return Err(GameError::FilesystemError(format!("Tried to sync file {:?} but FS is read-only", path)));
+        }
+
+        let p = self.to_absolute(path)?;
+        // some platforms only allow flushing files opened for writing
+        fs::OpenOptions::new().write(true).open(p)?.sync_all().map_err(GameError::from)
+    }
+
     /// Check if the file exists
     fn exists(&self, path: &Path) -> bool {
         match self.to_absolute(path) {
@@ -497,6 +554,26 @@ impl VFS for OverlayFS {
         Err(GameError::FilesystemError(format!("Could not remove file/dir {:?}", path)))
     }
 
+    /// Rename a file in the first filesystem containing it
+    fn rename(&self, from: &Path, to: &Path) -> GameResult {
+        for vfs in &self.roots {
+            if vfs.exists(from) {
+                return vfs.rename(from, to);
+            }
+        }
+        Err(GameError::FilesystemError(format!("Could not rename file {:?}", from)))
+    }
+
+    /// Flushes a file in the first filesystem containing it
+    fn sync(&self, path: &Path) -> GameResult {
+        for vfs in &self.roots {
+            if vfs.exists(path) {
+                return vfs.sync(path);
+            }
+        }
+        Err(GameError::FilesystemError(format!("Could not sync file {:?}", path)))
+    }
+
     /// Check if the file exists
     fn exists(&self, path: &Path) -> bool {
         for vfs in &self.roots {
@@ -671,6 +748,16 @@ mod tests {
             }
         }
 
+        {
+            // Test sync() and rename()
+            fs.sync(f1).unwrap();
+            let f2 = Path::new("/testdir/file2.txt");
+            fs.rename(f1, f2).unwrap();
+            assert!(!fs.exists(f1));
+            assert!(fs.exists(f2));
+            fs.rename(f2, f1).unwrap();
+        }
+
         {
             assert!(fs.exists(f1));
             fs.rm(f1).unwrap();
@@ -681,5 +768,41 @@ mod tests {
         assert!(!fs.exists(testdir));
     }
 
+    #[test]
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn headless_test_case_insensitive() {
+        let cargo_path = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let fs = PhysicalFS::new(cargo_path, false);
+        let testdir = Path::new("/testdir_case");
+
+        if fs.exists(testdir) {
+            fs.rmrf(testdir).unwrap();
+        }
+
+        fs.mkdir(Path::new("/testdir_case/Stage")).unwrap();
+        {
+            let mut f = fs.create(Path::new("/testdir_case/Stage/Head.tsc")).unwrap();
+            let _ = f.write(b"#0000").unwrap();
+        }
+
+        assert!(fs.exists(Path::new("/testdir_case/stage/head.tsc")));
+        assert!(fs.exists(Path::new("/TESTDIR_CASE/STAGE/HEAD.TSC")));
+        assert!(!fs.exists(Path::new("/testdir_case/stage/arms.tsc")));
+
+        {
+            let mut buf = Vec::new();
+            let mut f = fs.open(Path::new("/testdir_case/stage/head.tsc")).unwrap();
+            let _ = f.read_to_end(&mut buf).unwrap();
+            assert_eq!(&buf[..], b"#0000");
+        }
+
+        // cached resolutions must not outlive the file they point to
+        fs.rm(Path::new("/testdir_case/Stage/Head.tsc")).unwrap();
+        assert!(!fs.exists(Path::new("/testdir_case/stage/head.tsc")));
+
+        fs.rmrf(testdir).unwrap();
+        assert!(!fs.exists(testdir));
+    }
+
     // BUGGO: TODO: Make sure all functions are tested for OverlayFS and ZipFS!!
 }
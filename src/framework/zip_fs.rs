@@ -0,0 +1,306 @@
+//! Read-only VFS serving the contents of a `.zip` archive, so mods and data packs don't have to be extracted.
+
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::fs;
+use std::io::{self, Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use zip::ZipArchive;
+
+use crate::framework::error::GameError::FilesystemError;
+use crate::framework::error::GameResult;
+use crate::framework::vfs::{OpenOptions, VFile, VMetadata, VFS};
+
+/// Entries up to this size are kept decompressed after being opened once,
+/// scripts get reopened a lot during dialogue and inflating them every time adds up.
+const CACHE_MAX_ENTRY_SIZE: u64 = 1024 * 1024;
+
+#[derive(Debug)]
+pub struct ZipEntryFile(Cursor<Arc<[u8]>>);
+
+impl io::Read for ZipEntryFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl io::Seek for ZipEntryFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
+
+impl io::Write for ZipEntryFile {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(ErrorKind::PermissionDenied, "Zip archives are read-only."))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Err(io::Error::new(ErrorKind::PermissionDenied, "Zip archives are read-only."))
+    }
+}
+
+struct ZipMetadata {
+    is_dir: bool,
+    size: u64,
+}
+
+impl VMetadata for ZipMetadata {
+    fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+
+    fn is_file(&self) -> bool {
+        !self.is_dir
+    }
+
+    fn len(&self) -> u64 {
+        self.size
+    }
+}
+
+pub struct ZipFS {
+    path: PathBuf,
+    archive: Mutex<ZipArchive<fs::File>>,
+    /// Lowercase entry path (without the leading slash) -> (index in the archive, uncompressed size).
+    files: HashMap<String, (usize, u64)>,
+    /// Lowercase directory path -> names of its children in their original case.
+    dirs: HashMap<String, Vec<String>>,
+    cache: Mutex<HashMap<usize, Arc<[u8]>>>,
+}
+
+impl ZipFS {
+    /// Opens the archive and indexes its entries. Lookups ignore case, since original data mixes
+    /// `Stage/` and `stage/`. If every entry is inside a top level `data/` directory, it's used as the root.
+    pub fn new(path: &Path) -> GameResult<ZipFS> {
+        let file = fs::File::open(path)?;
+        let mut archive = ZipArchive::new(file)?;
+
+        let mut entries = Vec::with_capacity(archive.len());
+        for i in 0..archive.len() {
+            let entry = archive.by_index_raw(i)?;
+            entries.push((i, entry.name().replace('\\', "/"), entry.is_dir(), entry.size()));
+        }
+
+        let strip_data_dir = entries
+            .iter()
+            .filter(|(_, _, is_dir, _)| !is_dir)
+            .all(|(_, name, _, _)| name.get(..5).map_or(false, |prefix| prefix.eq_ignore_ascii_case("data/")));
+
+        let mut files = HashMap::new();
+        let mut dirs: HashMap<String, Vec<String>> = HashMap::new();
+        dirs.insert(String::new(), Vec::new());
+
+        for (index, name, is_dir, size) in entries {
+            let name = match name.get(5..) {
+                Some(stripped) if strip_data_dir && name[..5].eq_ignore_ascii_case("data/") => stripped,
+                _ if strip_data_dir => continue,
+                _ => name.as_str(),
+            };
+            let components: Vec<&str> = name.split('/').filter(|c| !c.is_empty()).collect();
+
+            if components.is_empty() {
+                continue;
+            }
+
+            let mut parent = String::new();
+            for (i, component) in components.iter().enumerate() {
+                let children = dirs.entry(parent.clone()).or_default();
+                if !children.iter().any(|c| c.eq_ignore_ascii_case(component)) {
+                    children.push(component.to_string());
+                }
+
+                if !parent.is_empty() {
+                    parent.push('/');
+                }
+                parent.push_str(&component.to_ascii_lowercase());
+
+                if i + 1 < components.len() || is_dir {
+                    dirs.entry(parent.clone()).or_default();
+                }
+            }
+
+            if !is_dir {
+                files.insert(parent, (index, size));
+            }
+        }
+
+        log::info!("Mounted {:?} with {} files.", path, files.len());
+
+        Ok(ZipFS {
+            path: path.to_path_buf(),
+            archive: Mutex::new(archive),
+            files,
+            dirs,
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn normalize(path: &Path) -> GameResult<String> {
+        let mut iter = path.components();
+
+        if !matches!(iter.next(), Some(Component::RootDir)) {
+            return Err(FilesystemError("Path must be absolute.".to_string()));
+        }
+
+        let mut normalized = String::new();
+        for component in iter {
+            match component {
+                Component::Normal(name) => {
+                    if !normalized.is_empty() {
+                        normalized.push('/');
+                    }
+                    normalized.push_str(&name.to_string_lossy().to_ascii_lowercase());
+                }
+                Component::CurDir => {}
+                _ => return Err(FilesystemError(format!("Invalid path: {:?}", path))),
+            }
+        }
+
+        Ok(normalized)
+    }
+
+    fn read_entry(&self, index: usize, size: u64) -> GameResult<Arc<[u8]>> {
+        if let Some(data) = self.cache.lock()?.get(&index) {
+            return Ok(data.clone());
+        }
+
+        let mut archive = self.archive.lock()?;
+        let mut entry = archive.by_index(index)?;
+        let mut buf = Vec::with_capacity(size as usize);
+        entry.read_to_end(&mut buf)?;
+
+        let data: Arc<[u8]> = buf.into();
+        if size <= CACHE_MAX_ENTRY_SIZE {
+            self.cache.lock()?.insert(index, data.clone());
+        }
+
+        Ok(data)
+    }
+}
+
+impl Debug for ZipFS {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "<ZipFS: {:?}>", self.path)
+    }
+}
+
+impl VFS for ZipFS {
+    fn open_options(&self, path: &Path, open_options: OpenOptions) -> GameResult<Box<dyn VFile>> {
+        if open_options.write || open_options.create || open_options.append || open_options.truncate {
+            let msg = format!("Cannot alter file {:?} in root {:?}, filesystem read-only", path, self);
+            return Err(FilesystemError(msg));
+        }
+
+        let normalized = ZipFS::normalize(path)?;
+        match self.files.get(&normalized) {
+            Some(&(index, size)) => Ok(Box::new(ZipEntryFile(Cursor::new(self.read_entry(index, size)?)))),
+            None if self.dirs.contains_key(&normalized) => Err(FilesystemError(format!("{:?} is a directory.", path))),
+            None => Err(FilesystemError("File not found.".to_string())),
+        }
+    }
+
+    fn mkdir(&self, _path: &Path) -> GameResult<()> {
+        Err(FilesystemError("Tried to make directory {} but FS is read-only".to_string()))
+    }
+
+    fn rm(&self, _path: &Path) -> GameResult<()> {
+        Err(FilesystemError("Tried to remove file {} but FS is read-only".to_string()))
+    }
+
+    fn rmrf(&self, _path: &Path) -> GameResult<()> {
+        Err(FilesystemError("Tried to remove file/dir {} but FS is read-only".to_string()))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        match ZipFS::normalize(path) {
+            Ok(normalized) => self.files.contains_key(&normalized) || self.dirs.contains_key(&normalized),
+            Err(_) => false,
+        }
+    }
+
+    fn metadata(&self, path: &Path) -> GameResult<Box<dyn VMetadata>> {
+        let normalized = ZipFS::normalize(path)?;
+
+        if let Some(&(_, size)) = self.files.get(&normalized) {
+            Ok(Box::new(ZipMetadata { is_dir: false, size }))
+        } else if self.dirs.contains_key(&normalized) {
+            Ok(Box::new(ZipMetadata { is_dir: true, size: 0 }))
+        } else {
+            Err(FilesystemError("File not found.".to_string()))
+        }
+    }
+
+    fn read_dir(&self, path: &Path) -> GameResult<Box<dyn Iterator<Item = GameResult<PathBuf>>>> {
+        let normalized = ZipFS::normalize(path)?;
+
+        match self.dirs.get(&normalized) {
+            Some(children) => {
+                // same as PhysicalFS, return paths relative to the VFS root
+                let entries: Vec<GameResult<PathBuf>> = children.iter().map(|name| Ok(path.join(name))).collect();
+                Ok(Box::new(entries.into_iter()))
+            }
+            None if self.files.contains_key(&normalized) => {
+                Err(FilesystemError(format!("Expected a directory, found a file: {:?}", path)))
+            }
+            None => Err(FilesystemError("File not found.".to_string())),
+        }
+    }
+
+    fn to_path_buf(&self) -> Option<PathBuf> {
+        None
+    }
+}
+
+#[test]
+fn test_zip_fs() {
+    use std::io::Write;
+
+    use zip::write::FileOptions;
+    use zip::CompressionMethod;
+
+    use crate::framework::filesystem::TempDataDir;
+
+    let dir = TempDataDir::new("zipfs");
+    let path = dir.root.join("data.zip");
+    {
+        let mut writer = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        writer.start_file("data/Stage/Cave.tsc", options).unwrap();
+        writer.write_all(b"#0100\r\n<END").unwrap();
+        writer.start_file("data/stage/Cave.pxm", options).unwrap();
+        writer.write_all(b"PXM").unwrap();
+        writer.start_file("data/stage.tbl", options).unwrap();
+        writer.write_all(&[0; 16]).unwrap();
+        writer.finish().unwrap();
+    }
+
+    let zip_fs = ZipFS::new(&path).unwrap();
+
+    // the data/ directory is the root and lookups ignore case
+    assert!(zip_fs.exists(Path::new("/stage.tbl")));
+    assert!(zip_fs.exists(Path::new("/Stage")));
+    assert!(zip_fs.exists(Path::new("/STAGE/cave.PXM")));
+    assert!(!zip_fs.exists(Path::new("/data/stage.tbl")));
+    assert!(zip_fs.metadata(Path::new("/stage")).unwrap().is_dir());
+    assert_eq!(zip_fs.metadata(Path::new("/stage.tbl")).unwrap().len(), 16);
+
+    // both spellings of the directory end up merged, keeping the case of the first one
+    let mut children: Vec<PathBuf> = zip_fs.read_dir(Path::new("/Stage")).unwrap().map(|p| p.unwrap()).collect();
+    children.sort();
+    assert_eq!(children, vec![PathBuf::from("/Stage/Cave.pxm"), PathBuf::from("/Stage/Cave.tsc")]);
+
+    // reopening a file during dialogue is served from the cache
+    for _ in 0..2 {
+        let mut buf = Vec::new();
+        zip_fs.open(Path::new("/stage/cave.tsc")).unwrap().read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"#0100\r\n<END");
+    }
+    assert_eq!(zip_fs.cache.lock().unwrap().len(), 1);
+
+    assert!(zip_fs.create(Path::new("/Stage/Cave.tsc")).is_err());
+    assert!(zip_fs.open(Path::new("/Stage")).is_err());
+    assert!(zip_fs.open(Path::new("/Stage/Missing.tsc")).is_err());
+}
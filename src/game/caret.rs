@@ -1,6 +1,6 @@
 use crate::common::{CDEG_RAD, Condition, Direction, Rect};
 use crate::engine_constants::EngineConstants;
-use crate::util::rng::RNG;
+use crate::util::rng::{XorShift, RNG};
 
 #[derive(Debug, EnumIter, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum CaretType {
@@ -407,3 +407,192 @@ impl Caret {
         !self.cond.alive()
     }
 }
+
+/// Fixed size storage for carets. Slots of dead carets get reused and once every slot is taken,
+/// the oldest caret is replaced, so effect heavy scenes (explosions, boss deaths) don't allocate.
+///
+/// Carets are iterated (and drawn) in the order they were spawned in, regardless of the slot they ended up in.
+pub struct CaretPool {
+    slots: Vec<Caret>,
+    capacity: usize,
+    /// Whether the caret in each slot is alive, cleared once a dead caret has been noticed in `tick`.
+    used: Vec<bool>,
+    free: Vec<usize>,
+    /// Indices of used slots, oldest caret first.
+    order: Vec<usize>,
+}
+
+impl CaretPool {
+    pub fn with_capacity(capacity: usize) -> CaretPool {
+        CaretPool {
+            slots: Vec::with_capacity(capacity),
+            capacity,
+            used: Vec::with_capacity(capacity),
+            free: Vec::with_capacity(capacity),
+            order: Vec::with_capacity(capacity),
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of alive carets.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, caret: Caret) {
+        let index = if let Some(index) = self.free.pop() {
+            index
+        } else if self.slots.len() < self.capacity {
+            self.slots.push(caret);
+            self.used.push(true);
+            self.order.push(self.slots.len() - 1);
+            return;
+        } else if !self.order.is_empty() {
+            self.order.remove(0)
+        } else {
+            return;
+        };
+
+        self.slots[index] = caret;
+        self.used[index] = true;
+        self.order.push(index);
+    }
+
+    pub fn tick(&mut self, rng: &dyn RNG, constants: &EngineConstants) {
+        for &index in self.order.iter() {
+            let caret = &mut self.slots[index];
+            if !caret.is_dead() {
+                caret.tick(rng, constants);
+            }
+
+            // also catches carets killed from outside, since the last tick
+            if caret.is_dead() {
+                self.used[index] = false;
+                self.free.push(index);
+            }
+        }
+
+        let used = &self.used;
+        self.order.retain(|&index| used[index]);
+    }
+
+    /// Iterates over alive carets, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Caret> {
+        self.order.iter().map(|&index| &self.slots[index]).filter(|caret| !caret.is_dead())
+    }
+
+    /// Iterates over alive carets in slot order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Caret> {
+        self.slots.iter_mut().filter(|caret| !caret.is_dead())
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.used.clear();
+        self.free.clear();
+        self.order.clear();
+    }
+}
+
+/// Spawns and ticks carets the way an effect heavy scene would, going from a few carets per frame to dozens during
+/// a boss death, either in a `CaretPool` or in a `Vec` cleaned up with `retain` like before the pool existed.
+/// Used by `tests/caret_allocations.rs`, which counts the heap allocations of every frame.
+#[doc(hidden)]
+pub struct CaretWorkload {
+    constants: EngineConstants,
+    rng: XorShift,
+    pool: Option<CaretPool>,
+    vec: Vec<Caret>,
+}
+
+impl CaretWorkload {
+    pub fn new(pooled: bool) -> CaretWorkload {
+        CaretWorkload {
+            constants: EngineConstants::defaults(),
+            rng: XorShift::new(0),
+            pool: if pooled { Some(CaretPool::with_capacity(64)) } else { None },
+            vec: Vec::with_capacity(32),
+        }
+    }
+
+    pub fn run_frame(&mut self, frame: i32) {
+        let spawned = if (300..360).contains(&frame) { 40 } else { 3 };
+
+        for i in 0..spawned {
+            let caret = Caret::new(frame * 40 + i, 0, CaretType::Shoot, Direction::Left, &self.constants);
+            match &mut self.pool {
+                Some(pool) => pool.push(caret),
+                None => self.vec.push(caret),
+            }
+        }
+
+        match &mut self.pool {
+            Some(pool) => pool.tick(&self.rng, &self.constants),
+            None => {
+                for caret in self.vec.iter_mut() {
+                    caret.tick(&self.rng, &self.constants);
+                }
+                self.vec.retain(|caret| !caret.is_dead());
+            }
+        }
+    }
+
+    /// Number of carets alive after the last frame.
+    pub fn alive(&self) -> usize {
+        match &self.pool {
+            Some(pool) => pool.len(),
+            None => self.vec.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::rng::XorShift;
+
+    fn caret(x: i32, constants: &EngineConstants) -> Caret {
+        Caret::new(x, 0, CaretType::Shoot, Direction::Left, constants)
+    }
+
+    #[test]
+    fn test_pool_reuses_dead_slots() {
+        let constants = EngineConstants::defaults();
+        let rng = XorShift::new(0);
+        let mut pool = CaretPool::with_capacity(4);
+
+        pool.push(caret(0, &constants));
+        pool.push(caret(1, &constants));
+        pool.iter_mut().next().unwrap().cond.set_alive(false);
+        pool.tick(&rng, &constants);
+        assert_eq!(pool.len(), 1);
+
+        pool.push(caret(2, &constants));
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.slots.len(), 2);
+        // the new caret took the first slot, but still comes after the older one
+        assert_eq!(pool.iter().map(|c| c.x).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_pool_recycles_oldest() {
+        let constants = EngineConstants::defaults();
+        let mut pool = CaretPool::with_capacity(3);
+
+        for x in 0..5 {
+            pool.push(caret(x, &constants));
+        }
+
+        assert_eq!(pool.iter().map(|c| c.x).collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+}
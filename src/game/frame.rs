@@ -11,6 +11,11 @@ pub enum UpdateTarget {
     Boss(u16),
 }
 
+/// Ticks up/down has to be held before the camera starts shifting.
+const LOOK_DELAY: u16 = 20;
+/// Maximum vertical camera shift caused by looking up/down.
+const LOOK_MAX_OFFSET: i32 = 0x6000;
+
 pub struct Frame {
     pub x: i32,
     pub y: i32,
@@ -20,6 +25,8 @@ pub struct Frame {
     pub target_x: i32,
     pub target_y: i32,
     pub wait: i32,
+    pub look_offset: i32,
+    look_counter: u16,
 }
 
 impl Frame {
@@ -33,6 +40,8 @@ impl Frame {
             target_x: 0,
             target_y: 0,
             wait: 16,
+            look_offset: 0,
+            look_counter: 0,
         }
     }
 
@@ -47,6 +56,28 @@ impl Frame {
         (x, y)
     }
 
+    /// Eases the extra look offset towards the held direction (-1 up, 1 down, 0 released).
+    pub fn tick_look(&mut self, direction: i32) {
+        let target = if direction != 0 {
+            if self.look_counter < LOOK_DELAY {
+                self.look_counter += 1;
+                0
+            } else {
+                direction.signum() * LOOK_MAX_OFFSET
+            }
+        } else {
+            self.look_counter = 0;
+            0
+        };
+
+        let delta = (target - self.look_offset) / 8;
+        if delta == 0 {
+            self.look_offset = target;
+        } else {
+            self.look_offset += delta;
+        }
+    }
+
     pub fn immediate_update(&mut self, state: &mut SharedGameState, stage: &Stage) {
         let mut screen_width = state.canvas_size.0;
         if state.constants.is_switch && stage.map.width <= 54 {
@@ -101,6 +132,9 @@ impl Frame {
         }
 
         let tile_size = state.tile_size.as_int();
+        // scroll bounds of axes that don't fit on screen, quake offsets are clamped to them
+        let mut scroll_max_x = None;
+        let mut scroll_max_y = None;
 
         if (stage.map.width as usize).saturating_sub(1) * (tile_size as usize) < screen_width as usize {
             self.x = -(((screen_width as i32 - (stage.map.width as i32 - 1) * tile_size) * 0x200) / 2);
@@ -115,12 +149,15 @@ impl Frame {
             if self.x > max_x {
                 self.x = max_x;
             }
+
+            scroll_max_x = Some(max_x);
         }
 
         if (stage.map.height as usize).saturating_sub(1) * (tile_size as usize) < state.canvas_size.1 as usize {
             self.y = -(((state.canvas_size.1 as i32 - (stage.map.height as i32 - 1) * tile_size) * 0x200) / 2);
         } else {
-            self.y += (self.target_y - (state.canvas_size.1 as i32 * 0x200 / 2) - self.y) / self.wait;
+            let target_y = self.target_y + self.look_offset;
+            self.y += (target_y - (state.canvas_size.1 as i32 * 0x200 / 2) - self.y) / self.wait;
 
             if self.y < 0 {
                 self.y = 0;
@@ -130,6 +167,8 @@ impl Frame {
             if self.y > max_y {
                 self.y = max_y;
             }
+
+            scroll_max_y = Some(max_y);
         }
 
         let intensity = state.settings.screen_shake_intensity.to_val();
@@ -153,5 +192,46 @@ impl Frame {
             self.x += (f64::from(new_x) * intensity).round() as i32;
             self.y += (f64::from(new_y) * intensity).round() as i32;
         }
+
+        // don't let the shake reveal the area outside of the stage
+        if let Some(max_x) = scroll_max_x {
+            self.x = self.x.clamp(0, max_x);
+        }
+
+        if let Some(max_y) = scroll_max_y {
+            self.y = self.y.clamp(0, max_y);
+        }
+    }
+}
+
+#[test]
+fn test_look_offset() {
+    let mut frame = Frame::new();
+
+    // nothing happens until up has been held for a while
+    for _ in 0..LOOK_DELAY {
+        frame.tick_look(-1);
+        assert_eq!(frame.look_offset, 0);
+    }
+
+    // then the camera eases up to the maximum shift
+    let mut last = frame.look_offset;
+    for _ in 0..100 {
+        frame.tick_look(-1);
+        assert!(frame.look_offset <= last);
+        last = frame.look_offset;
+    }
+    assert_eq!(frame.look_offset, -LOOK_MAX_OFFSET);
+
+    // and eases back down on release instead of snapping
+    frame.tick_look(0);
+    assert!(frame.look_offset > -LOOK_MAX_OFFSET && frame.look_offset < 0);
+
+    let mut last = frame.look_offset;
+    for _ in 0..100 {
+        frame.tick_look(0);
+        assert!(frame.look_offset >= last);
+        last = frame.look_offset;
     }
+    assert_eq!(frame.look_offset, 0);
 }
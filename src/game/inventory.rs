@@ -6,6 +6,11 @@ use crate::game::shared_game_state::SharedGameState;
 use crate::game::weapon::{Weapon, WeaponLevel, WeaponType};
 use crate::game::weapon::bullet::BulletManager;
 
+/// Number of weapon slots, same as in the original engine. Profiles can't store more than that either.
+const MAX_WEAPONS: usize = 8;
+/// Number of item slots, same as in the original engine. Profiles can't store more than that either.
+const MAX_ITEMS: usize = 32;
+
 #[derive(Clone, Copy)]
 /// (id, amount)
 pub struct Item(pub u16, pub u16);
@@ -13,7 +18,7 @@ pub struct Item(pub u16, pub u16);
 #[derive(Clone)]
 pub struct Inventory {
     pub current_item: u16,
-    pub current_weapon: u16,
+    current_weapon: u16,
     items: Vec<Item>,
     weapons: Vec<Weapon>,
 }
@@ -37,7 +42,9 @@ impl Inventory {
 
     pub fn add_item(&mut self, item_id: u16) {
         if !self.has_item(item_id) {
-            self.items.push(Item(item_id, 1));
+            if self.items.len() < MAX_ITEMS {
+                self.items.push(Item(item_id, 1));
+            }
         } else if let Some(item) = self.get_item(item_id) {
             item.1 += 1;
         }
@@ -45,7 +52,9 @@ impl Inventory {
 
     pub fn add_item_amount(&mut self, item_id: u16, amount: u16) {
         if !self.has_item(item_id) {
-            self.items.push(Item(item_id, amount));
+            if self.items.len() < MAX_ITEMS {
+                self.items.push(Item(item_id, amount));
+            }
         } else if let Some(item) = self.get_item(item_id) {
             item.1 += amount;
         }
@@ -110,9 +119,9 @@ impl Inventory {
     pub fn add_weapon(&mut self, weapon_id: WeaponType, ammo: u16) {
         let w = self.get_weapon_by_type_mut(weapon_id);
         if let Some(w) = w {
-            w.ammo += ammo;
-            w.max_ammo += ammo;
-        } else {
+            w.max_ammo = w.max_ammo.saturating_add(ammo);
+            w.ammo = w.ammo.saturating_add(ammo).min(w.max_ammo);
+        } else if self.weapons.len() < MAX_WEAPONS {
             self.weapons.push(Weapon::new(
                 weapon_id,
                 WeaponLevel::Level1,
@@ -156,19 +165,34 @@ impl Inventory {
         self.weapons.get_mut(self.current_weapon as usize)
     }
 
+    fn reset_spur_charge(&mut self) {
+        if let Some(weapon) = self.get_current_weapon_mut() {
+            if weapon.wtype == WeaponType::Spur {
+                weapon.reset_charge();
+            }
+        }
+    }
+
+    /// Switches to the weapon in given slot. Every weapon change goes through here, so a Spur charge
+    /// never carries over to the next time it's selected.
+    pub fn select_weapon(&mut self, idx: u16) {
+        self.reset_spur_charge();
+        self.current_weapon = idx;
+    }
+
     pub fn next_weapon(&mut self) {
         if (1 + self.current_weapon as usize) < self.weapons.len() {
-            self.current_weapon += 1;
+            self.select_weapon(self.current_weapon + 1);
         } else {
-            self.current_weapon = 0;
+            self.select_weapon(0);
         }
     }
 
     pub fn prev_weapon(&mut self) {
         if self.current_weapon as usize > 0 {
-            self.current_weapon -= 1;
+            self.select_weapon(self.current_weapon - 1);
         } else {
-            self.current_weapon = self.weapons.len().saturating_sub(1) as u16;
+            self.select_weapon(self.weapons.len().saturating_sub(1) as u16);
         }
     }
 
@@ -303,3 +327,72 @@ fn inventory_test() {
     assert!(inventory.has_item_amount(4, Ordering::Equal, 4));
     assert!(!inventory.has_item_amount(4, Ordering::Less, 2));
 }
+
+#[test]
+fn item_limit_test() {
+    let mut inventory = Inventory::new();
+
+    for item_id in 1..40 {
+        inventory.add_item(item_id);
+    }
+    inventory.add_item_amount(40, 5);
+
+    assert_eq!(inventory.items.len(), MAX_ITEMS);
+    assert!(inventory.has_item(32));
+    assert!(!inventory.has_item(33));
+    assert!(!inventory.has_item(40));
+
+    // items already held still stack when the inventory is full
+    inventory.add_item(1);
+    inventory.add_item_amount(2, 3);
+    assert!(inventory.has_item_amount(1, Ordering::Equal, 2));
+    assert!(inventory.has_item_amount(2, Ordering::Equal, 4));
+}
+
+#[test]
+fn weapon_test() {
+    let mut inventory = Inventory::new();
+
+    inventory.add_weapon(WeaponType::MissileLauncher, 5);
+    inventory.add_weapon(WeaponType::MissileLauncher, 5);
+    let launcher = inventory.get_weapon(0).unwrap();
+    assert_eq!((launcher.ammo, launcher.max_ammo), (10, 10));
+
+    for wtype in [
+        WeaponType::Snake,
+        WeaponType::PolarStar,
+        WeaponType::Fireball,
+        WeaponType::MachineGun,
+        WeaponType::Bubbler,
+        WeaponType::Blade,
+        WeaponType::Nemesis,
+        WeaponType::Spur,
+    ] {
+        inventory.add_weapon(wtype, 0);
+    }
+
+    assert_eq!(inventory.get_weapon_count(), MAX_WEAPONS);
+    assert!(!inventory.has_weapon(WeaponType::Spur));
+}
+
+#[test]
+fn spur_charge_reset_test() {
+    let mut inventory = Inventory::new();
+    inventory.add_weapon(WeaponType::PolarStar, 0);
+    inventory.add_weapon(WeaponType::Spur, 0);
+
+    inventory.select_weapon(1);
+    let spur = inventory.get_current_weapon_mut().unwrap();
+    spur.level = WeaponLevel::Level3;
+    spur.experience = 10;
+
+    // switching away drops the charge, other weapons keep their level
+    inventory.select_weapon(0);
+    let spur = inventory.get_weapon(1).unwrap();
+    assert_eq!((spur.level, spur.experience), (WeaponLevel::Level1, 0));
+
+    inventory.get_current_weapon_mut().unwrap().level = WeaponLevel::Level2;
+    inventory.next_weapon();
+    assert_eq!(inventory.get_current_weapon_idx(), 1);
+    assert_eq!(inventory.get_weapon(0).unwrap().level, WeaponLevel::Level2);
+}
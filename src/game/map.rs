@@ -5,11 +5,12 @@ use std::sync::Arc;
 
 use byteorder::{LE, ReadBytesExt};
 
-use crate::common::{Color, Rect};
+use crate::common::{Color, Direction, Rect};
 use crate::framework::context::Context;
 use crate::framework::error::{GameError, GameResult};
 use crate::framework::error::GameError::ResourceLoadError;
 use crate::framework::filesystem;
+use crate::game::caret::CaretType;
 use crate::game::shared_game_state::TileSize;
 use crate::game::stage::{PxPackScroll, PxPackStageData, StageData};
 use crate::util::encoding::read_cur_shift_jis;
@@ -625,3 +626,79 @@ impl WaterParams {
         self.entries.get(&tile).unwrap_or(&DEFAULT_ENTRY)
     }
 }
+
+#[derive(Clone, Copy)]
+pub struct AmbientEmitterEntry {
+    pub caret_type: CaretType,
+    pub direction: Direction,
+    /// Delay between spawned carets, in ticks.
+    pub interval: u16,
+}
+
+pub struct AmbientEmitterParams {
+    pub entries: HashMap<u8, AmbientEmitterEntry>,
+}
+
+impl AmbientEmitterParams {
+    pub fn new() -> AmbientEmitterParams {
+        AmbientEmitterParams { entries: HashMap::new() }
+    }
+
+    /// Loads emitter definitions, one per line in `tile_min:tile_max:caret_type:direction:interval` format.
+    pub fn load_from<R: io::Read>(&mut self, data: R) -> GameResult {
+        fn next_num<'a, T: std::str::FromStr>(s: &mut impl Iterator<Item = &'a str>, error_msg: &str) -> GameResult<T> {
+            match s.next() {
+                None => Err(GameError::ParseError("Out of range.".to_string())),
+                Some(v) => v.trim().parse::<T>().map_err(|_| GameError::ParseError(error_msg.to_string())),
+            }
+        }
+
+        for line in BufReader::new(data).lines() {
+            match line {
+                Ok(line) => {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+
+                    let mut splits = line.split(':');
+
+                    if splits.clone().count() != 5 {
+                        return Err(GameError::ParseError("Invalid count of delimiters.".to_string()));
+                    }
+
+                    let tile_min: u8 = next_num(&mut splits, "Invalid minimum tile value.")?;
+                    let tile_max: u8 = next_num(&mut splits, "Invalid maximum tile value.")?;
+
+                    if tile_min > tile_max {
+                        return Err(GameError::ParseError("tile_min > tile_max".to_string()));
+                    }
+
+                    let caret_type = CaretType::from_int(next_num(&mut splits, "Invalid caret type.")?)
+                        .ok_or_else(|| GameError::ParseError("Unknown caret type.".to_string()))?;
+                    let direction = Direction::from_int(next_num(&mut splits, "Invalid direction.")?)
+                        .ok_or_else(|| GameError::ParseError("Unknown direction.".to_string()))?;
+                    let interval: u16 = next_num(&mut splits, "Invalid interval value.")?;
+
+                    let entry = AmbientEmitterEntry { caret_type, direction, interval: interval.max(1) };
+
+                    for i in tile_min..=tile_max {
+                        let e = self.entries.entry(i);
+                        e.or_insert(entry);
+                    }
+                }
+                Err(e) => return Err(GameError::IOError(Arc::new(e))),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn loaded(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    pub fn get_entry(&self, tile: u8) -> Option<&AmbientEmitterEntry> {
+        self.entries.get(&tile)
+    }
+}
@@ -1,22 +1,30 @@
 use std::cell::UnsafeCell;
+use std::mem;
 use std::path::PathBuf;
+use std::pin::Pin;
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
+use downcast::Downcast;
 use lazy_static::lazy_static;
 
 use scripting::tsc::text_script::ScriptMode;
 
+use crate::common::{Color, Rect, BUILD_INFO};
 use crate::data::builtin_fs::BuiltinFS;
 use crate::framework::context::Context;
 use crate::framework::error::GameResult;
 use crate::framework::filesystem::{mount_user_vfs, mount_vfs};
 use crate::framework::graphics;
 use crate::framework::graphics::VSyncMode;
+use crate::framework::keyboard;
+use crate::framework::keyboard::ScanCode;
 use crate::framework::ui::UI;
 use crate::framework::vfs::PhysicalFS;
 use crate::game::shared_game_state::{Fps, SharedGameState, TimingMode};
+use crate::graphics::screenshot;
 use crate::graphics::texture_set::{G_MAG, I_MAG};
+use crate::scene::game_scene::GameScene;
 use crate::scene::loading_scene::LoadingScene;
 use crate::scene::Scene;
 
@@ -37,6 +45,103 @@ pub mod weapon;
 pub struct LaunchOptions {
     pub server_mode: bool,
     pub editor: bool,
+    pub gl_version: Option<(u8, u8)>,
+    pub no_opengl: bool,
+    /// Check `<TRA` targets of all stages, print a report and exit.
+    pub validate: bool,
+    /// Run this many ticks headless as fast as possible, print the average tick time and exit.
+    pub benchmark_ticks: Option<u32>,
+}
+
+impl LaunchOptions {
+    /// Parses the command line arguments of the desktop executable, unknown arguments are ignored.
+    pub fn parse(args: impl Iterator<Item = String>) -> Result<LaunchOptions, String> {
+        let mut options = LaunchOptions {
+            server_mode: false,
+            editor: false,
+            gl_version: None,
+            no_opengl: false,
+            validate: false,
+            benchmark_ticks: None,
+        };
+
+        for arg in args {
+            if arg == "--server-mode" {
+                options.server_mode = true;
+            }
+
+            if arg == "--editor" {
+                options.editor = true;
+            }
+
+            if arg == "--no-opengl" {
+                options.no_opengl = true;
+            }
+
+            if arg == "--validate" {
+                options.validate = true;
+            }
+
+            if let Some(ticks) = arg.strip_prefix("--benchmark-ticks=") {
+                match ticks.parse::<u32>() {
+                    Ok(ticks) => options.benchmark_ticks = Some(ticks),
+                    Err(_) => return Err(format!("Invalid tick count: {}.", ticks)),
+                }
+            }
+
+            if let Some(version) = arg.strip_prefix("--gl-version=") {
+                match version.split_once('.').map(|(major, minor)| (major.parse::<u8>(), minor.parse::<u8>())) {
+                    Some((Ok(major), Ok(minor))) => options.gl_version = Some((major, minor)),
+                    _ => return Err(format!("Invalid OpenGL version: {}, expected <major>.<minor>.", version)),
+                }
+            }
+        }
+
+        if options.server_mode && options.editor {
+            return Err("Cannot run in server mode and editor mode at the same time.".to_owned());
+        }
+
+        Ok(options)
+    }
+}
+
+/// Returns the engine version together with the commit hash and target it was built for.
+pub fn build_info() -> &'static str {
+    &BUILD_INFO
+}
+
+/// Upper bound of logic ticks processed in a single update. If the game falls further behind than that
+/// (eg. after the window was dragged or the system stalled), the backlog is dropped instead of being caught up on,
+/// so a slow frame can't snowball into even slower ones.
+const MAX_TICKS_PER_UPDATE: u32 = 10;
+
+/// How many ticks the benchmark waits for the game data and a stage to load before giving up.
+const BENCHMARK_LOADING_TICKS: u32 = 100;
+
+/// How much of a frame cap wait is spun instead of slept, `thread::sleep` can oversleep by about as much.
+#[cfg(not(target_arch = "wasm32"))]
+const FRAME_CAP_SPIN: u128 = 2_000_000;
+
+/// Held down to run the game 4 times faster in debug mode, none of the default key maps use it.
+const TURBO_KEY: ScanCode = ScanCode::Insert;
+
+/// What to do about the window being asked to close.
+#[derive(Debug, PartialEq, Eq)]
+enum QuitAction {
+    None,
+    Shutdown,
+    Confirm,
+}
+
+impl QuitAction {
+    /// Only the game scene has progress to lose and a pause menu to ask in, anywhere else the game just quits.
+    fn for_request(quit_requested: bool, in_game_scene: bool) -> QuitAction {
+        match (quit_requested, in_game_scene) {
+            (false, _) => QuitAction::None,
+            (true, false) => QuitAction::Shutdown,
+            (true, true) => QuitAction::Confirm,
+        }
+    }
 }
 
 lazy_static! {
@@ -52,6 +157,9 @@ pub struct Game {
     next_tick: u128,
     pub(crate) loops: u32,
     next_tick_draw: u128,
+    /// When the next frame can be drawn if the frame rate is limited.
+    next_capped_draw: u128,
+    screenshot_key_held: bool,
     present: bool,
     fps: Fps,
 }
@@ -67,6 +175,8 @@ impl Game {
             next_tick: 0,
             loops: 0,
             next_tick_draw: 0,
+            next_capped_draw: 0,
+            screenshot_key_held: false,
             present: true,
             fps: Fps::new(),
         };
@@ -75,21 +185,36 @@ impl Game {
     }
 
     pub(crate) fn update(&mut self, ctx: &mut Context) -> GameResult {
+        let now = self.start_time.elapsed().as_nanos();
+        self.update_at(ctx, now)
+    }
+
+    /// Runs the ticks that are due by `now`, in nanoseconds since the game started.
+    fn update_at(&mut self, ctx: &mut Context, now: u128) -> GameResult {
+        self.handle_quit_request();
+
         if let Some(scene) = &mut self.scene {
             let state_ref = unsafe { &mut *self.state.get() };
 
-            let speed =
+            let mut speed =
                 if state_ref.textscript_vm.mode == ScriptMode::Map && state_ref.textscript_vm.flags.cutscene_skip() {
                     4.0 * state_ref.settings.speed
                 } else {
                     1.0 * state_ref.settings.speed
                 };
 
+            // hold-to-turbo, for getting through long sections while debugging
+            if (cfg!(debug_assertions) || state_ref.settings.debug_mode)
+                && ctx.keyboard_context.is_key_pressed(TURBO_KEY)
+            {
+                speed *= 4.0;
+            }
+
             match state_ref.settings.timing_mode {
                 TimingMode::_50Hz | TimingMode::_60Hz => {
                     let last_tick = self.next_tick;
 
-                    while self.start_time.elapsed().as_nanos() >= self.next_tick && self.loops < 10 {
+                    while now >= self.next_tick && self.loops < MAX_TICKS_PER_UPDATE {
                         if (speed - 1.0).abs() < 0.01 {
                             self.next_tick += state_ref.settings.timing_mode.get_delta() as u128;
                         } else {
@@ -98,9 +223,9 @@ impl Game {
                         self.loops += 1;
                     }
 
-                    if self.loops == 10 {
+                    if self.loops == MAX_TICKS_PER_UPDATE {
                         log::warn!("Frame skip is way too high, a long system lag occurred?");
-                        self.last_tick = self.start_time.elapsed().as_nanos();
+                        self.last_tick = now;
                         self.next_tick =
                             self.last_tick + (state_ref.settings.timing_mode.get_delta() as f64 / speed) as u128;
                         self.loops = 0;
@@ -113,14 +238,95 @@ impl Game {
 
                     for _ in 0..self.loops {
                         scene.tick(state_ref, ctx)?;
+                        Game::finish_tick(state_ref, ctx);
                     }
                     self.fps.tick_count = self.fps.tick_count.saturating_add(self.loops as u32);
                 }
                 TimingMode::FrameSynchronized => {
                     scene.tick(state_ref, ctx)?;
+                    Game::finish_tick(state_ref, ctx);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish_tick(state: &mut SharedGameState, ctx: &mut Context) {
+        state.process_deferred_actions();
+        // a click nothing wanted this tick shouldn't trigger whatever ends up under the cursor later on
+        state.touch_controls.drop_mouse_clicks();
+
+        if !mem::take(&mut state.frame_held) {
+            ctx.keyboard_context.clear_tapped_keys();
+        }
+    }
+
+    fn handle_quit_request(&mut self) {
+        let state_ref = unsafe { &mut *self.state.get() };
+        let game_scene: Option<&mut GameScene> = self.scene.as_mut().and_then(|scene| scene.downcast_mut().ok());
+
+        match QuitAction::for_request(state_ref.quit_requested, game_scene.is_some()) {
+            QuitAction::None => {}
+            QuitAction::Shutdown => {
+                state_ref.quit_requested = false;
+                state_ref.shutdown();
+            }
+            QuitAction::Confirm => {
+                state_ref.quit_requested = false;
+                if let Some(game_scene) = game_scene {
+                    game_scene.pause_menu.open_quit_confirm(state_ref);
                 }
             }
         }
+    }
+
+    /// Replaces the current scene with the pending `next_scene` and initializes it.
+    pub(crate) fn switch_scene(&mut self, ctx: &mut Context) -> GameResult {
+        let state_ref = unsafe { &mut *self.state.get() };
+
+        mem::swap(&mut self.scene, &mut state_ref.next_scene);
+        state_ref.next_scene = None;
+        // clicks meant for the previous scene
+        state_ref.touch_controls.drop_mouse_clicks();
+        if let Some(scene) = &mut self.scene {
+            scene.init(state_ref, ctx)?;
+        }
+        self.loops = 0;
+        state_ref.frame_time = 0.0;
+
+        Ok(())
+    }
+
+    /// Restarts tick timing from now, so time spent suspended isn't caught up on as a burst of ticks.
+    pub(crate) fn resync_timing(&mut self) {
+        let delta = self.state.get_mut().settings.timing_mode.get_delta() as u128;
+
+        self.loops = 0;
+        self.next_tick = self.start_time.elapsed().as_nanos();
+        // keeps the interpolation factor sane until the next tick runs
+        self.last_tick = self.next_tick.saturating_sub(delta);
+    }
+
+    /// Advances the game by given amount of ticks right away, ignoring the timing mode and without drawing anything.
+    /// Scene switches are handled the same way as by the event loop.
+    pub(crate) fn run_ticks(&mut self, ctx: &mut Context, ticks: u32) -> GameResult {
+        let state_ref = unsafe { &mut *self.state.get() };
+
+        for _ in 0..ticks {
+            if state_ref.shutdown {
+                break;
+            }
+
+            if state_ref.should_switch_scene() {
+                self.switch_scene(ctx)?;
+            }
+
+            if let Some(scene) = &mut self.scene {
+                scene.tick(state_ref, ctx)?;
+                Game::finish_tick(state_ref, ctx);
+            }
+        }
+
         Ok(())
     }
 
@@ -155,7 +361,23 @@ impl Game {
             },
         }
 
+        if ctx.vsync_mode == VSyncMode::Uncapped && state_ref.settings.frame_cap != 0 {
+            let frame_delta = 1_000_000_000 / state_ref.settings.frame_cap as u128;
+
+            let now = self.start_time.elapsed().as_nanos();
+            if now < self.next_capped_draw {
+                // the browser paces frames itself and doesn't allow blocking the main thread
+                #[cfg(not(target_arch = "wasm32"))]
+                wait_until(self.start_time, self.next_capped_draw);
+                self.next_capped_draw += frame_delta;
+            } else {
+                // don't try to make up for frames that were late
+                self.next_capped_draw = now + frame_delta;
+            }
+        }
+
         if !self.present {
+            #[cfg(not(target_arch = "wasm32"))]
             std::thread::sleep(Duration::from_millis(2));
             self.loops = 0;
             return Ok(());
@@ -181,17 +403,29 @@ impl Game {
             state_ref.frame_time = if state_ref.settings.motion_interpolation { n1 / n2 } else { 1.0 };
         }
         unsafe {
-            G_MAG = if state_ref.settings.subpixel_coords { state_ref.scale } else { 1.0 };
+            G_MAG = if state_ref.settings.subpixel_coords && !state_ref.settings.pixel_snapping {
+                state_ref.scale
+            } else {
+                1.0
+            };
             I_MAG = state_ref.scale;
         }
         self.loops = 0;
 
         graphics::prepare_draw(ctx)?;
-        graphics::clear(ctx, [0.0, 0.0, 0.0, 1.0].into());
+        ctx.batch_draws = 0;
+        Game::draw_letterbox(state_ref, ctx)?;
 
         if let Some(scene) = &mut self.scene {
+            let (offset_x, offset_y) = state_ref.canvas_offset;
+
+            graphics::set_draw_offset(ctx, offset_x, offset_y)?;
             scene.draw(state_ref, ctx)?;
+            graphics::set_draw_offset(ctx, 0.0, 0.0)?;
+            Game::cover_outside_canvas(state_ref, ctx)?;
+
             if state_ref.settings.touch_controls {
+                graphics::set_draw_offset(ctx, offset_x, offset_y)?;
                 state_ref.touch_controls.draw(
                     state_ref.canvas_size,
                     state_ref.scale,
@@ -199,12 +433,22 @@ impl Game {
                     &mut state_ref.texture_set,
                     ctx,
                 )?;
+                graphics::set_draw_offset(ctx, 0.0, 0.0)?;
             }
 
             if state_ref.settings.fps_counter {
                 self.fps.act(state_ref, ctx, self.start_time.elapsed().as_nanos())?;
             }
 
+            // taken before the debug UI is drawn, so it doesn't end up in the screenshot
+            let screenshot_key_pressed = keyboard::is_key_pressed(ctx, state_ref.settings.screenshot_key);
+            if screenshot_key_pressed && !self.screenshot_key_held {
+                if let Err(err) = screenshot::take_screenshot(state_ref, ctx) {
+                    log::error!("Failed to take a screenshot: {}", err);
+                }
+            }
+            self.screenshot_key_held = screenshot_key_pressed;
+
             self.ui.draw(state_ref, ctx, scene)?;
         }
 
@@ -212,6 +456,207 @@ impl Game {
 
         Ok(())
     }
+
+    /// Fills the area the scene doesn't draw over, using the configured color or tiled texture.
+    fn draw_letterbox(state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
+        let (r, g, b) = state.settings.letterbox_color;
+        graphics::clear(ctx, Color::from_rgb(r, g, b));
+
+        Game::draw_letterbox_texture(state, ctx)
+    }
+
+    /// Covers whatever the scene drew past the edges of the canvas, so the letterbox stays clean
+    /// when the canvas is smaller than the window.
+    fn cover_outside_canvas(state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
+        for (bar, color) in Game::letterbox_bars(state) {
+            // clearing ignores the clip rect, so the bars have to be drawn over
+            graphics::set_clip_rect(ctx, Some(bar))?;
+            graphics::draw_rect(ctx, bar, color)?;
+            Game::draw_letterbox_texture(state, ctx)?;
+        }
+
+        graphics::set_clip_rect(ctx, None)
+    }
+
+    /// Parts of the window around the scaled canvas, in window pixels, with the color they're filled with.
+    fn letterbox_bars(state: &SharedGameState) -> Vec<(Rect<isize>, Color)> {
+        let (left, top) = (state.canvas_offset.0 as isize, state.canvas_offset.1 as isize);
+        let right = left + (state.canvas_size.0 * state.scale) as isize;
+        let bottom = top + (state.canvas_size.1 * state.scale) as isize;
+        let (screen_width, screen_height) = (state.screen_size.0 as isize, state.screen_size.1 as isize);
+
+        let (r, g, b) = state.settings.letterbox_color;
+        let bars = [
+            Rect::new(0, 0, screen_width, top),
+            Rect::new(0, bottom, screen_width, screen_height),
+            Rect::new(0, top, left, bottom),
+            Rect::new(right, top, screen_width, bottom),
+        ];
+
+        bars.into_iter()
+            .filter(|bar| bar.right > bar.left && bar.bottom > bar.top)
+            .map(|bar| (bar, Color::from_rgb(r, g, b)))
+            .collect()
+    }
+
+    fn draw_letterbox_texture(state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
+        if let Some(texture) = &state.settings.letterbox_texture {
+            let batch = match state.texture_set.get_or_load_batch(ctx, &state.constants, texture) {
+                Ok(batch) => batch,
+                Err(err) => {
+                    // the plain color was already drawn underneath, stop retrying every frame
+                    log::warn!("Failed to load letterbox texture {}, using the color instead: {}", texture, err);
+                    state.settings.letterbox_texture = None;
+                    return Ok(());
+                }
+            };
+            let (width, height) = batch.dimensions();
+
+            if width > 0 && height > 0 {
+                let rect = Rect::new(0, 0, width as u16, height as u16);
+                let (screen_width, screen_height) =
+                    (state.screen_size.0 / state.scale, state.screen_size.1 / state.scale);
+                let mut y = 0;
+
+                while (y as f32) < screen_height {
+                    let mut x = 0;
+
+                    while (x as f32) < screen_width {
+                        batch.add_rect(x as f32, y as f32, &rect);
+                        x += width;
+                    }
+
+                    y += height;
+                }
+
+                batch.draw(ctx)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Blocks until `deadline` nanoseconds after `start_time`. Sleeping alone is too coarse for high frame caps,
+/// so the last bit of the wait is spent spinning.
+#[cfg(not(target_arch = "wasm32"))]
+fn wait_until(start_time: Instant, deadline: u128) {
+    let now = start_time.elapsed().as_nanos();
+    if deadline > now + FRAME_CAP_SPIN {
+        std::thread::sleep(Duration::from_nanos((deadline - now - FRAME_CAP_SPIN) as u64));
+    }
+
+    while start_time.elapsed().as_nanos() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+/// Runs the game without a window on top of the null renderer, for tests and benchmarks. Nothing is ever drawn,
+/// ticks run back to back and input comes from a script instead of a backend.
+pub(crate) struct HeadlessRunner {
+    pub(crate) ctx: Pin<Box<Context>>,
+    pub(crate) game: Pin<Box<Game>>,
+}
+
+impl HeadlessRunner {
+    /// Starts the game on a context with its filesystems already mounted.
+    pub(crate) fn with_context(mut ctx: Pin<Box<Context>>) -> GameResult<HeadlessRunner> {
+        ctx.headless = true;
+        ctx.init_headless_renderer()?;
+
+        let mut game = Box::pin(Game::new(&mut ctx)?);
+        game.state.get_mut().handle_resize(&mut ctx)?;
+        #[cfg(feature = "scripting-lua")]
+        {
+            game.state.get().lua.update_refs(unsafe { &mut *game.state.get() }, &mut ctx as *mut Context);
+        }
+
+        Ok(HeadlessRunner { ctx, game })
+    }
+
+    pub(crate) fn state(&mut self) -> &mut SharedGameState {
+        self.game.state.get_mut()
+    }
+
+    /// Switches to `scene` on the next tick, the same way as setting `next_scene` from within the game does.
+    pub(crate) fn set_scene(&mut self, scene: Box<dyn Scene>) {
+        self.state().next_scene = Some(scene);
+    }
+
+    pub(crate) fn run_ticks(&mut self, ticks: u32) -> GameResult {
+        self.game.run_ticks(&mut self.ctx, ticks)
+    }
+
+    pub(crate) fn scene<T: Scene>(&mut self) -> Option<&mut T> {
+        self.game.scene.as_mut().and_then(|scene| scene.downcast_mut().ok())
+    }
+}
+
+#[cfg(test)]
+impl HeadlessRunner {
+    /// `mount` sets up the filesystems game data is read from, the built-in one is mounted after it.
+    pub(crate) fn new(mount: impl FnOnce(&mut Context)) -> GameResult<HeadlessRunner> {
+        let mut ctx = Box::pin(Context::new());
+        mount(&mut ctx);
+        mount_vfs(&mut ctx, Box::new(BuiltinFS::new()));
+
+        HeadlessRunner::with_context(ctx)
+    }
+
+    /// A headless context with the built-in data mounted and a game state loaded from it, for tests that tick
+    /// entities or scenes by hand instead of going through the game loop.
+    pub(crate) fn bare_state() -> (Context, SharedGameState) {
+        let mut ctx = Context::new();
+        ctx.headless = true;
+        mount_vfs(&mut ctx, Box::new(BuiltinFS::new()));
+        let state = SharedGameState::new(&mut ctx).unwrap();
+
+        (ctx, state)
+    }
+
+    /// Runs one tick for every entry of `script`, with exactly the keys listed in the entry held down during it.
+    pub(crate) fn run_script(&mut self, script: &[&[ScanCode]]) -> GameResult {
+        for keys in script {
+            let released: Vec<ScanCode> =
+                keyboard::pressed_keys(&self.ctx).iter().filter(|key| !keys.contains(key)).copied().collect();
+            for key in released {
+                self.ctx.keyboard_context.set_key(key, false);
+            }
+
+            for &key in keys.iter() {
+                if !keyboard::is_key_pressed(&self.ctx, key) {
+                    self.ctx.keyboard_context.set_key(key, true);
+                }
+            }
+
+            self.run_ticks(1)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Mounts all `.zip` archives placed in the resource directory, in alphabetical order.
+/// Loose files in the resource directory take priority over archived ones.
+#[cfg(all(feature = "zip-archives", not(any(target_os = "android", target_os = "horizon"))))]
+fn mount_zip_archives(context: &mut Context, resource_dir: &std::path::Path) {
+    use crate::framework::zip_fs::ZipFS;
+
+    let mut archives: Vec<PathBuf> = match std::fs::read_dir(resource_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("zip")))
+            .collect(),
+        Err(_) => return,
+    };
+    archives.sort();
+
+    for archive in archives {
+        match ZipFS::new(&archive) {
+            Ok(fs) => mount_vfs(context, Box::new(fs)),
+            Err(err) => log::warn!("Failed to mount {:?}: {}", archive, err),
+        }
+    }
 }
 
 pub fn init(options: LaunchOptions) -> GameResult {
@@ -221,6 +666,8 @@ pub fn init(options: LaunchOptions) -> GameResult {
         .with_level(log::Level::Info.to_level_filter())
         .init();
 
+    log::info!("{}", build_info());
+
     #[cfg(not(any(target_os = "android", target_os = "horizon")))]
     let resource_dir = if let Ok(data_dir) = std::env::var("CAVESTORY_DATA_DIR") {
         PathBuf::from(data_dir)
@@ -269,6 +716,9 @@ pub fn init(options: LaunchOptions) -> GameResult {
     #[cfg(not(any(target_os = "android", target_os = "horizon")))]
     mount_vfs(&mut context, Box::new(PhysicalFS::new(&resource_dir, true)));
 
+    #[cfg(all(feature = "zip-archives", not(any(target_os = "android", target_os = "horizon"))))]
+    mount_zip_archives(&mut context, &resource_dir);
+
     #[cfg(not(any(target_os = "android", target_os = "horizon")))]
     let project_dirs = match directories::ProjectDirs::from("", "", "doukutsu-rs") {
         Some(dirs) => dirs,
@@ -328,20 +778,339 @@ pub fn init(options: LaunchOptions) -> GameResult {
     log::info!("Mounting built-in FS");
     mount_vfs(&mut context, Box::new(BuiltinFS::new()));
 
-    if options.server_mode {
+    if let Some(ticks) = options.benchmark_ticks {
+        log::info!("Running {} ticks headless for benchmarking...", ticks);
+
+        let mut runner = HeadlessRunner::with_context(context)?;
+        runner.set_scene(Box::new(LoadingScene::new()));
+
+        // loading the data and the saved game isn't part of the measurement, only ticks spent in a stage are
+        let mut loading_ticks = 0;
+        while runner.scene::<GameScene>().is_none() {
+            if loading_ticks == BENCHMARK_LOADING_TICKS {
+                return Err(crate::framework::error::GameError::InvalidValue(
+                    "No stage was loaded to run the benchmark in.".to_owned(),
+                ));
+            }
+
+            runner.run_ticks(1)?;
+            loading_ticks += 1;
+        }
+
+        let start = Instant::now();
+        runner.run_ticks(ticks)?;
+        let elapsed = start.elapsed();
+
+        log::info!(
+            "Ran {} ticks in {:.3}s, {:.1}us per tick on average.",
+            ticks,
+            elapsed.as_secs_f64(),
+            elapsed.as_secs_f64() * 1_000_000.0 / ticks.max(1) as f64
+        );
+
+        return Ok(());
+    }
+
+    if options.validate {
+        log::info!("Running headless to validate warps...");
+        context.headless = true;
+    } else if options.server_mode {
         log::info!("Running in server mode...");
         context.headless = true;
     }
 
+    context.gl_version = options.gl_version;
+    context.no_opengl = options.no_opengl;
+
     let mut game = Box::pin(Game::new(&mut context)?);
+
+    if options.validate {
+        let state = game.state.get_mut();
+        state.reload_resources(&mut context)?;
+
+        let broken = crate::game::scripting::tsc::validator::validate_warps(state, &mut context)?;
+        if broken > 0 {
+            return Err(crate::framework::error::GameError::InvalidValue(format!(
+                "{} broken warp targets found.",
+                broken
+            )));
+        }
+
+        return Ok(());
+    }
     #[cfg(feature = "scripting-lua")]
     {
         game.state.get().lua.update_refs(unsafe { &mut *game.state.get() }, &mut context as *mut Context);
     }
 
     game.state.get_mut().next_scene = Some(Box::new(LoadingScene::new()));
+
     log::info!("Starting main loop...");
     context.run(game.as_mut().get_mut())?;
 
     Ok(())
 }
+
+#[test]
+fn test_launch_options_parse() {
+    let args = |list: &[&str]| LaunchOptions::parse(list.iter().map(|arg| arg.to_string()));
+
+    let options = args(&["doukutsu-rs", "--no-opengl", "--gl-version=3.2"]).unwrap();
+    assert!(options.no_opengl);
+    assert_eq!(options.gl_version, Some((3, 2)));
+
+    let options = args(&["doukutsu-rs"]).unwrap();
+    assert!(!options.no_opengl);
+    assert_eq!(options.gl_version, None);
+
+    let options = args(&["doukutsu-rs", "--validate", "--benchmark-ticks=600"]).unwrap();
+    assert!(options.validate);
+    assert_eq!(options.benchmark_ticks, Some(600));
+
+    assert!(args(&["doukutsu-rs", "--gl-version=3"]).is_err());
+    assert!(args(&["doukutsu-rs", "--benchmark-ticks=lots"]).is_err());
+    assert!(args(&["doukutsu-rs", "--server-mode", "--editor"]).is_err());
+}
+
+#[test]
+fn test_build_info() {
+    // what `--version` prints
+    let info = build_info();
+    let profile = if cfg!(debug_assertions) { "debug" } else { "release" };
+
+    assert!(info.starts_with("doukutsu-rs "), "{}", info);
+    assert!(info.contains(" (build "), "{}", info);
+    assert!(info.ends_with(&format!(" {})", profile)), "{}", info);
+
+    let hash = info.split(" (build ").nth(1).and_then(|rest| rest.split(',').next()).unwrap();
+    assert!(!hash.is_empty() && !hash.contains(' '), "{}", info);
+}
+
+#[test]
+fn test_unsaved_progress() {
+    use crate::framework::filesystem::TempDataDir;
+    use crate::game::scripting::tsc::text_script::{TextScript, TextScriptEncoding, TextScriptVM};
+    use crate::game::stage::Stage;
+
+    let dir = TempDataDir::new("unsaved-progress");
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    dir.mount_user(&mut ctx);
+
+    let stage = Stage::blank(20, 15);
+    state.stages.push(stage.data.clone());
+    let mut scene = GameScene::from_stage(&mut state, &mut ctx, stage, 0).unwrap();
+    let script = TextScript::compile(b"#0100\r\n<IT+0003<END\r\n", true, TextScriptEncoding::UTF8).unwrap();
+    state.textscript_vm.set_scene_script(script);
+    // started by hand, without going through `init`
+    state.textscript_vm.suspend = false;
+
+    // nothing to lose yet, so there's nothing to confirm
+    state.request_quit();
+    assert!(state.shutdown);
+    state.shutdown = false;
+
+    // picking up an item asks first
+    state.textscript_vm.start_script(100);
+    TextScriptVM::run(&mut state, &mut scene, &mut ctx).unwrap();
+    assert!(state.unsaved_progress);
+    state.request_quit();
+    assert!(!state.shutdown);
+    assert_eq!(QuitAction::for_request(state.quit_requested, true), QuitAction::Confirm);
+    // except outside of the game, where there's nothing to save from
+    assert_eq!(QuitAction::for_request(state.quit_requested, false), QuitAction::Shutdown);
+    state.quit_requested = false;
+
+    // until the game gets saved
+    state.save_game(&mut scene, &mut ctx).unwrap();
+    assert!(dir.root.join("Profile.dat").exists());
+    assert!(!state.unsaved_progress);
+    state.request_quit();
+    assert!(state.shutdown);
+}
+
+/// Sets the flag of every tick `Z` is held down in and rolls the game RNG once per tick.
+#[cfg(test)]
+#[derive(Default)]
+struct ScriptedScene {
+    ticks: usize,
+    rolls: Vec<i32>,
+}
+
+#[cfg(test)]
+impl Scene for ScriptedScene {
+    fn tick(&mut self, state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
+        if keyboard::is_key_pressed(ctx, ScanCode::Z) {
+            state.set_flag(self.ticks, true);
+        }
+
+        self.rolls.push(state.game_rng.range(0..99));
+        self.ticks += 1;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_headless_runner() {
+    let script: [&[ScanCode]; 6] = [&[], &[ScanCode::Z], &[ScanCode::Z, ScanCode::X], &[], &[ScanCode::Z], &[]];
+    let mut rolls = Vec::new();
+
+    for _ in 0..2 {
+        let mut runner = HeadlessRunner::new(|_| {}).unwrap();
+        runner.state().reseed(1234);
+        runner.set_scene(Box::new(ScriptedScene::default()));
+        runner.run_script(&script).unwrap();
+
+        let state = runner.state();
+        let flags: Vec<bool> = (0..script.len()).map(|tick| state.get_flag(tick)).collect();
+        assert_eq!(flags, vec![false, true, true, false, true, false]);
+        assert!(keyboard::pressed_keys(&runner.ctx).is_empty());
+
+        let scene: &mut ScriptedScene = runner.scene().unwrap();
+        assert_eq!(scene.ticks, script.len());
+        rolls.push(mem::take(&mut scene.rolls));
+    }
+
+    // a fixed seed plays out the same every run
+    assert_eq!(rolls[0], rolls[1]);
+}
+
+/// Starts looking for clicks anywhere on the screen after ignoring them for `ignore_ticks`.
+#[cfg(test)]
+#[derive(Default)]
+struct ClickScene {
+    ignore_ticks: usize,
+    ticks: usize,
+    clicks: Vec<bool>,
+}
+
+#[cfg(test)]
+impl Scene for ClickScene {
+    fn tick(&mut self, state: &mut SharedGameState, _ctx: &mut Context) -> GameResult {
+        if self.ticks >= self.ignore_ticks {
+            self.clicks.push(state.touch_controls.consume_click_in(Rect::new(-1, -1, 10000, 10000)));
+        }
+        self.ticks += 1;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_stale_mouse_clicks_dropped() {
+    let mut runner = HeadlessRunner::new(|_| {}).unwrap();
+
+    // a click made between two ticks reaches the next one
+    runner.set_scene(Box::new(ClickScene::default()));
+    runner.state().touch_controls.mouse_press((10.0, 10.0));
+    runner.run_ticks(2).unwrap();
+    assert_eq!(runner.scene::<ClickScene>().unwrap().clicks, vec![true, false]);
+
+    // but not any after it if nothing was looking for clicks then
+    runner.set_scene(Box::new(ClickScene { ignore_ticks: 2, ..Default::default() }));
+    runner.run_ticks(1).unwrap();
+    runner.state().touch_controls.mouse_press((10.0, 10.0));
+    runner.run_ticks(2).unwrap();
+    assert_eq!(runner.scene::<ClickScene>().unwrap().clicks, vec![false]);
+
+    // and it doesn't carry over into a different scene either
+    runner.state().touch_controls.mouse_press((10.0, 10.0));
+    runner.set_scene(Box::new(ClickScene::default()));
+    runner.run_ticks(1).unwrap();
+    assert_eq!(runner.scene::<ClickScene>().unwrap().clicks, vec![false]);
+}
+
+#[test]
+fn test_turbo_speed() {
+    /// Runs a second worth of 60 fps frames, returns how many ticks the scene got.
+    fn run_second(runner: &mut HeadlessRunner, now: &mut u128) -> usize {
+        let ticks = runner.scene::<ScriptedScene>().unwrap().ticks;
+        for _ in 0..60 {
+            *now += 1_000_000_000 / 60;
+            runner.game.update_at(&mut runner.ctx, *now).unwrap();
+            // the part of drawing a frame that matters here
+            runner.game.loops = 0;
+        }
+
+        runner.scene::<ScriptedScene>().unwrap().ticks - ticks
+    }
+
+    let mut runner = HeadlessRunner::new(|_| {}).unwrap();
+    runner.state().settings.debug_mode = true;
+    runner.set_scene(Box::new(ScriptedScene::default()));
+    runner.run_ticks(1).unwrap();
+
+    let tps = (1_000_000_000 / runner.state().settings.timing_mode.get_delta()) as usize;
+    let mut now = runner.game.next_tick;
+
+    let normal = run_second(&mut runner, &mut now);
+    assert!(normal.abs_diff(tps) <= 1, "{} ticks at normal speed", normal);
+
+    // holding turbo runs about 4 times the ticks, and keys held at the same time reach every one of them
+    runner.ctx.keyboard_context.set_key(TURBO_KEY, true);
+    runner.ctx.keyboard_context.set_key(ScanCode::Z, true);
+    let first_turbo_tick = runner.scene::<ScriptedScene>().unwrap().ticks;
+    let turbo = run_second(&mut runner, &mut now);
+    assert!(turbo.abs_diff(4 * tps) <= 2, "{} ticks with turbo held", turbo);
+    assert!((first_turbo_tick..first_turbo_tick + turbo).all(|tick| runner.state().get_flag(tick)));
+
+    runner.ctx.keyboard_context.set_key(TURBO_KEY, false);
+    runner.ctx.keyboard_context.set_key(ScanCode::Z, false);
+    let normal = run_second(&mut runner, &mut now);
+    assert!(normal.abs_diff(tps) <= 1, "{} ticks after letting go of turbo", normal);
+    assert!(!runner.state().get_flag(first_turbo_tick + turbo));
+}
+
+#[test]
+fn test_letterbox_bars() {
+    use crate::game::shared_game_state::{CanvasMode, ScalingMode};
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    state.settings.canvas_mode = CanvasMode::Classic;
+    state.settings.scaling_mode = ScalingMode::Integer;
+    state.settings.letterbox_color = (128, 128, 128);
+
+    // a 1080p fullscreen window fits the 320x240 view 4 times, centered
+    ctx.screen_size = (1920.0, 1080.0);
+    // fails on the lightmap as there's no renderer, but only after the sizes are in place
+    let _ = state.handle_resize(&mut ctx);
+    assert_eq!((state.scale, state.canvas_offset), (4.0, (320.0, 60.0)));
+
+    let bars: Vec<_> = Game::letterbox_bars(&state)
+        .into_iter()
+        .map(|(bar, color)| ((bar.left, bar.top, bar.right, bar.bottom), color))
+        .collect();
+
+    let gray = Color::from_rgb(128, 128, 128);
+    assert_eq!(
+        bars,
+        vec![
+            ((0, 0, 1920, 60), gray),
+            ((0, 1020, 1920, 1080), gray),
+            ((0, 60, 320, 1020), gray),
+            ((1600, 60, 1920, 1020), gray),
+        ]
+    );
+
+    // a window the view fills exactly has nothing to cover
+    ctx.screen_size = (640.0, 480.0);
+    let _ = state.handle_resize(&mut ctx);
+    assert!(Game::letterbox_bars(&state).is_empty());
+}
+
+#[test]
+fn test_frame_cap_wait() {
+    // 240 fps, the highest cap offered
+    let frame_delta = 1_000_000_000 / 240;
+    let start_time = Instant::now();
+
+    let mut deadline = 0;
+    for frame in 0..24 {
+        deadline += frame_delta;
+        wait_until(start_time, deadline);
+
+        // never early, and late by much less than a frame
+        let late = start_time.elapsed().as_nanos() - deadline;
+        assert!(late < FRAME_CAP_SPIN, "frame {} was {}ns late", frame, late);
+    }
+}
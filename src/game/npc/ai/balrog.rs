@@ -50,7 +50,7 @@ impl NPC {
                     self.anim_num = 1;
                     self.action_counter = 0;
 
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                     state.quake_counter = 30;
                     state.quake_rumble_counter = 30;
                 }
@@ -119,7 +119,7 @@ impl NPC {
 
                     let _ = npc_list.spawn(0x100, npc);
 
-                    state.sound_manager.play_sfx(39);
+                    state.sound_manager.play_sfx_at(39, self.x, self.y);
 
                     if self.action_counter2 == 0 {
                         self.action_num = 3;
@@ -150,7 +150,7 @@ impl NPC {
                     self.anim_num = 2;
                     self.damage = 0;
 
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                     state.quake_counter = 30;
                     state.quake_rumble_counter = 30;
                 }
@@ -276,7 +276,7 @@ impl NPC {
                 if self.y < 0 {
                     self.npc_type = 0;
                     self.spritesheet_id = 20; // NpcSym
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                     state.quake_counter = 30;
                     state.quake_rumble_counter = 30;
                 }
@@ -311,7 +311,7 @@ impl NPC {
                         let _ = npc_list.spawn(0x100, npc.clone());
                     }
 
-                    state.sound_manager.play_sfx(72);
+                    state.sound_manager.play_sfx_at(72, self.x, self.y);
                 }
 
                 self.target_x = 1; // ???
@@ -397,7 +397,7 @@ impl NPC {
 
                     self.anim_num += 1;
                     if self.anim_num == 10 || self.anim_num == 11 {
-                        state.sound_manager.play_sfx(23);
+                        state.sound_manager.play_sfx_at(23, self.x, self.y);
                     }
 
                     if self.anim_num > 12 {
@@ -416,7 +416,7 @@ impl NPC {
                     self.action_num = 71;
                     self.action_counter = 64;
                     self.anim_num = 13;
-                    state.sound_manager.play_sfx(29);
+                    state.sound_manager.play_sfx_at(29, self.x, self.y);
                 }
 
                 self.action_counter -= 1;
@@ -475,7 +475,7 @@ impl NPC {
                 let y = clamp(self.y / (0x2000), 0, stage.map.height as i32) as usize;
 
                 if y <= 34 && stage.change_tile(x, y, 0) {
-                    state.sound_manager.play_sfx(44);
+                    state.sound_manager.play_sfx_at(44, self.x, self.y);
                     state.super_quake_counter = 10;
                     state.super_quake_rumble_counter = 10;
 
@@ -557,8 +557,8 @@ impl NPC {
                         let _ = npc_list.spawn(0x100, npc.clone());
                     }
 
-                    state.sound_manager.play_sfx(12);
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(12, self.x, self.y);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                     state.quake_counter = 30;
                     state.quake_rumble_counter = 30;
 
@@ -575,7 +575,7 @@ impl NPC {
                     self.anim_num = 2;
                     self.action_counter = 0;
 
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                     state.quake_counter = 30;
                     state.quake_rumble_counter = 30;
                 }
@@ -690,7 +690,7 @@ impl NPC {
                     npc.y = self.y + 0x800;
 
                     let _ = npc_list.spawn(0x100, npc);
-                    state.sound_manager.play_sfx(39);
+                    state.sound_manager.play_sfx_at(39, self.x, self.y);
 
                     if self.vel_x2 == 0 {
                         self.action_num = 3;
@@ -729,7 +729,7 @@ impl NPC {
                     self.anim_num += 1;
                     if self.anim_num > 5 {
                         self.anim_num = 4;
-                        state.sound_manager.play_sfx(47);
+                        state.sound_manager.play_sfx_at(47, self.x, self.y);
                     }
                 }
 
@@ -755,8 +755,8 @@ impl NPC {
                     self.anim_num = 2;
                     self.damage = 0;
 
-                    state.sound_manager.play_sfx(25);
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(25, self.x, self.y);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                     state.quake_counter = 30;
                     state.quake_rumble_counter = 30;
 
@@ -856,7 +856,7 @@ impl NPC {
                     self.anim_num += 1;
 
                     if self.anim_num == 2 || self.anim_num == 4 {
-                        state.sound_manager.play_sfx(23);
+                        state.sound_manager.play_sfx_at(23, self.x, self.y);
                     }
 
                     if self.anim_num > 4 {
@@ -896,7 +896,7 @@ impl NPC {
                     self.anim_num = 8;
                     state.quake_counter = 30;
                     state.quake_rumble_counter = 30;
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                 }
 
                 let pi = self.get_closest_player_idx_mut(&players);
@@ -956,7 +956,7 @@ impl NPC {
             20 | 21 => {
                 if self.action_num == 20 {
                     let player = &mut players[self.vel_y2 as usize];
-                    state.sound_manager.play_sfx(25);
+                    state.sound_manager.play_sfx_at(25, self.x, self.y);
                     player.cond.set_hidden(false);
 
                     self.direction = self.direction.opposite();
@@ -1037,7 +1037,7 @@ impl NPC {
                     self.anim_num += 1;
 
                     if self.anim_num == 2 || self.anim_num == 4 {
-                        state.sound_manager.play_sfx(23);
+                        state.sound_manager.play_sfx_at(23, self.x, self.y);
                     }
 
                     if self.anim_num > 4 {
@@ -1101,7 +1101,7 @@ impl NPC {
 
                 self.action_counter += 1;
                 if self.action_counter <= 29 && self.action_counter % 6 == 1 {
-                    state.sound_manager.play_sfx(39);
+                    state.sound_manager.play_sfx_at(39, self.x, self.y);
 
                     let mut npc = NPC::create(170, &state.npc_table);
                     npc.cond.set_alive(true);
@@ -1117,7 +1117,7 @@ impl NPC {
                     self.anim_num = 8;
                     state.quake_counter = 30;
                     state.quake_rumble_counter = 30;
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                 }
                 if self.action_counter > 7
                     && self.x - 0x1800 < player.x
@@ -1167,7 +1167,7 @@ impl NPC {
                 let player = &mut players[self.target_x as usize];
 
                 if self.action_num == 20 {
-                    state.sound_manager.play_sfx(25);
+                    state.sound_manager.play_sfx_at(25, self.x, self.y);
                     player.cond.set_hidden(false);
 
                     if self.direction != Direction::Left {
@@ -1222,7 +1222,7 @@ impl NPC {
         if (self.direction == Direction::Left && self.flags.hit_left_wall())
             || (self.direction == Direction::Right && self.flags.hit_right_wall())
         {
-            state.sound_manager.play_sfx(44);
+            state.sound_manager.play_sfx_at(44, self.x, self.y);
             npc_list.create_death_smoke(self.x, self.y, 0, 3, state, &self.rng);
             self.vanish(state);
 
@@ -52,7 +52,7 @@ impl NPC {
                     // interpolation glitch fix
                     self.prev_x = self.x;
                     self.prev_y = self.y;
-                    state.sound_manager.play_sfx(29);
+                    state.sound_manager.play_sfx_at(29, self.x, self.y);
                 }
 
                 self.action_counter += 1;
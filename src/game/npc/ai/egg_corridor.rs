@@ -66,7 +66,7 @@ impl NPC {
                         state.quake_counter = 8;
                         state.quake_rumble_counter = 8;
 
-                        state.sound_manager.play_sfx(26);
+                        state.sound_manager.play_sfx_at(26, self.x, self.y);
 
                         let mut npc = NPC::create(4, &state.npc_table);
                         npc.cond.set_alive(true);
@@ -157,7 +157,7 @@ impl NPC {
 
                     self.vel_x = self.direction.vector_x() * 0x100;
                     self.vel_y = -0x5ff;
-                    state.sound_manager.play_sfx(30);
+                    state.sound_manager.play_sfx_at(30, self.x, self.y);
                 }
             }
             3 => {
@@ -167,7 +167,7 @@ impl NPC {
                     self.action_num = 1;
                     self.anim_num = 0;
 
-                    state.sound_manager.play_sfx(23);
+                    state.sound_manager.play_sfx_at(23, self.x, self.y);
                 }
             }
             _ => (),
@@ -569,7 +569,7 @@ impl NPC {
                 npc.vel_y = (angle.sin() * -1024.0) as i32;
 
                 let _ = npc_list.spawn(0x100, npc);
-                state.sound_manager.play_sfx(39);
+                state.sound_manager.play_sfx_at(39, self.x, self.y);
             }
 
             if self.action_counter2 > 8 {
@@ -634,7 +634,7 @@ impl NPC {
             self.npc_flags.set_shootable(false);
             self.damage = 0;
 
-            state.sound_manager.play_sfx(72);
+            state.sound_manager.play_sfx_at(72, self.x, self.y);
             npc_list.create_death_smoke(self.x, self.y, self.display_bounds.right as usize, 8, state, &self.rng);
             self.create_xp_drop(state, npc_list);
         }
@@ -704,7 +704,7 @@ impl NPC {
                     let _ = npc_list.spawn(0x100, npc);
 
                     if !player.cond.hidden() {
-                        state.sound_manager.play_sfx(33);
+                        state.sound_manager.play_sfx_at(33, self.x, self.y);
                     }
                 }
                 if self.action_counter > 60 {
@@ -810,7 +810,7 @@ impl NPC {
                     self.anim_num = 2;
 
                     self.vel_y = -0x5ff;
-                    state.sound_manager.play_sfx(30);
+                    state.sound_manager.play_sfx_at(30, self.x, self.y);
 
                     if self.direction == Direction::Left {
                         self.vel_x = -0x100;
@@ -826,7 +826,7 @@ impl NPC {
                     self.action_num = 1;
                     self.anim_num = 0;
 
-                    state.sound_manager.play_sfx(23);
+                    state.sound_manager.play_sfx_at(23, self.x, self.y);
                 }
             }
             _ => (),
@@ -878,7 +878,7 @@ impl NPC {
                 if self.flags.hit_anything() {
                     let player = self.get_closest_player_ref(&players);
                     if !player.cond.hidden() {
-                        state.sound_manager.play_sfx(12);
+                        state.sound_manager.play_sfx_at(12, self.x, self.y);
                     }
 
                     npc_list.create_death_smoke(
@@ -961,7 +961,7 @@ impl NPC {
                     self.vel_y = 0;
                     self.npc_flags.set_solid_hard(true);
 
-                    state.sound_manager.play_sfx(12);
+                    state.sound_manager.play_sfx_at(12, self.x, self.y);
                     npc_list.create_death_smoke(
                         self.x,
                         self.y,
@@ -1071,7 +1071,7 @@ impl NPC {
 
                         state.quake_counter = 20;
                         state.quake_rumble_counter = 20;
-                        state.sound_manager.play_sfx(35);
+                        state.sound_manager.play_sfx_at(35, self.x, self.y);
                         npc_list.create_death_smoke(self.x, self.y, 0x10000, 100 as usize, state, &self.rng);
                     }
                     _ => (),
@@ -1110,7 +1110,7 @@ impl NPC {
                 if self.action_num == 0 {
                     self.action_num = 1;
                     self.anim_num = self.tsc_direction;
-                    state.sound_manager.play_sfx(43);
+                    state.sound_manager.play_sfx_at(43, self.x, self.y);
                 }
 
                 self.x += 0x200;
@@ -1227,7 +1227,7 @@ impl NPC {
                 npc.vel_y = (angle.sin() * -1536.0) as i32;
 
                 let _ = npc_list.spawn(0x100, npc);
-                state.sound_manager.play_sfx(39);
+                state.sound_manager.play_sfx_at(39, self.x, self.y);
             }
 
             if self.action_counter2 > 16 {
@@ -62,7 +62,7 @@ impl NPC {
                     self.action_num = 3;
                     self.anim_num = 2;
                     self.vel_y = -0x5FF;
-                    state.sound_manager.play_sfx(30);
+                    state.sound_manager.play_sfx_at(30, self.x, self.y);
 
                     let player = self.get_closest_player_mut(players);
                     if self.x <= player.x {
@@ -97,7 +97,7 @@ impl NPC {
                     self.anim_num = 2;
                 } else {
                     if self.action_counter % 4 == 1 {
-                        state.sound_manager.play_sfx(109);
+                        state.sound_manager.play_sfx_at(109, self.x, self.y);
                     }
                     if self.flags.hit_bottom_wall() {
                         self.vel_y = -0x200;
@@ -114,7 +114,7 @@ impl NPC {
                         npc.vel_y = (angle.sin() * -1536.0) as i32;
 
                         let _ = npc_list.spawn(0x100, npc);
-                        state.sound_manager.play_sfx(39);
+                        state.sound_manager.play_sfx_at(39, self.x, self.y);
                     }
                     self.anim_counter += 1;
                     if self.anim_counter > 0 {
@@ -133,7 +133,7 @@ impl NPC {
                     self.action_counter = 0;
                     self.anim_num = 0;
                     self.action_num = 1;
-                    state.sound_manager.play_sfx(23);
+                    state.sound_manager.play_sfx_at(23, self.x, self.y);
                 }
             }
             _ => (),
@@ -259,7 +259,7 @@ impl NPC {
                         self.vel_y = -0x5FF;
                         self.action_num = 20;
                         if !player.cond.hidden() {
-                            state.sound_manager.play_sfx(30);
+                            state.sound_manager.play_sfx_at(30, self.x, self.y);
                         }
                     }
                 } else {
@@ -267,7 +267,7 @@ impl NPC {
                     self.vel_y = -0x5FF;
                     self.action_num = 20;
                     if !player.cond.hidden() {
-                        state.sound_manager.play_sfx(30);
+                        state.sound_manager.play_sfx_at(30, self.x, self.y);
                     }
                 }
             }
@@ -301,7 +301,7 @@ impl NPC {
                     self.vel_x = 0;
 
                     if !player.cond.hidden() {
-                        state.sound_manager.play_sfx(23);
+                        state.sound_manager.play_sfx_at(23, self.x, self.y);
                     }
                 }
             }
@@ -347,7 +347,7 @@ impl NPC {
                     Direction::Right => self.vel_x = -0x100,
                     _ => (),
                 };
-                state.sound_manager.play_sfx(53);
+                state.sound_manager.play_sfx_at(53, self.x, self.y);
             }
             1 if self.flags.hit_bottom_wall() => {
                 self.action_num = 2;
@@ -444,7 +444,7 @@ impl NPC {
                     let _ = npc_list.spawn(0x100, npc);
 
                     if !player.cond.hidden() {
-                        state.sound_manager.play_sfx(39);
+                        state.sound_manager.play_sfx_at(39, self.x, self.y);
                     }
                     self.action_num = 1;
                     self.action_counter = self.rng.range(70..150) as u16;
@@ -556,8 +556,8 @@ impl NPC {
                     state.quake_counter = 30;
                     state.quake_rumble_counter = 30;
 
-                    state.sound_manager.play_sfx(26);
-                    state.sound_manager.play_sfx(72);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
+                    state.sound_manager.play_sfx_at(72, self.x, self.y);
                 }
 
                 let player = self.get_closest_player_mut(players);
@@ -705,7 +705,7 @@ impl NPC {
             0 | 1 => {
                 if self.action_num == 0 {
                     npc_list.kill_npcs_by_type(161, true, state);
-                    state.sound_manager.play_sfx(72);
+                    state.sound_manager.play_sfx_at(72, self.x, self.y);
 
                     let mut npc = NPC::create(4, &state.npc_table);
                     npc.cond.set_alive(true);
@@ -770,7 +770,7 @@ impl NPC {
                     let _ = npc_list.spawn(0x100, npc);
                 }
                 if self.action_counter3 % 4 == 2 {
-                    state.sound_manager.play_sfx(21);
+                    state.sound_manager.play_sfx_at(21, self.x, self.y);
                 }
             }
             3 => {
@@ -883,7 +883,7 @@ impl NPC {
                     self.action_counter = 0;
                     self.vel_y = -0x400;
                     self.vel_x = 0x100;
-                    state.sound_manager.play_sfx(25);
+                    state.sound_manager.play_sfx_at(25, self.x, self.y);
                 }
 
                 self.x += self.vel_x;
@@ -891,7 +891,7 @@ impl NPC {
                 self.vel_y += 0x10;
 
                 if self.action_counter != 0 && self.flags.hit_bottom_wall() {
-                    state.sound_manager.play_sfx(35);
+                    state.sound_manager.play_sfx_at(35, self.x, self.y);
                     state.quake_counter = 40;
                     state.quake_rumble_counter = 40;
                     self.action_num = 0;
@@ -1043,7 +1043,7 @@ impl NPC {
                     if self.action_counter > 3 {
                         self.action_counter3 += 1;
                         if self.action_counter3 == 3 {
-                            state.sound_manager.play_sfx(30);
+                            state.sound_manager.play_sfx_at(30, self.x, self.y);
                             self.action_counter3 = 0;
                             self.action_num = 25;
                             self.action_counter = 0;
@@ -1056,7 +1056,7 @@ impl NPC {
                                 self.vel_x = 0x100;
                             }
                         } else {
-                            state.sound_manager.play_sfx(30);
+                            state.sound_manager.play_sfx_at(30, self.x, self.y);
                             self.action_num = 20;
                             self.anim_num = 2;
                             self.vel_y = -0x200;
@@ -1071,7 +1071,7 @@ impl NPC {
                 20 => {
                     self.action_counter += 1;
                     if self.flags.hit_bottom_wall() {
-                        state.sound_manager.play_sfx(23);
+                        state.sound_manager.play_sfx_at(23, self.x, self.y);
                         self.anim_num = 1;
                         self.action_num = 30;
                         self.action_counter = 0;
@@ -1091,7 +1091,7 @@ impl NPC {
                         npc.vel_y = (angle.sin() * -1536.0) as i32;
 
                         let _ = npc_list.spawn(0x100, npc);
-                        state.sound_manager.play_sfx(39);
+                        state.sound_manager.play_sfx_at(39, self.x, self.y);
 
                         self.anim_num = 3;
                         state.npc_curly_counter = self.rng.range(80..100) as u16;
@@ -1103,7 +1103,7 @@ impl NPC {
                     }
 
                     if self.flags.hit_bottom_wall() {
-                        state.sound_manager.play_sfx(23);
+                        state.sound_manager.play_sfx_at(23, self.x, self.y);
                         self.anim_num = 1;
                         self.action_num = 30;
                         self.action_counter = 0;
@@ -1173,7 +1173,7 @@ impl NPC {
                 if hit {
                     self.action_num = 2;
                     self.action_counter2 += 1;
-                    state.sound_manager.play_sfx(31);
+                    state.sound_manager.play_sfx_at(31, self.x, self.y);
                 }
             }
             2 => {
@@ -1304,7 +1304,7 @@ impl NPC {
                 }
                 let _ = npc_list.spawn(0x100, npc);
 
-                state.sound_manager.play_sfx(39);
+                state.sound_manager.play_sfx_at(39, self.x, self.y);
                 self.action_num = 0;
                 self.anim_num = 0;
 
@@ -1403,7 +1403,7 @@ impl NPC {
 
                 self.action_counter += 1;
                 if (self.action_counter & 7) == 0 {
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                 }
                 state.quake_counter = 20;
                 state.quake_rumble_counter = 20;
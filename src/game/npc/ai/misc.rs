@@ -674,7 +674,7 @@ impl NPC {
                 let player = self.get_closest_player_mut(players);
 
                 if (player.x - self.x).abs() < 0x1000 && player.y < self.y + 0x1000 && player.y > self.y - 0x2000 {
-                    state.sound_manager.play_sfx(43);
+                    state.sound_manager.play_sfx_at(43, self.x, self.y);
                     self.action_num = 1;
                 }
             }
@@ -1014,7 +1014,7 @@ impl NPC {
 
                         state.quake_counter = 10;
                         state.quake_rumble_counter = 10;
-                        state.sound_manager.play_sfx(26);
+                        state.sound_manager.play_sfx_at(26, self.x, self.y);
                     }
 
                     self.action_num = 1;
@@ -1059,7 +1059,7 @@ impl NPC {
         if self.life < 990 {
             npc_list.create_death_smoke(self.x, self.y, self.display_bounds.right as usize, 8, state, &self.rng);
             self.cond.set_alive(false);
-            state.sound_manager.play_sfx(70);
+            state.sound_manager.play_sfx_at(70, self.x, self.y);
 
             match self.direction {
                 // hidden heart
@@ -1120,7 +1120,7 @@ impl NPC {
                 self.action_counter += 1;
                 if self.action_counter > 10 {
                     self.action_num = 2;
-                    state.sound_manager.play_sfx(101);
+                    state.sound_manager.play_sfx_at(101, self.x, self.y);
                 }
             }
             2 => {
@@ -1177,7 +1177,7 @@ impl NPC {
             11 => {
                 self.action_counter += 1;
                 if self.action_counter % 10 == 6 {
-                    state.sound_manager.play_sfx(107);
+                    state.sound_manager.play_sfx_at(107, self.x, self.y);
                 }
 
                 if self.flags.hit_left_wall() {
@@ -1187,7 +1187,7 @@ impl NPC {
 
                     state.quake_counter = 10;
                     state.quake_rumble_counter = 10;
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
 
                     let mut npc = NPC::create(4, &state.npc_table);
                     npc.cond.set_alive(true);
@@ -1229,7 +1229,7 @@ impl NPC {
             21 => {
                 self.action_counter += 1;
                 if self.action_counter % 10 == 6 {
-                    state.sound_manager.play_sfx(107);
+                    state.sound_manager.play_sfx_at(107, self.x, self.y);
                 }
 
                 if self.flags.hit_right_wall() {
@@ -1239,7 +1239,7 @@ impl NPC {
 
                     state.quake_counter = 10;
                     state.quake_rumble_counter = 10;
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
 
                     let mut npc = NPC::create(4, &state.npc_table);
                     npc.cond.set_alive(true);
@@ -1323,7 +1323,7 @@ impl NPC {
             11 => {
                 self.action_counter += 1;
                 if self.action_counter % 10 == 6 {
-                    state.sound_manager.play_sfx(107);
+                    state.sound_manager.play_sfx_at(107, self.x, self.y);
                 }
 
                 if self.flags.hit_top_wall() {
@@ -1333,7 +1333,7 @@ impl NPC {
 
                     state.quake_counter = 10;
                     state.quake_rumble_counter = 10;
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
 
                     let mut npc = NPC::create(4, &state.npc_table);
                     npc.cond.set_alive(true);
@@ -1375,7 +1375,7 @@ impl NPC {
             21 => {
                 self.action_counter += 1;
                 if self.action_counter % 10 == 6 {
-                    state.sound_manager.play_sfx(107);
+                    state.sound_manager.play_sfx_at(107, self.x, self.y);
                 }
 
                 if self.flags.hit_bottom_wall() {
@@ -1385,7 +1385,7 @@ impl NPC {
 
                     state.quake_counter = 10;
                     state.quake_rumble_counter = 10;
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
 
                     let mut npc = NPC::create(4, &state.npc_table);
                     npc.cond.set_alive(true);
@@ -1484,7 +1484,7 @@ impl NPC {
                     let _ = npc_list.spawn(0x100, npc.clone());
                 }
 
-                state.sound_manager.play_sfx(72);
+                state.sound_manager.play_sfx_at(72, self.x, self.y);
                 self.cond.set_alive(false);
             }
             20 => self.animate(10, 0, 1),
@@ -1761,7 +1761,7 @@ impl NPC {
                         npc.y = self.y + self.rng.range(-8..8) * 0x200;
                         let _ = npc_list.spawn(0x100, npc.clone());
 
-                        state.sound_manager.play_sfx(12);
+                        state.sound_manager.play_sfx_at(12, self.x, self.y);
                     }
                 }
             }
@@ -1886,7 +1886,7 @@ impl NPC {
 
                         state.quake_counter = 10;
                         state.quake_rumble_counter = 10;
-                        state.sound_manager.play_sfx(26);
+                        state.sound_manager.play_sfx_at(26, self.x, self.y);
                     }
 
                     self.action_num = 1;
@@ -1928,7 +1928,7 @@ impl NPC {
             self.create_xp_drop_custom(self.x, self.y, self.flag_num, state, npc_list);
             npc_list.create_death_smoke(self.x, self.y, self.display_bounds.right as usize, 8, state, &self.rng);
 
-            state.sound_manager.play_sfx(25);
+            state.sound_manager.play_sfx_at(25, self.x, self.y);
         }
 
         self.anim_rect = state.constants.npc.n253_experience_capsule[self.anim_num as usize];
@@ -2089,7 +2089,7 @@ impl NPC {
                     self.vel_y = -0x200;
                     self.action_num = 110;
                     self.npc_flags.set_ignore_solidity(true);
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                     state.quake_counter = 10;
                     state.quake_rumble_counter = 10;
 
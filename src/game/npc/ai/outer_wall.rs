@@ -164,7 +164,7 @@ impl NPC {
                     npc.vel_x = self.rng.range(2..12) * 0x80;
                     let _ = npc_list.spawn(0x100, npc);
 
-                    state.sound_manager.play_sfx(21);
+                    state.sound_manager.play_sfx_at(21, self.x, self.y);
                 }
 
                 if self.action_counter > 50 {
@@ -235,7 +235,7 @@ impl NPC {
 
             if self.flags.hit_anything() {
                 npc_list.create_death_smoke(self.x, self.y, self.display_bounds.right as usize, 4, state, &self.rng);
-                state.sound_manager.play_sfx(28);
+                state.sound_manager.play_sfx_at(28, self.x, self.y);
                 self.cond.set_alive(false);
             }
         }
@@ -272,7 +272,7 @@ impl NPC {
             15 => {
                 self.action_counter += 1;
                 if self.action_counter > 10 {
-                    state.sound_manager.play_sfx(102);
+                    state.sound_manager.play_sfx_at(102, self.x, self.y);
                     self.action_num = 20;
                 }
             }
@@ -356,7 +356,7 @@ impl NPC {
                     self.action_num = 12;
                     self.anim_num = 3;
                     self.vel_x = 0x700;
-                    state.sound_manager.play_sfx(6);
+                    state.sound_manager.play_sfx_at(6, self.x, self.y);
                 }
             }
             12 => {
@@ -114,7 +114,7 @@ impl NPC {
 
         if self.life <= 100 {
             npc_list.create_death_smoke(self.x, self.y, self.display_bounds.right as usize, 8, state, &self.rng);
-            state.sound_manager.play_sfx(25);
+            state.sound_manager.play_sfx_at(25, self.x, self.y);
             self.cond.set_alive(false);
 
             let mut npc = NPC::create(45, &state.npc_table);
@@ -228,7 +228,7 @@ impl NPC {
                 if abs(self.x - player.x) < 0x1000 && player.y > self.y && player.y < self.y + 0x1000 {
                     self.action_num = 2;
                     self.action_counter = 0;
-                    state.sound_manager.play_sfx(102);
+                    state.sound_manager.play_sfx_at(102, self.x, self.y);
                 }
 
                 self.x += (player.x - self.x).signum() * 0x400;
@@ -373,7 +373,7 @@ impl NPC {
                     npc.vel_y = (angle.sin() * -1024.0) as i32;
 
                     let _ = npc_list.spawn(0x100, npc);
-                    state.sound_manager.play_sfx(39);
+                    state.sound_manager.play_sfx_at(39, self.x, self.y);
                 }
 
                 if self.action_counter > 50 {
@@ -664,7 +664,7 @@ impl NPC {
                 self.action_counter += 1;
                 self.shock += self.action_counter & 0xff;
                 if self.action_counter > 50 {
-                    state.sound_manager.play_sfx(25);
+                    state.sound_manager.play_sfx_at(25, self.x, self.y);
                     self.vanish(state);
                     npc_list.create_death_smoke(
                         self.x,
@@ -1067,7 +1067,7 @@ impl NPC {
                 state.quake_counter = 20;
                 state.quake_rumble_counter = 20;
                 if self.action_counter % 8 == 0 {
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                 }
             }
             _ => (),
@@ -1282,7 +1282,7 @@ impl NPC {
                     self.animate(4, 2, 4);
 
                     if self.anim_num == 4 && self.anim_counter == 0 {
-                        state.sound_manager.play_sfx(105);
+                        state.sound_manager.play_sfx_at(105, self.x, self.y);
                     }
                 }
             }
@@ -1334,7 +1334,7 @@ impl NPC {
                 }
 
                 if self.anim_num == 4 && self.anim_counter == 0 {
-                    state.sound_manager.play_sfx(105);
+                    state.sound_manager.play_sfx_at(105, self.x, self.y);
                 }
             }
             120 => {
@@ -1583,7 +1583,7 @@ impl NPC {
                     npc.vel_y = (angle.sin() * -1024.0) as i32;
 
                     let _ = npc_list.spawn(0x180, npc);
-                    state.sound_manager.play_sfx(39);
+                    state.sound_manager.play_sfx_at(39, self.x, self.y);
                 }
                 if self.flags.hit_bottom_wall() {
                     self.action_num = 10;
@@ -1628,7 +1628,7 @@ impl NPC {
 
                 self.action_counter += 1;
                 if (self.action_counter & 7) == 0 {
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                 }
 
                 if self.direction != Direction::Left {
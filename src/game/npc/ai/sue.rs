@@ -61,7 +61,7 @@ impl NPC {
             }
             6 | 7 => {
                 if self.action_num == 6 {
-                    state.sound_manager.play_sfx(50);
+                    state.sound_manager.play_sfx_at(50, self.x, self.y);
                     self.action_counter = 0;
                     self.action_num = 7;
                     self.anim_num = 7;
@@ -74,7 +74,7 @@ impl NPC {
             }
             8 | 9 => {
                 if self.action_num == 8 {
-                    state.sound_manager.play_sfx(50);
+                    state.sound_manager.play_sfx_at(50, self.x, self.y);
                     self.action_counter = 0;
                     self.action_num = 9;
                     self.anim_num = 7;
@@ -291,7 +291,7 @@ impl NPC {
                     self.anim_counter = 0;
                     self.x += 0xc00;
                     self.target_x = self.x;
-                    state.sound_manager.play_sfx(29);
+                    state.sound_manager.play_sfx_at(29, self.x, self.y);
                 }
 
                 self.action_counter += 1;
@@ -306,7 +306,7 @@ impl NPC {
                     self.action_num = 4;
                     self.action_counter = 0;
                     self.anim_num = 1;
-                    state.sound_manager.play_sfx(23);
+                    state.sound_manager.play_sfx_at(23, self.x, self.y);
                 }
             }
             _ => (),
@@ -351,7 +351,7 @@ impl NPC {
                     self.y -= 0x800;
                     self.action_counter3 = self.life;
 
-                    state.sound_manager.play_sfx(29);
+                    state.sound_manager.play_sfx_at(29, self.x, self.y);
                 }
 
                 self.action_counter += 1;
@@ -504,7 +504,7 @@ impl NPC {
                 self.animate(1, 4, 7);
 
                 if self.action_counter % 5 == 1 {
-                    state.sound_manager.play_sfx(109);
+                    state.sound_manager.play_sfx_at(109, self.x, self.y);
                 }
             }
             40 | 41 => {
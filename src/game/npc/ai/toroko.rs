@@ -128,7 +128,7 @@ impl NPC {
                 self.anim_num = 6;
                 self.vel_y = -0x400;
 
-                state.sound_manager.play_sfx(50);
+                state.sound_manager.play_sfx_at(50, self.x, self.y);
 
                 if self.direction == Direction::Left {
                     self.vel_x = -0x100;
@@ -408,7 +408,7 @@ impl NPC {
                     self.action_num = 26;
                     self.action_counter = 0;
                     self.anim_num = 2;
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                     state.quake_counter = 20;
                     state.quake_rumble_counter = 20;
                 }
@@ -478,7 +478,7 @@ impl NPC {
                     self.action_num = 102;
                     self.action_counter = 0;
                     self.anim_num = 2;
-                    state.sound_manager.play_sfx(26);
+                    state.sound_manager.play_sfx_at(26, self.x, self.y);
                     state.quake_counter = 20;
                     state.quake_rumble_counter = 20;
                 }
@@ -535,7 +535,7 @@ impl NPC {
                     self.action_num = 141;
                     self.action_counter = 0;
                     self.anim_num = 12;
-                    state.sound_manager.play_sfx(29);
+                    state.sound_manager.play_sfx_at(29, self.x, self.y);
                 }
 
                 self.anim_num += 1;
@@ -601,7 +601,7 @@ impl NPC {
                         self.action_counter = 0;
 
                         state.create_caret(self.x, self.y, CaretType::ProjectileDissipation, Direction::Left);
-                        state.sound_manager.play_sfx(0xc);
+                        state.sound_manager.play_sfx_at(0xc, self.x, self.y);
 
                         let mut npc = NPC::create(4, &state.npc_table);
                         npc.cond.set_alive(true);
@@ -669,7 +669,7 @@ impl NPC {
 
                     self.vel_x = (angle.cos() * -2048.0) as i32;
                     self.vel_y = (angle.sin() * -2048.0) as i32;
-                    state.sound_manager.play_sfx(0x27);
+                    state.sound_manager.play_sfx_at(0x27, self.x, self.y);
                 }
             }
         }
@@ -732,7 +732,7 @@ impl NPC {
                     self.action_num = 21;
                     self.action_counter = 0;
                     self.vel_x = 0;
-                    state.sound_manager.play_sfx(23);
+                    state.sound_manager.play_sfx_at(23, self.x, self.y);
                 }
             }
             21 => {
@@ -762,7 +762,7 @@ impl NPC {
                     self.anim_num = 0;
                     self.anim_counter = 0;
                     self.target_x = self.x;
-                    state.sound_manager.play_sfx(29);
+                    state.sound_manager.play_sfx_at(29, self.x, self.y);
                 }
                 self.action_counter += 1;
                 if self.action_counter == 64 {
@@ -783,7 +783,7 @@ impl NPC {
                     self.action_num = 4;
                     self.action_counter = 0;
                     self.anim_num = 4;
-                    state.sound_manager.play_sfx(23);
+                    state.sound_manager.play_sfx_at(23, self.x, self.y);
                 }
             }
             10 | 11 => {
@@ -168,10 +168,10 @@ impl NPC {
                     self.anim_num = 1;
                     self.vel_y = -0x5FF;
                     self.action_num = 20;
-                    state.sound_manager.play_sfx(30);
+                    state.sound_manager.play_sfx_at(30, self.x, self.y);
 
                     if player.cond.hidden() {
-                        state.sound_manager.play_sfx(30); // ???
+                        state.sound_manager.play_sfx_at(30, self.x, self.y); // ???
                     }
                 }
             }
@@ -197,7 +197,7 @@ impl NPC {
                     self.vel_x = 0;
 
                     if player.cond.hidden() {
-                        state.sound_manager.play_sfx(23); // ???
+                        state.sound_manager.play_sfx_at(23, self.x, self.y); // ???
                     }
                 }
             }
@@ -243,7 +243,7 @@ impl NPC {
                 self.vel_x = 0x100 * self.direction.opposite().vector_x();
                 self.vel_y = -0x200;
 
-                state.sound_manager.play_sfx(53);
+                state.sound_manager.play_sfx_at(53, self.x, self.y);
             }
             1 => {
                 if self.flags.hit_bottom_wall() {
@@ -313,7 +313,7 @@ impl NPC {
 
                 if self.flags.hit_anything() {
                     state.create_caret(self.x, self.y, CaretType::ProjectileDissipation, Direction::Right);
-                    state.sound_manager.play_sfx(28);
+                    state.sound_manager.play_sfx_at(28, self.x, self.y);
                     self.cond.set_alive(false);
                 }
             }
@@ -324,7 +324,7 @@ impl NPC {
 
         if self.action_counter2 >= 300 {
             state.create_caret(self.x, self.y, CaretType::ProjectileDissipation, Direction::Right);
-            state.sound_manager.play_sfx(28);
+            state.sound_manager.play_sfx_at(28, self.x, self.y);
             self.cond.set_alive(false);
         }
 
@@ -512,7 +512,7 @@ impl NPC {
 
             let _ = npc_list.spawn(0x100, npc.clone());
 
-            state.sound_manager.play_sfx(72);
+            state.sound_manager.play_sfx_at(72, self.x, self.y);
             npc_list.create_death_smoke(self.x, self.y, 0, 1, state, &self.rng);
             self.cond.set_alive(false);
         }
@@ -533,7 +533,7 @@ impl NPC {
         match self.action_num {
             0 | 1 => {
                 if self.action_num == 0 {
-                    state.sound_manager.play_sfx(29);
+                    state.sound_manager.play_sfx_at(29, self.x, self.y);
                     self.x -= 0x800;
                     self.y += 0x1400;
                     self.action_num = 1;
@@ -576,7 +576,7 @@ impl NPC {
                     npc.vel_x = (angle.cos() * -1536.0) as i32;
                     npc.vel_y = (angle.sin() * -1536.0) as i32;
                     let _ = npc_list.spawn(0x100, npc);
-                    state.sound_manager.play_sfx(33);
+                    state.sound_manager.play_sfx_at(33, self.x, self.y);
                 }
 
                 if self.action_counter > 100 {
@@ -596,7 +596,7 @@ impl NPC {
 
                 self.action_counter += 1;
                 if self.action_counter % 20 == 0 {
-                    state.sound_manager.play_sfx(52);
+                    state.sound_manager.play_sfx_at(52, self.x, self.y);
                 }
 
                 npc_list.kill_npcs_by_type(369, true, state);
@@ -631,7 +631,7 @@ impl NPC {
                         npc.direction = Direction::Right;
 
                         let _ = npc_list.spawn(0x100, npc);
-                        state.sound_manager.play_sfx(4);
+                        state.sound_manager.play_sfx_at(4, self.x, self.y);
                     }
                 }
                 1 => {
@@ -670,7 +670,7 @@ impl NPC {
                         self.action_counter2 = 4;
                         self.npc_flags.set_shootable(true);
 
-                        state.sound_manager.play_sfx(4);
+                        state.sound_manager.play_sfx_at(4, self.x, self.y);
                     }
                 }
                 4 => {
@@ -683,7 +683,7 @@ impl NPC {
                         npc.direction = Direction::Right;
 
                         let _ = npc_list.spawn(0x100, npc);
-                        state.sound_manager.play_sfx(4);
+                        state.sound_manager.play_sfx_at(4, self.x, self.y);
                     }
                 }
                 5 => {
@@ -697,7 +697,7 @@ impl NPC {
                         npc.direction = Direction::Right;
 
                         let _ = npc_list.spawn(0x100, npc);
-                        state.sound_manager.play_sfx(4);
+                        state.sound_manager.play_sfx_at(4, self.x, self.y);
                     }
                 }
                 6 => {
@@ -761,12 +761,12 @@ impl NPC {
                 if self.action_num == 0 {
                     self.anim_num = 0;
                     self.action_num = 1;
-                    self.event_num = 450;
+                    npc_list.set_event_num(self, 450);
                 }
 
                 if self.action_num == 10 || self.flags.hit_bottom_wall() {
                     if self.action_num != 10 {
-                        state.sound_manager.play_sfx(23);
+                        state.sound_manager.play_sfx_at(23, self.x, self.y);
                         self.action_counter = 0;
                         self.anim_num = 3;
                         self.action_num = 10;
@@ -846,7 +846,7 @@ impl NPC {
                     self.anim_num = 4;
                     self.damage = 0;
                     self.action_num = 31;
-                    state.sound_manager.play_sfx(30);
+                    state.sound_manager.play_sfx_at(30, self.x, self.y);
                 }
             }
             31 => {
@@ -879,7 +879,7 @@ impl NPC {
 
                     self.action_counter += 1;
                     if self.action_counter > 100 {
-                        state.sound_manager.play_sfx(25);
+                        state.sound_manager.play_sfx_at(25, self.x, self.y);
 
                         player.x = self.x;
                         player.y = self.y;
@@ -907,7 +907,7 @@ impl NPC {
                 }
             }
             50 => {
-                state.sound_manager.play_sfx(25);
+                state.sound_manager.play_sfx_at(25, self.x, self.y);
 
                 player.x = self.x;
                 player.y = self.y;
@@ -944,7 +944,7 @@ impl NPC {
                 self.npc_type = 363;
                 self.action_num = 0;
                 self.damage = 0;
-                self.event_num = 451;
+                npc_list.set_event_num(self, 451);
             }
             _ => {}
         }
@@ -994,7 +994,7 @@ impl NPC {
             self.npc_type = 363;
             self.action_num = 0;
             self.damage = 0;
-            self.event_num = 451;
+            npc_list.set_event_num(self, 451);
             self.action_counter3 = 0;
         }
 
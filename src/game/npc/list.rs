@@ -1,4 +1,5 @@
-use std::cell::{Cell, UnsafeCell};
+use std::cell::{Cell, RefCell, UnsafeCell};
+use std::collections::HashMap;
 use std::mem::{MaybeUninit, transmute};
 
 use crate::framework::error::{GameError, GameResult};
@@ -14,6 +15,11 @@ pub struct NPCList {
     // from theoretically performing some optimizations that might break the code.
     npcs: Box<UnsafeCell<[NPC; NPC_LIST_MAX_CAP]>>,
     max_npc: Cell<u16>,
+    /// Maps event numbers to sorted IDs of the NPC slots carrying them. Kept up to date by `spawn`, `spawn_at_slot`
+    /// and `set_event_num`, dead NPCs stay indexed until their slot is reused and are skipped on lookup.
+    event_index: RefCell<HashMap<u16, Vec<u16>>>,
+    /// Maximum number of alive NPCs of given type that can be added with `spawn`.
+    spawn_limits: RefCell<HashMap<u16, u16>>,
     seed: i32,
 }
 
@@ -32,6 +38,8 @@ impl NPCList {
                 transmute(parts_uninit)
             })),
             max_npc: Cell::new(0),
+            event_index: RefCell::new(HashMap::new()),
+            spawn_limits: RefCell::new(HashMap::new()),
             seed: 0,
         };
 
@@ -56,6 +64,13 @@ impl NPCList {
             return Err(GameError::InvalidValue("NPC ID is out of bounds".to_string()));
         }
 
+        if let Some(&limit) = self.spawn_limits.borrow().get(&npc.npc_type) {
+            if self.iter_alive().filter(|n| n.npc_type == npc.npc_type).count() >= limit as usize {
+                log::warn!("Refusing to spawn NPC type {}, limit of {} reached.", npc.npc_type, limit);
+                return Err(GameError::InvalidValue("NPC spawn limit reached!".to_string()));
+            }
+        }
+
         for id in min_id..(npc_len as u16) {
             let npc_ref = unsafe { self.npcs_mut().get_unchecked_mut(id as usize) };
 
@@ -68,7 +83,10 @@ impl NPCList {
 
                 npc.init_rng(self.seed);
 
+                self.unindex_event(id, npc_ref.event_num);
+                let event_num = npc.event_num;
                 *npc_ref = npc;
+                self.index_event(id, event_num);
 
                 if self.max_npc.get() <= id {
                     self.max_npc.replace(id + 1);
@@ -97,10 +115,13 @@ impl NPCList {
 
         npc.init_rng(self.seed);
 
+        let event_num = npc.event_num;
         unsafe {
             let npc_ref = self.npcs_mut().get_unchecked_mut(id as usize);
+            self.unindex_event(id, npc_ref.event_num);
             *npc_ref = npc;
         }
+        self.index_event(id, event_num);
 
         if self.max_npc.get() <= id {
             self.max_npc.replace(id + 1);
@@ -124,6 +145,20 @@ impl NPCList {
         NPCListMutableAliveIterator::new(self)
     }
 
+    /// Returns an iterator over alive NPCs with specified event number, in ascending ID order.
+    /// NPCs may be spawned or have their event number changed while iterating.
+    pub fn entities_with_event(&self, event_num: u16) -> NPCListEventIterator {
+        NPCListEventIterator { next_id: 0, event_num, map: self }
+    }
+
+    /// Changes the event number of an NPC from this list. Event numbers must not be assigned directly,
+    /// otherwise `entities_with_event` won't find the NPC under its new number.
+    pub fn set_event_num(&self, npc: &mut NPC, event_num: u16) {
+        self.unindex_event(npc.id, npc.event_num);
+        npc.event_num = event_num;
+        self.index_event(npc.id, event_num);
+    }
+
     /// Removes all NPCs from this list and resets it's capacity.
     pub fn clear(&self) {
         for (idx, npc) in self.iter_alive().enumerate() {
@@ -132,6 +167,13 @@ impl NPCList {
         }
 
         self.max_npc.replace(0);
+        self.event_index.borrow_mut().clear();
+    }
+
+    /// Replaces the per-type limits of alive NPCs enforced by `spawn`. NPCs inserted with `spawn_at_slot`
+    /// (the ones placed in map files) aren't affected.
+    pub fn set_spawn_limits(&self, limits: &HashMap<u16, u16>) {
+        self.spawn_limits.replace(limits.clone());
     }
 
     /// Returns current capacity of this NPC list.
@@ -144,6 +186,31 @@ impl NPCList {
         NPC_LIST_MAX_CAP as u16
     }
 
+    fn index_event(&self, id: u16, event_num: u16) {
+        let mut event_index = self.event_index.borrow_mut();
+        let ids = event_index.entry(event_num).or_insert_with(Vec::new);
+
+        if let Err(pos) = ids.binary_search(&id) {
+            ids.insert(pos, id);
+        }
+    }
+
+    fn unindex_event(&self, id: u16, event_num: u16) {
+        if let Some(ids) = self.event_index.borrow_mut().get_mut(&event_num) {
+            if let Ok(pos) = ids.binary_search(&id) {
+                ids.remove(pos);
+            }
+        }
+    }
+
+    /// Returns the lowest ID not below `min_id` indexed under given event number.
+    fn next_with_event(&self, event_num: u16, min_id: u16) -> Option<u16> {
+        let event_index = self.event_index.borrow();
+        let ids = event_index.get(&event_num)?;
+
+        ids.get(ids.partition_point(|&id| id < min_id)).copied()
+    }
+
     unsafe fn npcs<'a: 'b, 'b>(&'a self) -> &'b [NPC; NPC_LIST_MAX_CAP] {
         &*self.npcs.get()
     }
@@ -215,6 +282,30 @@ impl<'a> Iterator for NPCListMutableAliveIterator<'a> {
     }
 }
 
+pub struct NPCListEventIterator<'a> {
+    next_id: u16,
+    event_num: u16,
+    map: &'a NPCList,
+}
+
+impl<'a> Iterator for NPCListEventIterator<'a> {
+    type Item = &'a mut NPC;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // the index is only borrowed for the lookup, the caller is free to spawn NPCs in between
+        while let Some(id) = self.map.next_with_event(self.event_num, self.next_id) {
+            self.next_id = id + 1;
+
+            let npc = unsafe { self.map.npcs_mut().get_unchecked_mut(id as usize) };
+            if npc.cond.alive() && npc.event_num == self.event_num {
+                return Some(npc);
+            }
+        }
+
+        None
+    }
+}
+
 #[test]
 pub fn test_npc_list() -> GameResult {
     impl NPC {
@@ -274,3 +365,122 @@ pub fn test_npc_list() -> GameResult {
 
     Ok(())
 }
+
+#[test]
+pub fn test_npc_list_event_index() -> GameResult {
+    let mut npc = NPC::empty();
+    npc.cond.set_alive(true);
+    npc.event_num = 300;
+
+    let map = Box::new(NPCList::new());
+    map.spawn(0, npc.clone())?;
+    map.spawn(0, npc.clone())?;
+    map.spawn_at_slot(10, npc.clone())?;
+
+    assert_eq!(map.entities_with_event(300).map(|npc| npc.id).collect::<Vec<_>>(), vec![0, 1, 10]);
+    assert_eq!(map.entities_with_event(301).count(), 0);
+
+    map.set_event_num(map.get_npc(1).unwrap(), 301);
+    map.get_npc(10).unwrap().cond.set_alive(false);
+
+    assert_eq!(map.entities_with_event(300).map(|npc| npc.id).collect::<Vec<_>>(), vec![0]);
+    assert_eq!(map.entities_with_event(301).map(|npc| npc.id).collect::<Vec<_>>(), vec![1]);
+
+    // reusing the slot of a dead NPC drops its old event number from the index
+    npc.event_num = 302;
+    map.spawn_at_slot(10, npc.clone())?;
+    assert_eq!(map.entities_with_event(300).map(|npc| npc.id).collect::<Vec<_>>(), vec![0]);
+    assert_eq!(map.entities_with_event(302).map(|npc| npc.id).collect::<Vec<_>>(), vec![10]);
+
+    Ok(())
+}
+
+#[test]
+pub fn test_npc_list_event_change_while_iterating() -> GameResult {
+    let mut npc = NPC::empty();
+    npc.cond.set_alive(true);
+    npc.event_num = 450;
+
+    let map = Box::new(NPCList::new());
+    for _ in 0..3 {
+        map.spawn(0, npc.clone())?;
+    }
+
+    // like <CNP/<INP running the AI of a changed NPC, which may give it a new event number and spawn others
+    let mut child = npc.clone();
+    child.event_num = 0;
+    for npc_ref in map.entities_with_event(450) {
+        npc_ref.npc_type = 363;
+        map.set_event_num(npc_ref, 451);
+        map.spawn(0x100, child.clone())?;
+    }
+
+    assert_eq!(map.entities_with_event(450).count(), 0);
+    assert_eq!(map.entities_with_event(451).map(|npc| npc.id).collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert_eq!(map.entities_with_event(0).map(|npc| npc.id).collect::<Vec<_>>(), vec![0x100, 0x101, 0x102]);
+
+    Ok(())
+}
+
+#[test]
+pub fn test_npc_list_spawn_limits() -> GameResult {
+    let mut npc = NPC::empty();
+    npc.cond.set_alive(true);
+    npc.npc_type = 12;
+
+    let map = Box::new(NPCList::new());
+    map.set_spawn_limits(&HashMap::from([(12, 4)]));
+
+    for _ in 0..10 {
+        let _ = map.spawn(0x100, npc.clone());
+    }
+    assert_eq!(map.iter_alive().count(), 4);
+
+    // other types aren't limited
+    npc.npc_type = 13;
+    for _ in 0..10 {
+        map.spawn(0x100, npc.clone())?;
+    }
+    assert_eq!(map.iter_alive().count(), 14);
+
+    // freed slots are reused once the count drops below the limit
+    map.get_npc(0x100).unwrap().cond.set_alive(false);
+    npc.npc_type = 12;
+    map.spawn(0x100, npc.clone())?;
+    assert!(map.spawn(0x100, npc.clone()).is_err());
+    assert_eq!(map.get_npc(0x100).unwrap().npc_type, 12);
+    assert_eq!(map.current_capacity(), 0x100 + 14);
+
+    Ok(())
+}
+
+#[test]
+pub fn test_npc_list_remove_enemies() -> GameResult {
+    use crate::game::HeadlessRunner;
+
+    let (_, mut state) = HeadlessRunner::bare_state();
+
+    let mut bystander = NPC::empty();
+    bystander.cond.set_alive(true);
+    bystander.event_num = 500;
+
+    let mut enemy = bystander.clone();
+    enemy.npc_flags.set_shootable(true);
+
+    let map = Box::new(NPCList::new());
+    map.spawn(0x100, bystander.clone())?;
+    for flag_num in 700..703 {
+        enemy.flag_num = flag_num;
+        map.spawn(0x100, enemy.clone())?;
+    }
+
+    // the whole wave goes at once, so the caller flashes and plays the sound a single time
+    assert_eq!(map.remove_enemies(&mut state), 3);
+    assert_eq!(map.iter_alive().map(|npc| npc.id).collect::<Vec<_>>(), vec![0x100]);
+    assert!((700..703).all(|flag_num| state.get_flag(flag_num)));
+
+    // nothing left to clear, so no second flash or sound either
+    assert_eq!(map.remove_enemies(&mut state), 0);
+
+    Ok(())
+}
@@ -219,6 +219,42 @@ impl NPC {
         Ok(())
     }
 
+    /// Whether the sprite can share a batch with other NPCs using the same spritesheet.
+    pub fn is_batchable(&self, state: &SharedGameState) -> bool {
+        !(self.is_sue() && state.more_rust)
+    }
+
+    /// Adds the sprite to its spritesheet batch without submitting it, see [`NPC::is_batchable`].
+    pub fn draw_into_batch(&self, state: &mut SharedGameState, ctx: &mut Context, frame: &Frame) -> GameResult {
+        if !self.cond.alive() || self.cond.hidden() {
+            return Ok(());
+        }
+
+        let (final_x, final_y) = self.screen_position(state, frame);
+        let texture_ref = state.npc_table.get_texture_ref(self.spritesheet_id);
+        let batch = state.texture_set.get_or_load_batch(ctx, &state.constants, &*texture_ref)?;
+        batch.add_rect(final_x, final_y, &self.anim_rect);
+
+        Ok(())
+    }
+
+    fn screen_position(&self, state: &SharedGameState, frame: &Frame) -> (f32, f32) {
+        let off_x =
+            if self.direction == Direction::Left { self.display_bounds.left } else { self.display_bounds.right } as i32;
+        let shock = if self.shock > 0 { (2 * ((self.shock as i32 / 2) % 2) - 1) as f32 } else { 0.0 };
+
+        let (frame_x, frame_y) = frame.xy_interpolated(state.frame_time);
+
+        let final_x = interpolate_fix9_scale(self.prev_x - off_x, self.x - off_x, state.frame_time) + shock - frame_x;
+        let final_y = interpolate_fix9_scale(
+            self.prev_y - self.display_bounds.top as i32,
+            self.y - self.display_bounds.top as i32,
+            state.frame_time,
+        ) - frame_y;
+
+        (final_x, final_y)
+    }
+
     fn is_sue(&self) -> bool {
         [42, 92, 280, 284].contains(&self.npc_type)
     }
@@ -653,18 +689,7 @@ impl GameEntity<([&mut Player; 2], &NPCList, &mut Stage, &mut BulletManager, &mu
 
         let batch = state.texture_set.get_or_load_batch(ctx, &state.constants, &*texture_ref)?;
 
-        let off_x =
-            if self.direction == Direction::Left { self.display_bounds.left } else { self.display_bounds.right } as i32;
-        let shock = if self.shock > 0 { (2 * ((self.shock as i32 / 2) % 2) - 1) as f32 } else { 0.0 };
-
-        let (frame_x, frame_y) = frame.xy_interpolated(state.frame_time);
-
-        let final_x = interpolate_fix9_scale(self.prev_x - off_x, self.x - off_x, state.frame_time) + shock - frame_x;
-        let final_y = interpolate_fix9_scale(
-            self.prev_y - self.display_bounds.top as i32,
-            self.y - self.display_bounds.top as i32,
-            state.frame_time,
-        ) - frame_y;
+        let (final_x, final_y) = self.screen_position(state, frame);
 
         if self.is_sue() && state.more_rust {
             // tint sue blue
@@ -250,7 +250,7 @@ impl NPCList {
     /// Returns true if at least one NPC with specified event is alive.
     #[inline]
     pub fn is_alive_by_event(&self, event_num: u16) -> bool {
-        self.iter_alive().any(|npc| npc.event_num == event_num)
+        self.entities_with_event(event_num).next().is_some()
     }
 
     /// Deletes NPCs with specified type.
@@ -346,11 +346,9 @@ impl NPCList {
 
     /// Removes NPCs whose event number matches the provided one.
     pub fn remove_by_event(&self, event_num: u16, state: &mut SharedGameState) {
-        for npc in self.iter_alive() {
-            if npc.event_num == event_num {
-                npc.cond.set_alive(false);
-                state.set_flag(npc.flag_num as usize, true);
-            }
+        for npc in self.entities_with_event(event_num) {
+            npc.cond.set_alive(false);
+            state.set_flag(npc.flag_num as usize, true);
         }
     }
 
@@ -371,6 +369,31 @@ impl NPCList {
         }
     }
 
+    /// Removes all shootable NPCs (and creates a smoke effect), leaving bystanders alone.
+    /// Returns the number of removed NPCs.
+    pub fn remove_enemies(&self, state: &mut SharedGameState) -> usize {
+        let mut count = 0;
+
+        for npc in self.iter_alive() {
+            if !npc.npc_flags.shootable() {
+                continue;
+            }
+
+            npc.cond.set_alive(false);
+            state.set_flag(npc.flag_num as usize, true);
+            count += 1;
+
+            match npc.size {
+                1 => self.create_death_smoke(npc.x, npc.y, npc.display_bounds.right as usize, 3, state, &npc.rng),
+                2 => self.create_death_smoke(npc.x, npc.y, npc.display_bounds.right as usize, 7, state, &npc.rng),
+                3 => self.create_death_smoke(npc.x, npc.y, npc.display_bounds.right as usize, 12, state, &npc.rng),
+                _ => {}
+            };
+        }
+
+        count
+    }
+
     /// Creates NPC death smoke diffusing in random directions.
     #[inline]
     pub fn create_death_smoke(
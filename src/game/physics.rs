@@ -119,6 +119,33 @@ pub trait PhysicalEntity {
         false
     }
 
+    /// Returns the tile the entity is centered on and how many of the `OFFSETS` around it `tick_map_collisions`
+    /// tests, nearest first.
+    fn collision_tiles(&self, tile_size: TileSize) -> (i32, i32, usize) {
+        let hit_rect_size = self.hit_rect_size().clamp(1, 4);
+        let count = if tile_size == TileSize::Tile8x8 {
+            4 * hit_rect_size * hit_rect_size
+        } else {
+            hit_rect_size * hit_rect_size
+        };
+
+        let tile_size = tile_size.as_int() * 0x200;
+
+        ((self.x() + self.offset_x()) / tile_size, (self.y() + self.offset_y()) / tile_size, count)
+    }
+
+    /// The head, feet, left and right side points of the hit rect, in that order.
+    fn probe_points(&self) -> [(i32, i32); 4] {
+        let bounds = self.hit_bounds();
+
+        [
+            (self.x(), self.y() - bounds.top as i32),
+            (self.x(), self.y() + bounds.bottom as i32),
+            (self.x() - bounds.left as i32, self.y()),
+            (self.x() + bounds.right as i32, self.y()),
+        ]
+    }
+
     fn test_block_hit(&mut self, state: &mut SharedGameState, x: i32, y: i32) {
         let bounds_x = if self.is_player() { 0x600 } else { 0x600 };
         let bounds_top = if self.is_player() { 0x800 } else { 0x600 };
@@ -735,18 +762,12 @@ pub trait PhysicalEntity {
     }
 
     fn tick_map_collisions(&mut self, state: &mut SharedGameState, _npc_list: &NPCList, stage: &mut Stage) {
-        let hit_rect_size = self.hit_rect_size().clamp(1, 4);
-        let hit_rect_size = if state.tile_size == TileSize::Tile8x8 {
-            4 * hit_rect_size * hit_rect_size
-        } else {
-            hit_rect_size * hit_rect_size
-        };
-
-        let tile_size = state.tile_size.as_int() * 0x200;
-        let x = (self.x() + self.offset_x()) / tile_size;
-        let y = (self.y() + self.offset_y()) / tile_size;
+        let (x, y, hit_rect_size) = self.collision_tiles(state.tile_size);
 
         self.flags().0 = 0;
+        // Tiles are tested one by one in `OFFSETS` order, nearest first, and each test starts from the position left by
+        // the previous ones. Blocks push the entity out along either axis while slopes only ever move it vertically, so
+        // walking up a slope into a solid block stops at the block instead of climbing over it, whichever comes first.
         for (idx, &(ox, oy)) in OFFSETS.iter().enumerate() {
             if idx == hit_rect_size {
                 break;
@@ -909,3 +930,173 @@ pub trait PhysicalEntity {
         }
     }
 }
+
+#[cfg(test)]
+struct TestEntity {
+    x: i32,
+    y: i32,
+    vel_x: i32,
+    vel_y: i32,
+    hit_bounds: Rect<u32>,
+    cond: Condition,
+    flags: Flag,
+}
+
+#[cfg(test)]
+impl TestEntity {
+    fn new(x: i32, y: i32, vel_x: i32, vel_y: i32) -> TestEntity {
+        TestEntity {
+            x,
+            y,
+            vel_x,
+            vel_y,
+            hit_bounds: Rect::new(0xa00, 0x1000, 0xa00, 0x1000),
+            cond: Condition(0x80),
+            flags: Flag(0),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PhysicalEntity for TestEntity {
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn vel_x(&self) -> i32 {
+        self.vel_x
+    }
+
+    fn vel_y(&self) -> i32 {
+        self.vel_y
+    }
+
+    fn hit_rect_size(&self) -> usize {
+        2
+    }
+
+    fn hit_bounds(&self) -> &Rect<u32> {
+        &self.hit_bounds
+    }
+
+    fn display_bounds(&self) -> &Rect<u32> {
+        &self.hit_bounds
+    }
+
+    fn set_x(&mut self, x: i32) {
+        self.x = x;
+    }
+
+    fn set_y(&mut self, y: i32) {
+        self.y = y;
+    }
+
+    fn set_vel_x(&mut self, vel_x: i32) {
+        self.vel_x = vel_x;
+    }
+
+    fn set_vel_y(&mut self, vel_y: i32) {
+        self.vel_y = vel_y;
+    }
+
+    fn cond(&mut self) -> &mut Condition {
+        &mut self.cond
+    }
+
+    fn flags(&mut self) -> &mut Flag {
+        &mut self.flags
+    }
+
+    fn direction(&self) -> Direction {
+        Direction::Right
+    }
+
+    fn is_player(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+fn run_map_collisions(entity: &mut TestEntity, tiles: &[(usize, usize, u8)]) {
+    use crate::game::HeadlessRunner;
+
+    let (_, mut state) = HeadlessRunner::bare_state();
+    state.water_level = i32::MAX;
+
+    let mut stage = Stage::blank(4, 4);
+    for &(x, y, attr) in tiles {
+        stage.map.tiles[y * 4 + x] = attr;
+    }
+
+    entity.tick_map_collisions(&mut state, &NPCList::new(), &mut stage);
+}
+
+#[test]
+fn test_map_collisions_floor() {
+    // falling into a flat floor made of two solid blocks
+    let mut entity = TestEntity::new(0x2800, 0x2100, 0, 0x300);
+    run_map_collisions(&mut entity, &[(1, 2, 0x41), (2, 2, 0x41)]);
+
+    assert_eq!(entity.y, 0x2000);
+    assert_eq!(entity.vel_y, 0);
+    assert!(entity.flags.hit_bottom_wall());
+    assert!(!entity.flags.hit_left_wall() && !entity.flags.hit_right_wall());
+}
+
+#[test]
+fn test_map_collisions_slope_into_wall() {
+    // a slope rising to the right from the middle of its tile to the top, ending at a two blocks high wall
+    let layout = [(1, 2, 0x57), (2, 1, 0x41), (2, 2, 0x41)];
+
+    // walking up the slope
+    let mut entity = TestEntity::new(0x2400, 0x2680, 0x200, 0);
+    run_map_collisions(&mut entity, &layout);
+    assert_eq!(entity.x, 0x2400);
+    assert_eq!(entity.y, 0x2600);
+    assert!(entity.flags.hit_bottom_wall() && entity.flags.hit_right_slope());
+    assert!(!entity.flags.hit_right_wall());
+
+    // moving into the wall; it's pushed back and stays on the slope at its new position instead of popping up
+    let mut entity = TestEntity::new(0x2700, 0x2580, 0x200, 0);
+    run_map_collisions(&mut entity, &layout);
+    assert_eq!(entity.x, 0x2600);
+    assert_eq!(entity.y, 0x2500);
+    assert_eq!(entity.vel_x, 0);
+    assert!(entity.flags.hit_right_wall());
+    assert!(entity.flags.hit_bottom_wall() && entity.flags.hit_right_slope());
+}
+
+#[test]
+fn test_probe_points_follow_slope() {
+    // the same slope as above, rising half a tile to the right over the tile at (1, 2)
+    let slope_surface = |x: i32| 2 * 0x2000 - (x - 0x2000) / 2 - 0x800;
+
+    for x in [0x1800, 0x2000, 0x2400] {
+        let mut entity = TestEntity::new(x, slope_surface(x) - 0x1000 + 0x80, 0, 0x100);
+        run_map_collisions(&mut entity, &[(1, 2, 0x57)]);
+
+        // the slope is one of the tested tiles, and the feet end up on its surface
+        let (tile_x, tile_y, count) = entity.collision_tiles(TileSize::Tile16x16);
+        assert!(OFFSETS[..count].contains(&(1 - tile_x, 2 - tile_y)));
+
+        let [head, feet, left, right] = entity.probe_points();
+        assert_eq!(feet, (x, slope_surface(x)));
+        assert_eq!(head, (x, slope_surface(x) - 0x2000));
+        assert_eq!((left, right), ((x - 0xa00, entity.y), (x + 0xa00, entity.y)));
+    }
+}
+
+#[test]
+fn test_map_collisions_water() {
+    let mut entity = TestEntity::new(0x2100, 0x2100, 0, 0);
+    run_map_collisions(&mut entity, &[(1, 1, 0x60)]);
+    assert!(entity.flags.in_water());
+
+    let mut entity = TestEntity::new(0x2100, 0x2100, 0, 0);
+    run_map_collisions(&mut entity, &[(3, 3, 0x60)]);
+    assert!(!entity.flags.in_water());
+}
@@ -3,7 +3,7 @@ use std::clone::Clone;
 use num_derive::FromPrimitive;
 use num_traits::clamp;
 
-use crate::common::{interpolate_fix9_scale, Condition, Direction, Equipment, Flag, Rect};
+use crate::common::{interpolate_fix9_scale, Condition, Direction, Equipment, FadeState, Flag, Rect};
 use crate::components::number_popup::NumberPopup;
 use crate::entity::GameEntity;
 use crate::framework::context::Context;
@@ -74,6 +74,46 @@ impl DogStack {
     }
 }
 
+/// Timers for jump input buffering and coyote time, see `PlayerConsts::jump_buffer_ticks` and `coyote_ticks`.
+#[derive(Clone, Copy, Default)]
+struct JumpAssist {
+    buffer: u8,
+    coyote: u8,
+}
+
+impl JumpAssist {
+    /// A single press is remembered for a few ticks, holding the button doesn't refill it.
+    pub fn update(&mut self, jump_pressed: bool, on_ground: bool, buffer_ticks: u8, coyote_ticks: u8) {
+        if jump_pressed {
+            self.buffer = buffer_ticks.saturating_add(1);
+        }
+
+        if on_ground {
+            self.coyote = coyote_ticks.saturating_add(1);
+        }
+    }
+
+    /// Whether the player stood on the ground recently enough to still jump off it.
+    pub fn grounded(&self) -> bool {
+        self.coyote > 0
+    }
+
+    /// Performs a buffered jump if the player can still jump, consuming both timers.
+    pub fn take_jump(&mut self) -> bool {
+        if self.buffer > 0 && self.coyote > 0 {
+            *self = JumpAssist::default();
+            return true;
+        }
+
+        false
+    }
+
+    pub fn tick(&mut self) {
+        self.buffer = self.buffer.saturating_sub(1);
+        self.coyote = self.coyote.saturating_sub(1);
+    }
+}
+
 #[derive(Clone)]
 pub struct Player {
     pub x: i32,
@@ -99,6 +139,7 @@ pub struct Player {
     pub booster_fuel: u32,
     pub up: bool,
     pub down: bool,
+    /// Ticks of invulnerability left after getting hit. Damage is blocked and the sprite blinks while it's non-zero.
     pub shock_counter: u8,
     pub xp_counter: u8,
     pub current_weapon: u8,
@@ -112,6 +153,8 @@ pub struct Player {
     strafe_up: bool,
     weapon_offset_y: i8,
     splash: bool,
+    /// Set by a jump started in water, only such a jump gets the water exit boost.
+    water_jump: bool,
     tick: u8,
     booster_switch: BoosterSwitch,
     damage_counter: u16,
@@ -123,6 +166,12 @@ pub struct Player {
     dog_stack: Vec<DogStack>,
     pub has_dog: bool,
     pub teleport_counter: u16,
+    pub transition_grace: u16,
+    /// If set, dying doesn't end the game, instead the player comes back after `respawn_counter` runs out.
+    /// Used by the second player in co-op.
+    pub respawn_on_death: bool,
+    pub respawn_counter: u16,
+    jump_assist: JumpAssist,
 }
 
 impl Player {
@@ -153,6 +202,7 @@ impl Player {
             question: false,
             booster_fuel: 0,
             splash: false,
+            water_jump: false,
             up: false,
             down: false,
             current_weapon: 0,
@@ -178,6 +228,10 @@ impl Player {
             dog_stack: Vec::new(),
             has_dog: false,
             teleport_counter: 0,
+            transition_grace: 0,
+            respawn_on_death: false,
+            respawn_counter: 0,
+            jump_assist: JumpAssist::default(),
         }
     }
 
@@ -189,12 +243,24 @@ impl Player {
         }
     }
 
+    /// Drops buffered jump presses and coyote time, so they don't carry over when the player is moved by a script.
+    pub fn reset_jump_assist(&mut self) {
+        self.jump_assist = JumpAssist::default();
+    }
+
     pub fn load_skin(&mut self, texture_name: String, state: &mut SharedGameState, ctx: &mut Context) {
         self.skin = Box::new(BasicPlayerSkin::new(texture_name, state, ctx));
         self.display_bounds = self.skin.get_display_bounds();
         self.hit_bounds = self.skin.get_hit_bounds();
     }
 
+    fn tick_transition_grace(&mut self, state: &SharedGameState) {
+        // the grace period only starts running down once the screen has fully faded in
+        if self.transition_grace != 0 && state.fade_state == FadeState::Visible {
+            self.transition_grace -= 1;
+        }
+    }
+
     fn tick_normal(&mut self, state: &mut SharedGameState, npc_list: &NPCList) -> GameResult {
         if !state.control_flags.interactions_disabled() && state.control_flags.control_enabled() {
             if self.equip.has_air_tank() {
@@ -251,9 +317,19 @@ impl Player {
             self.strafe_up = false;
         }
 
+        let on_ground = self.flags.hit_bottom_wall() || self.flags.hit_right_slope() || self.flags.hit_left_slope();
+
+        self.jump_assist.update(
+            state.control_flags.control_enabled() && self.controller.trigger_jump(),
+            on_ground,
+            state.constants.player.jump_buffer_ticks,
+            state.constants.player.coyote_ticks,
+        );
+
         // ground movement
-        if self.flags.hit_bottom_wall() || self.flags.hit_right_slope() || self.flags.hit_left_slope() {
+        if on_ground {
             self.booster_switch = BoosterSwitch::None;
+            self.water_jump = false;
 
             if state.settings.infinite_booster {
                 self.booster_fuel = u32::MAX;
@@ -323,7 +399,7 @@ impl Player {
         } else {
             // air movement
             if state.control_flags.control_enabled() {
-                if self.controller.trigger_jump() && self.booster_fuel != 0 {
+                if self.controller.trigger_jump() && self.booster_fuel != 0 && !self.jump_assist.grounded() {
                     if self.equip.has_booster_0_8() {
                         self.booster_switch = BoosterSwitch::Up;
 
@@ -396,15 +472,15 @@ impl Player {
             self.up = self.controller.move_up() || self.strafe_up;
             self.down = self.controller.move_down() && !self.flags.hit_bottom_wall();
 
-            if self.controller.trigger_jump()
-                && (self.flags.hit_bottom_wall() || self.flags.hit_right_slope() || self.flags.hit_left_slope())
-                && !self.flags.force_up()
-            {
+            if !self.flags.force_up() && self.jump_assist.take_jump() {
                 self.vel_y = -physics.jump;
+                self.water_jump = self.flags.in_water();
                 state.sound_manager.play_sfx(15);
             }
         }
 
+        self.jump_assist.tick();
+
         // stop interacting when moved
         if state.control_flags.control_enabled()
             && (self.controller.move_left()
@@ -537,6 +613,27 @@ impl Player {
             }
         }
 
+        // splash still being set means we've been in water on the previous tick
+        if state.settings.water_exit_boost && self.water_jump && self.splash && !self.flags.in_water() && self.vel_y < 0
+        {
+            self.vel_y -= state.constants.player.water_exit_boost;
+
+            let mut droplet = NPC::create(73, &state.npc_table);
+            droplet.cond.set_alive(true);
+            droplet.y = self.y;
+            droplet.direction = self.direction;
+
+            for _ in 0..4 {
+                droplet.x = self.x + (state.game_rng.range(-8..8) * 0x200) as i32;
+                droplet.vel_x = self.vel_x + state.game_rng.range(-0x200..0x200);
+                droplet.vel_y = state.game_rng.range(-0x200..0x80) + self.vel_y / 2;
+
+                let _ = npc_list.spawn(0x100, droplet.clone());
+            }
+
+            state.sound_manager.play_sfx(56);
+        }
+
         let max_move = if self.flags.in_water()
             && !(self.flags.force_left()
                 || self.flags.force_up()
@@ -583,6 +680,7 @@ impl Player {
 
         if !self.flags.in_water() {
             self.splash = false;
+            self.water_jump = false;
         }
 
         // spike damage
@@ -875,12 +973,12 @@ impl Player {
     }
 
     pub fn damage(&mut self, hp: i32, state: &mut SharedGameState, npc_list: &NPCList) {
-        if self.life == 0 || hp <= 0 || state.settings.god_mode || self.shock_counter > 0 {
+        if self.life == 0 || hp <= 0 || state.settings.god_mode || self.shock_counter > 0 || self.transition_grace > 0 {
             return;
         }
 
         state.sound_manager.play_sfx(16);
-        self.shock_counter = 128;
+        self.shock_counter = state.constants.player.invulnerability_ticks;
         self.cond.set_interacted(false);
 
         if self.control_mode == ControlMode::Normal {
@@ -909,9 +1007,14 @@ impl Player {
         if self.life == 0 {
             state.sound_manager.play_sfx(17);
             self.cond.0 = 0;
-            state.control_flags.set_tick_world(true);
-            state.control_flags.set_interactions_disabled(true);
-            state.textscript_vm.start_script(40);
+
+            if self.respawn_on_death {
+                self.respawn_counter = (state.settings.timing_mode.get_tps() * 3) as u16;
+            } else {
+                state.control_flags.set_tick_world(true);
+                state.control_flags.set_interactions_disabled(true);
+                state.textscript_vm.start_script(40);
+            }
 
             state.create_caret(self.x, self.y, CaretType::Explosion, Direction::Left);
             let mut npc = NPC::create(4, &state.npc_table);
@@ -925,6 +1028,11 @@ impl Player {
         }
     }
 
+    /// Whether the sprite is left out on this tick of the blinking after getting hit.
+    pub fn is_blink_hidden(&self) -> bool {
+        self.shock_counter / 2 % 2 != 0
+    }
+
     pub fn update_teleport_counter(&mut self, state: &SharedGameState) {
         self.teleport_counter += 1;
 
@@ -958,6 +1066,8 @@ impl GameEntity<&NPCList> for Player {
             self.damage_taken = 0;
         }
 
+        self.tick_transition_grace(state);
+
         match (self.control_mode, state.settings.noclip) {
             (_, true) => self.tick_ironhead(state)?,
             (ControlMode::Normal, _) => self.tick_normal(state, npc_list)?,
@@ -1034,7 +1144,7 @@ impl GameEntity<&NPCList> for Player {
             }
         }
 
-        if self.shock_counter / 2 % 2 != 0 {
+        if self.is_blink_hidden() {
             return Ok(());
         }
 
@@ -1094,3 +1204,180 @@ impl GameEntity<&NPCList> for Player {
         Ok(())
     }
 }
+
+#[test]
+fn test_jump_assist_buffered_jump() {
+    let mut assist = JumpAssist::default();
+
+    // pressed 4 ticks before landing, remembered for 4
+    assist.update(true, false, 4, 4);
+    assert!(!assist.take_jump());
+    for _ in 0..3 {
+        assist.tick();
+        assist.update(false, false, 4, 4);
+        assert!(!assist.take_jump());
+    }
+
+    assist.tick();
+    assist.update(false, true, 4, 4);
+    assert!(assist.take_jump());
+
+    // the press was used up, standing on the ground doesn't jump again
+    assist.tick();
+    assist.update(false, true, 4, 4);
+    assert!(!assist.take_jump());
+
+    // with the original behavior presses in mid-air are dropped right away
+    let mut assist = JumpAssist::default();
+    assist.update(true, false, 0, 0);
+    assist.tick();
+    assist.update(false, true, 0, 0);
+    assert!(!assist.take_jump());
+}
+
+#[test]
+fn test_jump_assist_coyote_time() {
+    let mut assist = JumpAssist::default();
+
+    assist.update(false, true, 4, 4);
+    assert!(assist.grounded());
+
+    // walked off the ledge 4 ticks ago, still allowed to jump
+    for _ in 0..4 {
+        assist.tick();
+        assist.update(false, false, 4, 4);
+    }
+    assert!(assist.grounded());
+    assist.update(true, false, 4, 4);
+    assert!(assist.take_jump());
+
+    // one tick too late
+    let mut assist = JumpAssist::default();
+    assist.update(false, true, 4, 4);
+    for _ in 0..5 {
+        assist.tick();
+        assist.update(false, false, 4, 4);
+    }
+    assist.update(true, false, 4, 4);
+    assert!(!assist.grounded());
+    assert!(!assist.take_jump());
+
+    // no coyote time with the original behavior
+    let mut assist = JumpAssist::default();
+    assist.update(false, true, 0, 0);
+    assist.tick();
+    assist.update(true, false, 0, 0);
+    assert!(!assist.take_jump());
+}
+
+#[test]
+fn test_transition_grace_waits_for_fade_in() {
+    use crate::common::FadeDirection;
+    use crate::game::HeadlessRunner;
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    let mut player = Player::new(&mut state, &mut ctx);
+    player.transition_grace = 50;
+
+    // nothing runs down while the screen is black or still fading in
+    for fade_state in [FadeState::Hidden, FadeState::FadeIn(8, FadeDirection::Left)] {
+        state.fade_state = fade_state;
+        for _ in 0..10 {
+            player.tick_transition_grace(&state);
+        }
+        assert_eq!(player.transition_grace, 50);
+    }
+
+    state.fade_state = FadeState::Visible;
+    for _ in 0..10 {
+        player.tick_transition_grace(&state);
+    }
+    assert_eq!(player.transition_grace, 40);
+}
+
+#[test]
+fn test_water_exit_boost_needs_jump() {
+    use crate::game::HeadlessRunner;
+    use crate::input::replay_player_controller::{KeyState, ReplayController};
+
+    // returns the vertical velocity right after leaving the water
+    fn exit_water(jump: bool, boost: bool) -> i32 {
+        let (mut ctx, mut state) = HeadlessRunner::bare_state();
+        state.control_flags.set_control_enabled(true);
+        state.settings.water_exit_boost = boost;
+        let npc_list = NPCList::new();
+
+        let mut player = Player::new(&mut state, &mut ctx);
+        player.cond.set_alive(true);
+        player.splash = true;
+
+        let mut controller = ReplayController::new();
+        controller.state = KeyState(0);
+        controller.state.set_jump(jump);
+
+        player.flags.set_in_water(true);
+        if jump {
+            player.flags.set_hit_bottom_wall(true);
+        } else {
+            // carried up and out by a current instead
+            player.flags.set_force_up(true);
+            player.vel_y = -0x300;
+        }
+        controller.update_trigger();
+        player.controller = Box::new(controller);
+        player.tick(&mut state, &npc_list).unwrap();
+
+        player.flags = Flag(0);
+        controller.update_trigger();
+        player.controller = Box::new(controller);
+        player.tick(&mut state, &npc_list).unwrap();
+
+        player.vel_y
+    }
+
+    let boost = HeadlessRunner::bare_state().1.constants.player.water_exit_boost;
+    assert_eq!(exit_water(true, true), exit_water(true, false) - boost);
+    assert_eq!(exit_water(false, true), exit_water(false, false));
+}
+
+#[test]
+fn test_blink_matches_invulnerability() {
+    use crate::game::HeadlessRunner;
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    let npc_list = NPCList::new();
+
+    for ticks in [1, 7, 30, 255] {
+        state.constants.player.invulnerability_ticks = ticks;
+
+        let mut player = Player::new(&mut state, &mut ctx);
+        player.cond.set_alive(true);
+        player.max_life = 100;
+        player.life = 100;
+        player.damage(1, &mut state, &npc_list);
+        assert_eq!(player.life, 99);
+
+        let mut elapsed = 0;
+        let mut last_hidden = None;
+        while player.life == 99 {
+            player.tick(&mut state, &npc_list).unwrap();
+            elapsed += 1;
+
+            let hidden = player.is_blink_hidden();
+            if hidden {
+                last_hidden = Some(elapsed);
+            }
+
+            // the blinking is over by the time it can be hurt again
+            player.damage(1, &mut state, &npc_list);
+            assert!(player.life == 99 || !hidden);
+        }
+
+        assert_eq!(elapsed, ticks as usize);
+        // and it lasts until the end instead of stopping at the default duration
+        match last_hidden {
+            Some(last_hidden) => assert!(last_hidden + 3 >= elapsed, "{} ticks: last blink at {}", ticks, last_hidden),
+            None => assert!(ticks < 4),
+        }
+    }
+}
@@ -334,6 +334,7 @@ impl Player {
             state.control_flags.set_interactions_disabled(true);
             state.textscript_vm.executor_player = id;
             state.textscript_vm.start_script(npc.event_num);
+            state.textscript_vm.executor_npc = Some(npc.id);
         }
 
         if state.control_flags.control_enabled() && !npc.npc_flags.interactable() {
@@ -359,6 +360,7 @@ impl Player {
             state.control_flags.set_interactions_disabled(true);
             state.textscript_vm.executor_player = id;
             state.textscript_vm.start_script(npc.event_num);
+            state.textscript_vm.executor_npc = Some(npc.id);
             self.vel_x = 0;
             self.question = false;
         }
@@ -26,8 +26,9 @@ pub struct SkinMeta {
     pub frame_size_height: u16,
     #[serde(default = "skinmeta_default_hit_box")]
     pub hit_box: Rect<u16>,
-    #[serde(default = "skinmeta_default_display_box")]
-    pub display_box: Rect<u16>,
+    /// Sprite draw offset relative to player's position, derived from frame size if not specified.
+    #[serde(default)]
+    pub display_box: Option<Rect<u16>>,
     #[serde(default = "skinmeta_default_whimsical_star")]
     pub whimsical_star_rect: Rect<u16>,
     #[serde(default)]
@@ -46,16 +47,23 @@ const fn skinmeta_default_hit_box() -> Rect<u16> {
     Rect { left: 5, top: 8, right: 5, bottom: 8 }
 }
 
-const fn skinmeta_default_display_box() -> Rect<u16> {
-    Rect { left: 8, top: 8, right: 8, bottom: 8 }
-}
-
 const fn skinmeta_default_whimsical_star() -> Rect<u16> {
     Rect { left: 192, top: 0, right: 200, bottom: 8 }
 }
 
 pub static SUPPORTED_SKINMETA_VERSIONS: [u8; 1] = [1];
 
+impl SkinMeta {
+    /// Centers the frame horizontally and aligns its bottom edge with the bottom of the hit box,
+    /// so feet of taller or shorter costumes still touch the ground.
+    pub fn default_display_box(&self) -> Rect<u16> {
+        let half_width = self.frame_size_width / 2;
+        let bottom = self.hit_box.bottom;
+
+        Rect { left: half_width, top: self.frame_size_height.saturating_sub(bottom), right: half_width, bottom }
+    }
+}
+
 lazy_static! {
     pub static ref DEFAULT_SKINMETA: SkinMeta = SkinMeta {
         name: "Player".to_string(),
@@ -66,7 +74,7 @@ lazy_static! {
         frame_size_width: 16,
         frame_size_height: 16,
         hit_box: skinmeta_default_hit_box(),
-        display_box: skinmeta_default_display_box(),
+        display_box: None,
         whimsical_star_rect: skinmeta_default_whimsical_star(),
         version: 1
     };
@@ -234,7 +242,7 @@ impl PlayerSkin for BasicPlayerSkin {
     }
 
     fn get_display_bounds(&self) -> Rect<u32> {
-        let ubox = &self.metadata.display_box;
+        let ubox = self.metadata.display_box.unwrap_or_else(|| self.metadata.default_display_box());
 
         Rect {
             left: ubox.left as u32 * 0x200,
@@ -262,3 +270,28 @@ impl PlayerSkin for BasicPlayerSkin {
         self.skinsheet_offset = offset;
     }
 }
+
+#[test]
+fn test_tall_skin_feet_on_floor() {
+    use crate::framework::filesystem::TempDataDir;
+    use crate::game::HeadlessRunner;
+
+    let dir = TempDataDir::new("tall-skin");
+    dir.write("Tall.dskinmeta", br#"{"name": "Tall", "frameSizeWidth": 24, "frameSizeHeight": 32, "version": 1}"#);
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    dir.mount(&mut ctx);
+    state.constants.base_paths = vec!["/".to_owned()];
+
+    for (texture_name, frame_height) in [("MyChar", 16), ("Tall", 32)] {
+        let skin = BasicPlayerSkin::new(texture_name.to_owned(), &state, &mut ctx);
+        let (hit_bounds, display_bounds) = (skin.get_hit_bounds(), skin.get_display_bounds());
+
+        // standing with the bottom of the hit box on the floor, the bottom of the frame has to be there as well
+        let floor = 5 * 16 * 0x200;
+        let y = floor - hit_bounds.bottom as i32;
+        let sprite_top = y - display_bounds.top as i32;
+        assert_eq!(sprite_top + frame_height * 0x200, floor, "{}", texture_name);
+        assert_eq!(display_bounds.left + display_bounds.right, skin.metadata.frame_size_width as u32 * 0x200);
+    }
+}
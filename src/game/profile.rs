@@ -56,7 +56,7 @@ impl GameProfile {
 
         let _ = state.sound_manager.play_song(self.current_song as usize, &state.constants, &state.settings, ctx);
 
-        game_scene.inventory_player1.current_weapon = self.current_weapon as u16;
+        game_scene.inventory_player1.select_weapon(self.current_weapon as u16);
         game_scene.inventory_player1.current_item = self.current_item as u16;
         for weapon in &self.weapon_data {
             if weapon.weapon_id == 0 {
@@ -132,6 +132,7 @@ impl GameProfile {
             }
         }
 
+        state.play_counter = self.counter;
         state.textscript_vm.start_script(0);
 
         game_scene.player1.equip.0 = self.equipment as u16;
@@ -166,11 +167,11 @@ impl GameProfile {
         let max_life = game_scene.player1.max_life;
         let stars = game_scene.player1.stars as u16;
         let life = game_scene.player1.life;
-        let current_weapon = game_scene.inventory_player1.current_weapon as u32;
+        let current_weapon = game_scene.inventory_player1.get_current_weapon_idx() as u32;
         let current_item = game_scene.inventory_player1.current_item as u32;
         let equipment = game_scene.player1.equip.0 as u32;
         let control_mode = game_scene.player1.control_mode as u32;
-        let counter = 0; // TODO
+        let counter = state.play_counter;
         let mut weapon_data = [
             WeaponData { weapon_id: 0, level: 0, exp: 0, max_ammo: 0, ammo: 0 },
             WeaponData { weapon_id: 0, level: 0, exp: 0, max_ammo: 0, ammo: 0 },
@@ -290,11 +291,10 @@ impl GameProfile {
             data.write_u32::<LE>(slot.event_num)?;
         }
 
-        let something = [0u8; 0x80];
-        data.write(&something)?;
+        data.write_all(&self.map_flags)?;
 
         data.write_u32::<BE>(0x464c4147)?;
-        data.write(&self.flags)?;
+        data.write_all(&self.flags)?;
 
         data.write_u32::<LE>(0)?; // unused(?) CS+ space
 
@@ -370,8 +370,17 @@ impl GameProfile {
             return Err(ResourceLoadError("Invalid FLAG signature".to_owned()));
         }
 
+        // some tools write truncated profiles, flags past the end of file are treated as unset
         let mut flags = [0u8; 1000];
-        data.read_exact(&mut flags)?;
+        let mut flags_read = 0;
+        while flags_read < flags.len() {
+            match data.read(&mut flags[flags_read..]) {
+                Ok(0) => break,
+                Ok(n) => flags_read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
 
         data.read_u32::<LE>().unwrap_or(0); // unused(?) CS+ space
 
@@ -402,3 +411,78 @@ impl GameProfile {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // layout of the original Profile.dat, followed by the CS+ extension
+    fn sample_profile() -> Vec<u8> {
+        let mut data = vec![0u8; 0x604 + 13];
+        data[0..8].copy_from_slice(b"Do041220");
+        data[0x08] = 13; // current map
+        data[0x18] = 2; // direction
+        data[0x218 - 0x80 + 3] = 1; // map flag 3
+        data[0x218..0x21c].copy_from_slice(b"FLAG");
+        data[0x21c] = 0xff;
+        data[0x21c + 999] = 0x80;
+        data
+    }
+
+    #[test]
+    fn test_profile_round_trip() {
+        let data = sample_profile();
+        let profile = GameProfile::load_from_save(&data[..]).unwrap();
+
+        assert_eq!(profile.current_map, 13);
+        assert_eq!(profile.map_flags[3], 1);
+        assert_eq!(profile.flags[0], 0xff);
+
+        let mut written = Vec::new();
+        profile.write_save(&mut written).unwrap();
+        assert_eq!(data, written);
+    }
+
+    #[test]
+    fn test_profile_truncated_flags() {
+        let data = sample_profile();
+        let profile = GameProfile::load_from_save(&data[..0x21c + 100]).unwrap();
+
+        assert_eq!(profile.flags[0], 0xff);
+        assert_eq!(profile.flags[999], 0);
+        assert_eq!(profile.timestamp, 0);
+    }
+
+    #[test]
+    fn test_profile_counter_saved() {
+        use crate::framework::filesystem::TempDataDir;
+        use crate::game::stage::Stage;
+        use crate::game::HeadlessRunner;
+
+        let dir = TempDataDir::new("profile-counter");
+        // left behind by a longer profile
+        dir.write("Profile.dat", vec![0xaa; 0x2000]);
+
+        let (mut ctx, mut state) = HeadlessRunner::bare_state();
+        dir.mount_user(&mut ctx);
+
+        let stage = Stage::blank(3, 2);
+        state.stages.push(stage.data.clone());
+        let mut scene = GameScene::from_stage(&mut state, &mut ctx, stage, 0).unwrap();
+        state.play_counter = 123_456;
+        state.save_game(&mut scene, &mut ctx).unwrap();
+
+        let data = std::fs::read(dir.root.join("Profile.dat")).unwrap();
+        let profile = GameProfile::load_from_save(&data[..]).unwrap();
+        assert_eq!(profile.counter, 123_456);
+
+        // nothing of the old file is left past the end of the new one
+        let mut written = Vec::new();
+        profile.write_save(&mut written).unwrap();
+        assert_eq!(data, written);
+
+        state.play_counter = 0;
+        profile.apply(&mut state, &mut scene, &mut ctx);
+        assert_eq!(state.play_counter, 123_456);
+    }
+}
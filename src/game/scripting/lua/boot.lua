@@ -56,6 +56,9 @@ __doukutsu_rs_runtime_dont_touch._known_settings = {
     ["doukutsu-rs.new_game.event_id"] = 0x1003,
     ["doukutsu-rs.new_game.stage_id"] = 0x1004,
     ["doukutsu-rs.new_game.pos"] = 0x1005,
+    ["doukutsu-rs.tsc.clear_room_event"] = 0x1006,
+    ["doukutsu-rs.tsc.flash_duration"] = 0x1007,
+    ["doukutsu-rs.tsc.myb_away_from_executor"] = 0x1008,
     ["doukutsu-rs.font_scale"] = 0x2000,
 }
 
@@ -65,10 +68,10 @@ require = function(modname)
     if __doukutsu_rs_runtime_dont_touch._requires[modname] == nil then
         local mod = __doukutsu_rs:loadScript(modname)
 
-        __doukutsu_rs_runtime_dont_touch._requires[modname] = { mod = mod, loaded = True }
-    else
-        return __doukutsu_rs_runtime_dont_touch._requires[modname].mod
+        __doukutsu_rs_runtime_dont_touch._requires[modname] = { mod = mod, loaded = true }
     end
+
+    return __doukutsu_rs_runtime_dont_touch._requires[modname].mod
 end
 
 __doukutsu_rs_runtime_dont_touch._registered = {
@@ -389,6 +392,14 @@ function doukutsu.playSong(id)
     __doukutsu_rs:playSong(id)
 end
 
+function doukutsu.getMapFlag(id)
+    return __doukutsu_rs:getMapFlag(id) or false
+end
+
+function doukutsu.setMapFlag(id, value)
+    __doukutsu_rs:setMapFlag(id, value)
+end
+
 function doukutsu.players()
     return { __doukutsu_rs_runtime_dont_touch._playerRef0, __doukutsu_rs_runtime_dont_touch._playerRef1 }
 end
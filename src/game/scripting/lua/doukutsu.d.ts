@@ -258,6 +258,19 @@ declare namespace doukutsu {
      */
     function setSkipFlag(id: number, value: boolean): void;
 
+    /**
+     * Returns whether the map has been visited, as set by <MP+ and used by <MPJ.
+     * @param id the stage number
+     */
+    function getMapFlag(id: number): boolean;
+
+    /**
+     * Marks the map as visited or not visited.
+     * @param id the stage number
+     * @param value the flag value
+     */
+    function setMapFlag(id: number, value: boolean): void;
+
     /**
      * Returns a list of players currently in game.
      */
@@ -11,6 +11,11 @@ use crate::game::scripting::lua::{check_status, DRS_RUNTIME_GLOBAL, LuaScripting
 use crate::scene::game_scene::LightingMode;
 use crate::util::rng::RNG;
 
+/// Ids of flags that can exist, negative ones from scripts don't refer to any.
+fn flag_index(id: c_int) -> Option<usize> {
+    usize::try_from(id).ok()
+}
+
 pub struct Doukutsu {
     pub ptr: *mut LuaScriptingState,
 }
@@ -54,10 +59,10 @@ impl Doukutsu {
     }
 
     unsafe fn lua_set_setting(&self, state: &mut State) -> c_int {
-        if let Some(index) = state.to_int(2) {
+        if let Some(index) = state.to_int(2).and_then(flag_index) {
             let game_state = &mut (*(*self.ptr).state_ptr);
 
-            state.push(game_state.get_flag(index.max(0) as usize));
+            state.push(game_state.get_flag(index));
         } else {
             state.push_nil();
         }
@@ -66,10 +71,10 @@ impl Doukutsu {
     }
 
     unsafe fn lua_get_flag(&self, state: &mut State) -> c_int {
-        if let Some(index) = state.to_int(2) {
+        if let Some(index) = state.to_int(2).and_then(flag_index) {
             let game_state = &mut (*(*self.ptr).state_ptr);
 
-            state.push(game_state.get_flag(index.max(0) as usize));
+            state.push(game_state.get_flag(index));
         } else {
             state.push_nil();
         }
@@ -78,23 +83,23 @@ impl Doukutsu {
     }
 
     unsafe fn lua_set_flag(&self, state: &mut State) -> c_int {
-        let flag_id = state.to_int(2);
+        let flag_id = state.to_int(2).and_then(flag_index);
         let flag_val = state.to_bool(3);
 
         if let (Some(flag_id), Some(flag_val)) = (flag_id, flag_val) {
             let game_state = &mut (*(*self.ptr).state_ptr);
 
-            game_state.set_flag(flag_id.max(0) as usize, flag_val);
+            game_state.set_flag(flag_id, flag_val);
         }
 
         0
     }
 
     unsafe fn lua_get_skip_flag(&self, state: &mut State) -> c_int {
-        if let Some(index) = state.to_int(2) {
+        if let Some(index) = state.to_int(2).and_then(flag_index) {
             let game_state = &mut (*(*self.ptr).state_ptr);
 
-            state.push(game_state.get_skip_flag(index.max(0) as usize));
+            state.push(game_state.get_skip_flag(index));
         } else {
             state.push_nil();
         }
@@ -103,13 +108,38 @@ impl Doukutsu {
     }
 
     unsafe fn lua_set_skip_flag(&self, state: &mut State) -> c_int {
-        let flag_id = state.to_int(2);
+        let flag_id = state.to_int(2).and_then(flag_index);
         let flag_val = state.to_bool(3);
 
         if let (Some(flag_id), Some(flag_val)) = (flag_id, flag_val) {
             let game_state = &mut (*(*self.ptr).state_ptr);
 
-            game_state.set_skip_flag(flag_id.max(0) as usize, flag_val);
+            game_state.set_skip_flag(flag_id, flag_val);
+        }
+
+        0
+    }
+
+    unsafe fn lua_get_map_flag(&self, state: &mut State) -> c_int {
+        if let Some(index) = state.to_int(2).and_then(flag_index) {
+            let game_state = &mut (*(*self.ptr).state_ptr);
+
+            state.push(game_state.get_map_flag(index));
+        } else {
+            state.push_nil();
+        }
+
+        1
+    }
+
+    unsafe fn lua_set_map_flag(&self, state: &mut State) -> c_int {
+        let map_id = state.to_int(2).and_then(flag_index);
+        let flag_val = state.to_bool(3);
+
+        if let (Some(map_id), Some(flag_val)) = (map_id, flag_val) {
+            let game_state = &mut (*(*self.ptr).state_ptr);
+
+            game_state.set_map_flag(map_id, flag_val);
         }
 
         0
@@ -156,6 +186,25 @@ impl Doukutsu {
                         game_state.constants.game.new_game_player_pos = (ng_x as i16, ng_y as i16);
                     }
                 }
+                0x1006 => {
+                    // clear room event
+                    if let Some(event_num) = state.to_int(3) {
+                        game_state.constants.textscript.clear_room_event =
+                            if event_num > 0 { Some(event_num as u16) } else { None };
+                    }
+                }
+                0x1007 => {
+                    // flash duration
+                    if let Some(duration) = state.to_int(3) {
+                        game_state.constants.textscript.flash_duration = duration.max(0) as u16;
+                    }
+                }
+                0x1008 => {
+                    // <MYB0000 knocks away from the executor NPC
+                    if let Some(enabled) = state.to_bool(3) {
+                        game_state.constants.textscript.myb_away_from_executor = enabled;
+                    }
+                }
                 0x2000 => {
                     // font scale
                     if let Some(font_scale) = state.to_float(3) {
@@ -308,7 +357,7 @@ impl Doukutsu {
                 }
                 0x120 => {
                     if let Some(v) = state.to_int(4) {
-                        npc.event_num = v as u16;
+                        game_scene.npc_list.set_event_num(npc, v as u16);
                     }
                 }
                 0x121 | 0x122 => {
@@ -520,11 +569,14 @@ impl LuaObject for Doukutsu {
     fn lua_fns() -> Vec<luaL_Reg> {
         vec![
             lua_method!("playSfx", Doukutsu, Doukutsu::lua_play_sfx),
+            lua_method!("playSfxLoop", Doukutsu, Doukutsu::lua_play_sfx_loop),
             lua_method!("playSong", Doukutsu, Doukutsu::lua_play_song),
             lua_method!("getFlag", Doukutsu, Doukutsu::lua_get_flag),
             lua_method!("setFlag", Doukutsu, Doukutsu::lua_set_flag),
             lua_method!("getSkipFlag", Doukutsu, Doukutsu::lua_get_skip_flag),
             lua_method!("setSkipFlag", Doukutsu, Doukutsu::lua_set_skip_flag),
+            lua_method!("getMapFlag", Doukutsu, Doukutsu::lua_get_map_flag),
+            lua_method!("setMapFlag", Doukutsu, Doukutsu::lua_set_map_flag),
             lua_method!("setEngineConstant", Doukutsu, Doukutsu::lua_set_engine_constant),
             lua_method!("playerCommand", Doukutsu, Doukutsu::lua_player_command),
             lua_method!("npcCommand", Doukutsu, Doukutsu::lua_npc_command),
@@ -559,3 +611,12 @@ impl LuaScriptingState {
         result
     }
 }
+
+#[test]
+fn test_flag_index() {
+    assert_eq!(flag_index(0), Some(0));
+    assert_eq!(flag_index(8000), Some(8000));
+    // used to wrap around to flag 0
+    assert_eq!(flag_index(-1), None);
+    assert_eq!(flag_index(c_int::MIN), None);
+}
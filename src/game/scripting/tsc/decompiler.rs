@@ -9,6 +9,112 @@ use crate::game::scripting::tsc::bytecode_utils::read_cur_varint;
 use crate::game::scripting::tsc::opcodes::TSCOpCode;
 use crate::game::scripting::tsc::text_script::TextScript;
 
+fn operand_count(op: TSCOpCode) -> usize {
+    match op {
+        // Zero operand codes
+        TSCOpCode::AEp
+        | TSCOpCode::CAT
+        | TSCOpCode::CIL
+        | TSCOpCode::CLO
+        | TSCOpCode::CLR
+        | TSCOpCode::CPS
+        | TSCOpCode::CRE
+        | TSCOpCode::CSS
+        | TSCOpCode::END
+        | TSCOpCode::ESC
+        | TSCOpCode::FLA
+        | TSCOpCode::FMU
+        | TSCOpCode::FRE
+        | TSCOpCode::HMC
+        | TSCOpCode::INI
+        | TSCOpCode::KEY
+        | TSCOpCode::LDP
+        | TSCOpCode::MLP
+        | TSCOpCode::MM0
+        | TSCOpCode::MNA
+        | TSCOpCode::MS2
+        | TSCOpCode::MS3
+        | TSCOpCode::MSG
+        | TSCOpCode::NOD
+        | TSCOpCode::PRI
+        | TSCOpCode::RMU
+        | TSCOpCode::SAT
+        | TSCOpCode::SLP
+        | TSCOpCode::SMC
+        | TSCOpCode::SPS
+        | TSCOpCode::STC
+        | TSCOpCode::SVP
+        | TSCOpCode::TUR
+        | TSCOpCode::WAS
+        | TSCOpCode::ZAM
+        | TSCOpCode::HM2
+        | TSCOpCode::POP
+        | TSCOpCode::KE2
+        | TSCOpCode::FR2 => 0,
+        // One operand codes
+        TSCOpCode::BOA
+        | TSCOpCode::BSL
+        | TSCOpCode::FOB
+        | TSCOpCode::FOM
+        | TSCOpCode::QUA
+        | TSCOpCode::UNI
+        | TSCOpCode::MYB
+        | TSCOpCode::MYD
+        | TSCOpCode::FAI
+        | TSCOpCode::FAO
+        | TSCOpCode::WAI
+        | TSCOpCode::FAC
+        | TSCOpCode::GIT
+        | TSCOpCode::NUM
+        | TSCOpCode::DNA
+        | TSCOpCode::DNP
+        | TSCOpCode::FLm
+        | TSCOpCode::FLp
+        | TSCOpCode::MPp
+        | TSCOpCode::SKm
+        | TSCOpCode::SKp
+        | TSCOpCode::EQp
+        | TSCOpCode::EQm
+        | TSCOpCode::MLp
+        | TSCOpCode::ITp
+        | TSCOpCode::ITm
+        | TSCOpCode::AMm
+        | TSCOpCode::MPJ
+        | TSCOpCode::YNJ
+        | TSCOpCode::EVE
+        | TSCOpCode::XX1
+        | TSCOpCode::SIL
+        | TSCOpCode::LIp
+        | TSCOpCode::SOU
+        | TSCOpCode::CMU
+        | TSCOpCode::SSS
+        | TSCOpCode::ACH
+        | TSCOpCode::S2MV
+        | TSCOpCode::S2PJ
+        | TSCOpCode::PSH => 1,
+        // Two operand codes
+        TSCOpCode::FON
+        | TSCOpCode::MOV
+        | TSCOpCode::AMp
+        | TSCOpCode::NCJ
+        | TSCOpCode::ECJ
+        | TSCOpCode::FLJ
+        | TSCOpCode::ITJ
+        | TSCOpCode::SKJ
+        | TSCOpCode::AMJ
+        | TSCOpCode::UNJ
+        | TSCOpCode::SMP
+        | TSCOpCode::PSp
+        | TSCOpCode::IpN
+        | TSCOpCode::FFm => 2,
+        // Three operand codes
+        TSCOpCode::ANP | TSCOpCode::CNP | TSCOpCode::INP | TSCOpCode::TAM | TSCOpCode::CMP | TSCOpCode::INJ => 3,
+        // Four operand codes
+        TSCOpCode::TRA | TSCOpCode::MNP | TSCOpCode::SNP => 4,
+        TSCOpCode::_STR | TSCOpCode::_NOP | TSCOpCode::_UNI | TSCOpCode::_END => 0,
+    }
+}
+
 impl TextScript {
     pub fn decompile_event(&self, id: u16) -> GameResult<String> {
         if let Some(bytecode) = self.event_map.get(&id) {
@@ -20,130 +126,6 @@ impl TextScript {
 
                 if let Some(op) = op_maybe {
                     match op {
-                        // Zero operand codes
-                        TSCOpCode::AEp
-                        | TSCOpCode::CAT
-                        | TSCOpCode::CIL
-                        | TSCOpCode::CLO
-                        | TSCOpCode::CLR
-                        | TSCOpCode::CPS
-                        | TSCOpCode::CRE
-                        | TSCOpCode::CSS
-                        | TSCOpCode::END
-                        | TSCOpCode::ESC
-                        | TSCOpCode::FLA
-                        | TSCOpCode::FMU
-                        | TSCOpCode::FRE
-                        | TSCOpCode::HMC
-                        | TSCOpCode::INI
-                        | TSCOpCode::KEY
-                        | TSCOpCode::LDP
-                        | TSCOpCode::MLP
-                        | TSCOpCode::MM0
-                        | TSCOpCode::MNA
-                        | TSCOpCode::MS2
-                        | TSCOpCode::MS3
-                        | TSCOpCode::MSG
-                        | TSCOpCode::NOD
-                        | TSCOpCode::PRI
-                        | TSCOpCode::RMU
-                        | TSCOpCode::SAT
-                        | TSCOpCode::SLP
-                        | TSCOpCode::SMC
-                        | TSCOpCode::SPS
-                        | TSCOpCode::STC
-                        | TSCOpCode::SVP
-                        | TSCOpCode::TUR
-                        | TSCOpCode::WAS
-                        | TSCOpCode::ZAM
-                        | TSCOpCode::HM2
-                        | TSCOpCode::POP
-                        | TSCOpCode::KE2
-                        | TSCOpCode::FR2 => {
-                            writeln!(&mut result, "{:?}()", op).unwrap();
-                        }
-                        // One operand codes
-                        TSCOpCode::BOA
-                        | TSCOpCode::BSL
-                        | TSCOpCode::FOB
-                        | TSCOpCode::FOM
-                        | TSCOpCode::QUA
-                        | TSCOpCode::UNI
-                        | TSCOpCode::MYB
-                        | TSCOpCode::MYD
-                        | TSCOpCode::FAI
-                        | TSCOpCode::FAO
-                        | TSCOpCode::WAI
-                        | TSCOpCode::FAC
-                        | TSCOpCode::GIT
-                        | TSCOpCode::NUM
-                        | TSCOpCode::DNA
-                        | TSCOpCode::DNP
-                        | TSCOpCode::FLm
-                        | TSCOpCode::FLp
-                        | TSCOpCode::MPp
-                        | TSCOpCode::SKm
-                        | TSCOpCode::SKp
-                        | TSCOpCode::EQp
-                        | TSCOpCode::EQm
-                        | TSCOpCode::MLp
-                        | TSCOpCode::ITp
-                        | TSCOpCode::ITm
-                        | TSCOpCode::AMm
-                        | TSCOpCode::UNJ
-                        | TSCOpCode::MPJ
-                        | TSCOpCode::YNJ
-                        | TSCOpCode::EVE
-                        | TSCOpCode::XX1
-                        | TSCOpCode::SIL
-                        | TSCOpCode::LIp
-                        | TSCOpCode::SOU
-                        | TSCOpCode::CMU
-                        | TSCOpCode::SSS
-                        | TSCOpCode::ACH
-                        | TSCOpCode::S2MV
-                        | TSCOpCode::S2PJ
-                        | TSCOpCode::PSH => {
-                            let par_a = read_cur_varint(&mut cursor)?;
-
-                            writeln!(&mut result, "{:?}({})", op, par_a).unwrap();
-                        }
-                        // Two operand codes
-                        TSCOpCode::FON
-                        | TSCOpCode::MOV
-                        | TSCOpCode::AMp
-                        | TSCOpCode::NCJ
-                        | TSCOpCode::ECJ
-                        | TSCOpCode::FLJ
-                        | TSCOpCode::ITJ
-                        | TSCOpCode::SKJ
-                        | TSCOpCode::AMJ
-                        | TSCOpCode::SMP
-                        | TSCOpCode::PSp
-                        | TSCOpCode::IpN
-                        | TSCOpCode::FFm => {
-                            let par_a = read_cur_varint(&mut cursor)?;
-                            let par_b = read_cur_varint(&mut cursor)?;
-
-                            writeln!(&mut result, "{:?}({}, {})", op, par_a, par_b).unwrap();
-                        }
-                        // Three operand codes
-                        TSCOpCode::ANP | TSCOpCode::CNP | TSCOpCode::INP | TSCOpCode::TAM | TSCOpCode::CMP | TSCOpCode::INJ => {
-                            let par_a = read_cur_varint(&mut cursor)?;
-                            let par_b = read_cur_varint(&mut cursor)?;
-                            let par_c = read_cur_varint(&mut cursor)?;
-
-                            writeln!(&mut result, "{:?}({}, {}, {})", op, par_a, par_b, par_c).unwrap();
-                        }
-                        // Four operand codes
-                        TSCOpCode::TRA | TSCOpCode::MNP | TSCOpCode::SNP => {
-                            let par_a = read_cur_varint(&mut cursor)?;
-                            let par_b = read_cur_varint(&mut cursor)?;
-                            let par_c = read_cur_varint(&mut cursor)?;
-                            let par_d = read_cur_varint(&mut cursor)?;
-
-                            writeln!(&mut result, "{:?}({}, {}, {}, {})", op, par_a, par_b, par_c, par_d).unwrap();
-                        }
                         TSCOpCode::_STR => {
                             let len = read_cur_varint(&mut cursor)?;
 
@@ -173,6 +155,14 @@ impl TextScript {
                         TSCOpCode::_NOP => result.push_str("%no_op()\n"),
                         TSCOpCode::_UNI => result.push_str("%unimplemented()\n"),
                         TSCOpCode::_END => result.push_str("%end_marker()\n"),
+                        _ => {
+                            let mut args = Vec::with_capacity(4);
+                            for _ in 0..operand_count(op) {
+                                args.push(read_cur_varint(&mut cursor)?.to_string());
+                            }
+
+                            writeln!(&mut result, "{:?}({})", op, args.join(", ")).unwrap();
+                        }
                     }
                 } else {
                     break;
@@ -184,4 +174,59 @@ impl TextScript {
             Err(InvalidValue("Unknown script.".to_string()))
         }
     }
+
+    /// Returns operands of every occurrence of given opcode within an event, skipping over everything else.
+    pub fn find_opcode_operands(&self, id: u16, target: TSCOpCode) -> GameResult<Vec<Vec<i32>>> {
+        let bytecode = self.event_map.get(&id).ok_or_else(|| InvalidValue("Unknown script.".to_string()))?;
+        let mut result = Vec::new();
+        let mut cursor: Cursor<&[u8]> = Cursor::new(bytecode);
+
+        while let Ok(op_num) = read_cur_varint(&mut cursor) {
+            let op = match FromPrimitive::from_i32(op_num) {
+                Some(op) => op,
+                None => break,
+            };
+
+            if op == TSCOpCode::_STR {
+                let len = read_cur_varint(&mut cursor)?;
+                for _ in 0..len {
+                    read_cur_varint(&mut cursor)?;
+                }
+                continue;
+            }
+
+            let mut args = Vec::with_capacity(4);
+            for _ in 0..operand_count(op) {
+                args.push(read_cur_varint(&mut cursor)?);
+            }
+
+            if op == target {
+                result.push(args);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[test]
+fn test_find_opcode_operands() {
+    use crate::game::scripting::tsc::text_script::TextScriptEncoding;
+
+    let script = b"#0100\r\n<KEY<UNJ0001:0101<CMU0003<ANP0200:0000:0002<CNP0300:0117:0000<BOA0100\r\n\
+        <ECJ0200:0201<ITJ0002:0202<AMJ0009:0203<EQ+0032<MLP<TRA0001:0094:0010:0008\r\n";
+    let script = TextScript::compile(script, true, TextScriptEncoding::UTF8).unwrap();
+
+    let operands = |op| script.find_opcode_operands(100, op).unwrap();
+    assert_eq!(operands(TSCOpCode::UNJ), vec![vec![1, 101]]);
+    assert_eq!(operands(TSCOpCode::CMU), vec![vec![3]]);
+    assert_eq!(operands(TSCOpCode::ANP), vec![vec![200, 0, 2]]);
+    assert_eq!(operands(TSCOpCode::CNP), vec![vec![300, 117, 0]]);
+    assert_eq!(operands(TSCOpCode::BOA), vec![vec![100]]);
+    assert_eq!(operands(TSCOpCode::ECJ), vec![vec![200, 201]]);
+    assert_eq!(operands(TSCOpCode::ITJ), vec![vec![2, 202]]);
+    assert_eq!(operands(TSCOpCode::AMJ), vec![vec![9, 203]]);
+    assert_eq!(operands(TSCOpCode::EQp), vec![vec![32]]);
+    assert_eq!(operands(TSCOpCode::MLP), vec![Vec::<i32>::new()]);
+    assert_eq!(operands(TSCOpCode::TRA), vec![vec![1, 94, 10, 8]]);
 }
@@ -6,3 +6,4 @@ mod encryption;
 mod opcodes;
 mod parse_utils;
 pub mod text_script;
+pub mod validator;
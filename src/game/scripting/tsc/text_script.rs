@@ -19,7 +19,7 @@ use crate::framework::context::Context;
 use crate::framework::error::GameResult;
 use crate::game::frame::UpdateTarget;
 use crate::game::npc::NPC;
-use crate::game::player::{ControlMode, TargetPlayer};
+use crate::game::player::{ControlMode, Player, TargetPlayer};
 use crate::game::scripting::tsc::bytecode_utils::read_cur_varint;
 use crate::game::scripting::tsc::encryption::decrypt_tsc;
 use crate::game::scripting::tsc::opcodes::TSCOpCode;
@@ -120,6 +120,8 @@ pub struct TextScriptVM {
     pub mode: ScriptMode,
     /// The player who triggered the event.
     pub executor_player: TargetPlayer,
+    /// The NPC that triggered the event, if any.
+    pub executor_npc: Option<u16>,
     /// Toggle for non-strict TSC parsing because English versions of CS+ (both AG and Nicalis release)
     /// modified the events carelessly and since original Pixel's engine hasn't enforced constraints
     /// while parsing no one noticed them.
@@ -138,6 +140,8 @@ pub struct TextScriptVM {
     pub illustration_state: IllustrationState,
     prev_char: char,
     pub substitution_rect_map: [(char, Rect<u16>); TSC_SUBSTITUTION_MAP_SIZE],
+    /// Reloaded scene script waiting for the running event to end before replacing the current one.
+    pending_scene_script: Option<TextScript>,
 }
 
 pub struct Scripts {
@@ -200,6 +204,7 @@ impl TextScriptVM {
             flags: TextScriptFlags(0),
             mode: ScriptMode::Map,
             executor_player: TargetPlayer::Player1,
+            executor_npc: None,
             strict_mode: false,
             suspend: true,
             reset_invicibility: false,
@@ -214,6 +219,7 @@ impl TextScriptVM {
             illustration_state: IllustrationState::Hidden,
             prev_char: '\x00',
             substitution_rect_map: [('=', Rect::new(0, 0, 0, 0))],
+            pending_scene_script: None,
         }
     }
 
@@ -234,11 +240,25 @@ impl TextScriptVM {
             scripts.scene_script = script;
         }
 
+        self.pending_scene_script = None;
+
         if !self.suspend {
             self.reset();
         }
     }
 
+    /// Replaces the scene script without resetting the VM, used for reloading it while the game is running.
+    /// If an event is being executed, the new script is only swapped in once it ends.
+    pub fn reload_scene_script(&mut self, script: TextScript) {
+        if self.state == TextScriptExecutionState::Ended {
+            self.scripts.borrow_mut().scene_script = script;
+            self.pending_scene_script = None;
+        } else {
+            log::info!("An event is running, the reloaded scene script will be used once it ends.");
+            self.pending_scene_script = Some(script);
+        }
+    }
+
     pub fn set_inventory_script(&mut self, script: TextScript) {
         let mut scripts = self.scripts.borrow_mut();
         scripts.inventory_script = script;
@@ -283,12 +303,19 @@ impl TextScriptVM {
     pub fn start_script(&mut self, event_num: u16) {
         self.reset();
         self.reset_invicibility = true;
+        self.executor_npc = None;
         self.state = TextScriptExecutionState::Running(event_num, 0);
 
         log::info!("Started script: #{:04}", event_num);
     }
 
     pub fn run(state: &mut SharedGameState, game_scene: &mut GameScene, ctx: &mut Context) -> GameResult {
+        if state.textscript_vm.state == TextScriptExecutionState::Ended {
+            if let Some(script) = state.textscript_vm.pending_scene_script.take() {
+                state.textscript_vm.scripts.borrow_mut().scene_script = script;
+            }
+        }
+
         let scripts_ref = state.textscript_vm.scripts.clone();
         let scripts = scripts_ref.borrow();
         let mut cached_event: Option<(u16, &Vec<u8>)> = None;
@@ -543,7 +570,7 @@ impl TextScriptVM {
                     break;
                 }
                 TextScriptExecutionState::WaitStanding(event, ip) => {
-                    if game_scene.player1.flags.hit_bottom_wall() || game_scene.player2.flags.hit_bottom_wall() {
+                    if is_standing(&game_scene.player1) || is_standing(&game_scene.player2) {
                         state.textscript_vm.state = TextScriptExecutionState::Running(event, ip);
                     }
                     break;
@@ -765,51 +792,48 @@ impl TextScriptVM {
                 game_scene.player1.cond.set_interacted(false);
                 game_scene.player2.cond.set_interacted(false);
 
-                if let Some(direction) = Direction::from_int_facing(new_direction) {
-                    match direction {
-                        Direction::Left => {
-                            game_scene.player1.direction = Left;
-                            game_scene.player2.direction = Left;
-                            game_scene.player1.vel_x = 0x200;
-                            game_scene.player2.vel_x = 0x200;
-                        }
-                        Direction::Up => {
-                            game_scene.player1.vel_y = -0x200;
-                            game_scene.player2.vel_y = -0x200;
-                        }
-                        Direction::Right => {
-                            game_scene.player1.direction = Right;
-                            game_scene.player2.direction = Right;
-                            game_scene.player1.vel_x = -0x200;
-                            game_scene.player2.vel_x = -0x200;
-                        }
-                        Direction::Bottom => {
-                            game_scene.player1.vel_y = 0x200;
-                            game_scene.player2.vel_y = 0x200;
-                        }
-                        _ => (),
+                let executor_npc_x = state
+                    .textscript_vm
+                    .executor_npc
+                    .filter(|_| state.constants.textscript.myb_away_from_executor)
+                    .and_then(|id| game_scene.npc_list.get_npc(id as usize))
+                    .filter(|npc| npc.cond.alive())
+                    .map(|npc| npc.x);
+
+                let knockback_source_x = match Direction::from_int_facing(new_direction) {
+                    // with `myb_away_from_executor`, <MYB0000 in an event started by an NPC knocks the players away from it
+                    Some(Direction::Left) if executor_npc_x.is_some() => executor_npc_x,
+                    Some(Direction::Left) => {
+                        game_scene.player1.direction = Left;
+                        game_scene.player2.direction = Left;
+                        game_scene.player1.vel_x = 0x200;
+                        game_scene.player2.vel_x = 0x200;
+                        None
                     }
-                } else {
-                    for npc in game_scene.npc_list.iter_alive() {
-                        if npc.event_num == new_direction as u16 {
-                            if game_scene.player1.x >= npc.x {
-                                game_scene.player1.direction = Left;
-                                game_scene.player1.vel_x = 0x200;
-                            } else {
-                                game_scene.player1.direction = Right;
-                                game_scene.player1.vel_x = -0x200;
-                            }
-
-                            if game_scene.player2.x >= npc.x {
-                                game_scene.player2.direction = Left;
-                                game_scene.player2.vel_x = 0x200;
-                            } else {
-                                game_scene.player2.direction = Right;
-                                game_scene.player2.vel_x = -0x200;
-                            }
-                            break;
-                        }
+                    Some(Direction::Up) => {
+                        game_scene.player1.vel_y = -0x200;
+                        game_scene.player2.vel_y = -0x200;
+                        None
+                    }
+                    Some(Direction::Right) => {
+                        game_scene.player1.direction = Right;
+                        game_scene.player2.direction = Right;
+                        game_scene.player1.vel_x = -0x200;
+                        game_scene.player2.vel_x = -0x200;
+                        None
                     }
+                    Some(Direction::Bottom) => {
+                        game_scene.player1.vel_y = 0x200;
+                        game_scene.player2.vel_y = 0x200;
+                        None
+                    }
+                    Some(Direction::FacingPlayer) => None,
+                    None => game_scene.npc_list.entities_with_event(new_direction as u16).next().map(|npc| npc.x),
+                };
+
+                if let Some(npc_x) = knockback_source_x {
+                    knock_back_from(&mut game_scene.player1, npc_x);
+                    knock_back_from(&mut game_scene.player2, npc_x);
                 }
 
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
@@ -1159,10 +1183,18 @@ impl TextScriptVM {
                 // Reset ground collision for WAS / WaitStanding
                 new_scene.player1.flags.set_hit_bottom_wall(false);
                 new_scene.player2.flags.set_hit_bottom_wall(false);
+                new_scene.player1.reset_jump_assist();
+                new_scene.player2.reset_jump_assist();
+                new_scene.player1.transition_grace = state.settings.transition_invincibility;
+                new_scene.player2.transition_grace = state.settings.transition_invincibility;
                 new_scene.frame.wait = game_scene.frame.wait;
                 new_scene.nikumaru = game_scene.nikumaru;
                 new_scene.replay = game_scene.replay.clone();
 
+                if let Some(song_id) = new_scene.stage.data.entry_song(&game_scene.stage.data) {
+                    state.sound_manager.play_song(song_id, &state.constants, &state.settings, ctx)?;
+                }
+
                 let skip = state.textscript_vm.flags.cutscene_skip();
                 state.control_flags.set_tick_world(true);
                 state.control_flags.set_interactions_disabled(true);
@@ -1194,6 +1226,7 @@ impl TextScriptVM {
                     player.vel_y = 0;
                     player.x = pos_x;
                     player.y = pos_y;
+                    player.reset_jump_assist();
                 }
 
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
@@ -1290,7 +1323,7 @@ impl TextScriptVM {
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
             }
             TSCOpCode::FMU => {
-                state.sound_manager.play_song(0, &state.constants, &state.settings, ctx)?;
+                state.sound_manager.fade_out_music(100, state.current_tps());
 
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
             }
@@ -1309,7 +1342,11 @@ impl TextScriptVM {
             TSCOpCode::DNP => {
                 let event_num = read_cur_varint(&mut cursor)? as u16;
 
-                game_scene.npc_list.remove_by_event(event_num, state);
+                if state.constants.textscript.clear_room_event == Some(event_num) {
+                    game_scene.clear_room(state);
+                } else {
+                    game_scene.npc_list.remove_by_event(event_num, state);
+                }
 
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
             }
@@ -1379,21 +1416,19 @@ impl TextScriptVM {
                 let tsc_direction = read_cur_varint(&mut cursor)? as usize;
                 let direction = Direction::from_int_facing(tsc_direction).unwrap_or(Direction::Left);
 
-                for npc in game_scene.npc_list.iter_alive() {
-                    if npc.event_num == event_num {
-                        npc.action_num = action_num;
-                        npc.tsc_direction = tsc_direction as u16;
+                for npc in game_scene.npc_list.entities_with_event(event_num) {
+                    npc.action_num = action_num;
+                    npc.tsc_direction = tsc_direction as u16;
 
-                        if direction == Direction::FacingPlayer {
-                            let player = match state.textscript_vm.executor_player {
-                                TargetPlayer::Player1 => &game_scene.player1,
-                                TargetPlayer::Player2 => &game_scene.player2,
-                            };
+                    if direction == Direction::FacingPlayer {
+                        let player = match state.textscript_vm.executor_player {
+                            TargetPlayer::Player1 => &game_scene.player1,
+                            TargetPlayer::Player2 => &game_scene.player2,
+                        };
 
-                            npc.direction = if player.x < npc.x { Direction::Left } else { Direction::Right };
-                        } else if tsc_direction != 5 {
-                            npc.direction = direction;
-                        }
+                        npc.direction = if player.x < npc.x { Direction::Left } else { Direction::Right };
+                    } else if tsc_direction != 5 {
+                        npc.direction = direction;
                     }
                 }
 
@@ -1405,65 +1440,74 @@ impl TextScriptVM {
                 let tsc_direction = read_cur_varint(&mut cursor)? as usize;
                 let direction = Direction::from_int_facing(tsc_direction).unwrap_or(Direction::Left);
 
-                for npc in game_scene.npc_list.iter_alive() {
-                    if npc.event_num == event_num {
-                        npc.npc_flags.set_solid_soft(false);
-                        npc.npc_flags.set_ignore_tile_44(false);
-                        npc.npc_flags.set_invulnerable(false);
-                        npc.npc_flags.set_ignore_solidity(false);
-                        npc.npc_flags.set_bouncy(false);
-                        npc.npc_flags.set_shootable(false);
-                        npc.npc_flags.set_solid_hard(false);
-                        npc.npc_flags.set_rear_and_top_not_hurt(false);
-                        npc.npc_flags.set_show_damage(false);
-
-                        if op == TSCOpCode::INP {
-                            npc.npc_flags.set_event_when_touched(true);
-                        }
-
-                        npc.npc_type = new_type;
-                        npc.display_bounds = state.npc_table.get_display_bounds(new_type);
-                        npc.hit_bounds = state.npc_table.get_hit_bounds(new_type);
-                        let entry = state.npc_table.get_entry(new_type).unwrap().to_owned();
-                        npc.npc_flags.0 |= entry.npc_flags.0;
-                        npc.life = entry.life;
-                        npc.size = entry.size;
-                        npc.exp = entry.experience as u16;
-                        npc.damage = entry.damage as u16;
-                        npc.spritesheet_id = entry.spritesheet_id as u16;
-
-                        npc.cond.set_alive(true);
-                        npc.action_num = 0;
-                        npc.action_counter = 0;
-                        npc.anim_num = 0;
-                        npc.anim_counter = 0;
-                        npc.vel_x = 0;
-                        npc.vel_y = 0;
-                        npc.tsc_direction = tsc_direction as u16;
-
-                        if direction == Direction::FacingPlayer {
-                            let player = match state.textscript_vm.executor_player {
-                                TargetPlayer::Player1 => &game_scene.player1,
-                                TargetPlayer::Player2 => &game_scene.player2,
-                            };
+                for npc in game_scene.npc_list.entities_with_event(event_num) {
+                    npc.npc_flags.set_solid_soft(false);
+                    npc.npc_flags.set_ignore_tile_44(false);
+                    npc.npc_flags.set_invulnerable(false);
+                    npc.npc_flags.set_ignore_solidity(false);
+                    npc.npc_flags.set_bouncy(false);
+                    npc.npc_flags.set_shootable(false);
+                    npc.npc_flags.set_solid_hard(false);
+                    npc.npc_flags.set_rear_and_top_not_hurt(false);
+                    npc.npc_flags.set_show_damage(false);
+
+                    if op == TSCOpCode::INP {
+                        npc.npc_flags.set_event_when_touched(true);
+                    }
 
-                            npc.direction = if player.x < npc.x { Direction::Left } else { Direction::Right };
-                        } else if tsc_direction != 5 {
-                            npc.direction = direction;
-                        }
+                    npc.npc_type = new_type;
+                    npc.display_bounds = state.npc_table.get_display_bounds(new_type);
+                    npc.hit_bounds = state.npc_table.get_hit_bounds(new_type);
+                    // mods can change NPCs into types past the end of npc.tbl, those get the NPC::create defaults
+                    let (flags, life, size, exp, damage, spritesheet_id) = match state.npc_table.get_entry(new_type) {
+                        Some(entry) => (
+                            entry.npc_flags.0,
+                            entry.life,
+                            entry.size,
+                            entry.experience as u16,
+                            entry.damage as u16,
+                            entry.spritesheet_id as u16,
+                        ),
+                        None => (0, 0, 2, 0, 0, 0),
+                    };
+                    npc.npc_flags.0 |= flags;
+                    npc.life = life;
+                    npc.size = size;
+                    npc.exp = exp;
+                    npc.damage = damage;
+                    npc.spritesheet_id = spritesheet_id;
 
-                        npc.tick(
-                            state,
-                            (
-                                [&mut game_scene.player1, &mut game_scene.player2],
-                                &game_scene.npc_list,
-                                &mut game_scene.stage,
-                                &mut game_scene.bullet_manager,
-                                &mut game_scene.flash,
-                                &mut game_scene.boss,
-                            ),
-                        )?;
+                    npc.cond.set_alive(true);
+                    npc.action_num = 0;
+                    npc.action_counter = 0;
+                    npc.anim_num = 0;
+                    npc.anim_counter = 0;
+                    npc.vel_x = 0;
+                    npc.vel_y = 0;
+                    npc.tsc_direction = tsc_direction as u16;
+
+                    if direction == Direction::FacingPlayer {
+                        let player = match state.textscript_vm.executor_player {
+                            TargetPlayer::Player1 => &game_scene.player1,
+                            TargetPlayer::Player2 => &game_scene.player2,
+                        };
+
+                        npc.direction = if player.x < npc.x { Direction::Left } else { Direction::Right };
+                    } else if tsc_direction != 5 {
+                        npc.direction = direction;
                     }
+
+                    npc.tick(
+                        state,
+                        (
+                            [&mut game_scene.player1, &mut game_scene.player2],
+                            &game_scene.npc_list,
+                            &mut game_scene.stage,
+                            &mut game_scene.bullet_manager,
+                            &mut game_scene.flash,
+                            &mut game_scene.boss,
+                        ),
+                    )?;
                 }
 
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
@@ -1476,25 +1520,23 @@ impl TextScriptVM {
                 let direction = Direction::from_int_facing(tsc_direction).unwrap_or(Direction::Left);
                 let block_size = state.tile_size.as_int() * 0x200;
 
-                for npc in game_scene.npc_list.iter_alive() {
-                    if npc.event_num == event_num {
-                        npc.x = x * block_size;
-                        npc.y = y * block_size;
-                        npc.tsc_direction = tsc_direction as u16;
+                for npc in game_scene.npc_list.entities_with_event(event_num) {
+                    npc.x = x * block_size;
+                    npc.y = y * block_size;
+                    npc.tsc_direction = tsc_direction as u16;
 
-                        if direction == Direction::FacingPlayer {
-                            let player = match state.textscript_vm.executor_player {
-                                TargetPlayer::Player1 => &game_scene.player1,
-                                TargetPlayer::Player2 => &game_scene.player2,
-                            };
-
-                            npc.direction = if player.x < npc.x { Direction::Left } else { Direction::Right };
-                        } else if tsc_direction != 5 {
-                            npc.direction = direction;
-                        }
+                    if direction == Direction::FacingPlayer {
+                        let player = match state.textscript_vm.executor_player {
+                            TargetPlayer::Player1 => &game_scene.player1,
+                            TargetPlayer::Player2 => &game_scene.player2,
+                        };
 
-                        break;
+                        npc.direction = if player.x < npc.x { Direction::Left } else { Direction::Right };
+                    } else if tsc_direction != 5 {
+                        npc.direction = direction;
                     }
+
+                    break;
                 }
 
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
@@ -1551,6 +1593,8 @@ impl TextScriptVM {
                     state.mod_requirements.append_item(ctx, item_id)?;
                 }
 
+                state.unsaved_progress = true;
+
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
             }
             TSCOpCode::IpN => {
@@ -1569,6 +1613,8 @@ impl TextScriptVM {
                     state.mod_requirements.append_item(ctx, item_id)?;
                 }
 
+                state.unsaved_progress = true;
+
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
             }
             TSCOpCode::ITm => {
@@ -1579,6 +1625,8 @@ impl TextScriptVM {
                 game_scene.inventory_player1.current_item = 0;
                 game_scene.inventory_player2.current_item = 0;
 
+                state.unsaved_progress = true;
+
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
             }
             TSCOpCode::AMp => {
@@ -1596,6 +1644,8 @@ impl TextScriptVM {
                     state.mod_requirements.append_weapon(ctx, weapon_id as u16)?;
                 }
 
+                state.unsaved_progress = true;
+
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
             }
             TSCOpCode::AMm => {
@@ -1607,6 +1657,8 @@ impl TextScriptVM {
                     game_scene.inventory_player2.remove_weapon(wtype);
                 }
 
+                state.unsaved_progress = true;
+
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
             }
             TSCOpCode::AEp => {
@@ -1652,9 +1704,10 @@ impl TextScriptVM {
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
             }
             TSCOpCode::FLA => {
-                game_scene.flash.set_blink();
+                let duration = state.constants.textscript.flash_duration;
+                game_scene.flash.set_fade(duration);
 
-                exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
+                exec_state = TextScriptExecutionState::WaitTicks(event, cursor.position() as u32, duration);
             }
             TSCOpCode::INI => {
                 game_scene.player1.flags.0 = 0;
@@ -1771,10 +1824,13 @@ impl TextScriptVM {
                 );
             }
             TSCOpCode::STC => {
-                let new_record = game_scene.nikumaru.save_counter(state, ctx)?;
+                // watching a replay must not count as setting a time
+                if !matches!(state.replay_state, ReplayState::Playback(_)) {
+                    let new_record = game_scene.nikumaru.save_counter(state, ctx)?;
 
-                if state.replay_state == ReplayState::Recording {
-                    game_scene.replay.stop_recording(state, ctx, new_record)?;
+                    if state.replay_state == ReplayState::Recording {
+                        game_scene.replay.stop_recording(state, ctx, new_record)?;
+                    }
                 }
 
                 exec_state = TextScriptExecutionState::Running(event, cursor.position() as u32);
@@ -1849,3 +1905,133 @@ impl TextScript {
         self.event_map.contains_key(&id)
     }
 }
+
+/// Whether the player can end a `<WAS` wait.
+fn is_standing(player: &Player) -> bool {
+    // collision flags of a dropped player 2 are stale and would end the wait early
+    player.cond.alive() && player.flags.hit_bottom_wall()
+}
+
+/// Pushes the player away from the NPC at `npc_x`, turning them to face it.
+fn knock_back_from(player: &mut Player, npc_x: i32) {
+    if player.x >= npc_x {
+        player.direction = Left;
+        player.vel_x = 0x200;
+    } else {
+        player.direction = Right;
+        player.vel_x = -0x200;
+    }
+}
+
+#[test]
+fn test_myb_knocks_back_from_npc() {
+    use crate::game::HeadlessRunner;
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    let mut player = Player::new(&mut state, &mut ctx);
+
+    // standing right of the NPC, pushed further right
+    player.x = 0x6000;
+    knock_back_from(&mut player, 0x4000);
+    assert_eq!(player.direction, Left);
+    assert_eq!(player.vel_x, 0x200);
+
+    // standing left of it, pushed further left
+    player.x = 0x2000;
+    knock_back_from(&mut player, 0x4000);
+    assert_eq!(player.direction, Right);
+    assert_eq!(player.vel_x, -0x200);
+}
+
+#[test]
+fn test_myb_knock_back_direction() {
+    use crate::game::npc::NPC;
+    use crate::game::stage::Stage;
+    use crate::game::HeadlessRunner;
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    let stage = Stage::blank(20, 15);
+    state.stages.push(stage.data.clone());
+    let mut scene = GameScene::from_stage(&mut state, &mut ctx, stage, 0).unwrap();
+    let script = TextScript::compile(b"#0100\r\n<MYB0000<END\r\n", true, TextScriptEncoding::UTF8).unwrap();
+    state.textscript_vm.set_scene_script(script);
+    // started by hand, without going through `init`
+    state.textscript_vm.suspend = false;
+
+    let mut npc = NPC::empty();
+    npc.cond.set_alive(true);
+    npc.x = 0x8000;
+    scene.npc_list.spawn(0x100, npc).unwrap();
+    let npc_id = scene.npc_list.iter_alive().next().unwrap().id;
+
+    // the player stands left of the NPC that started the event, originally always knocked to the right
+    for (away_from_executor, direction, vel_x) in [(false, Left, 0x200), (true, Right, -0x200)] {
+        state.constants.textscript.myb_away_from_executor = away_from_executor;
+        scene.player1.x = 0x4000;
+        scene.player1.vel_x = 0;
+
+        state.textscript_vm.start_script(100);
+        state.textscript_vm.executor_npc = Some(npc_id);
+        TextScriptVM::run(&mut state, &mut scene, &mut ctx).unwrap();
+
+        assert_eq!(state.textscript_vm.state, TextScriptExecutionState::Ended);
+        assert_eq!(scene.player1.direction, direction);
+        assert_eq!(scene.player1.vel_x, vel_x);
+    }
+}
+
+#[test]
+fn test_was_waits_for_landing() {
+    use crate::game::HeadlessRunner;
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    let mut player1 = Player::new(&mut state, &mut ctx);
+    let mut player2 = Player::new(&mut state, &mut ctx);
+
+    // player 1 is still falling, player 2 left the game while standing
+    player1.cond.set_alive(true);
+    player2.cond.set_alive(false);
+    player2.flags.set_hit_bottom_wall(true);
+    assert!(!is_standing(&player1) && !is_standing(&player2));
+
+    player1.flags.set_hit_bottom_wall(true);
+    assert!(is_standing(&player1));
+}
+
+#[test]
+fn test_fla_holds_script_until_faded() {
+    use crate::entity::GameEntity;
+    use crate::game::stage::Stage;
+    use crate::game::HeadlessRunner;
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    let stage = Stage::blank(20, 15);
+    state.stages.push(stage.data.clone());
+    let mut scene = GameScene::from_stage(&mut state, &mut ctx, stage, 0).unwrap();
+    let script = TextScript::compile(b"#0100\r\n<FLA<FL+0001<END\r\n", true, TextScriptEncoding::UTF8).unwrap();
+    state.textscript_vm.set_scene_script(script);
+    // started by hand, without going through `init`
+    state.textscript_vm.suspend = false;
+
+    // ticked in the same order as the game scene does
+    state.textscript_vm.start_script(100);
+    TextScriptVM::run(&mut state, &mut scene, &mut ctx).unwrap();
+    scene.flash.tick(&mut state, ()).unwrap();
+    assert!(scene.flash.is_active());
+
+    let duration = state.constants.textscript.flash_duration as usize;
+    let mut resumed_at = None;
+    for tick in 1..=duration * 2 {
+        TextScriptVM::run(&mut state, &mut scene, &mut ctx).unwrap();
+        scene.flash.tick(&mut state, ()).unwrap();
+
+        // the script goes on once the flash is gone, and it doesn't come back
+        assert_eq!(state.get_flag(1), !scene.flash.is_active());
+        if state.get_flag(1) {
+            resumed_at.get_or_insert(tick);
+        }
+    }
+
+    assert_eq!(resumed_at, Some(duration));
+    assert_eq!(state.textscript_vm.state, TextScriptExecutionState::Ended);
+}
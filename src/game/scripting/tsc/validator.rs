@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use crate::framework::context::Context;
+use crate::framework::error::GameResult;
+use crate::game::scripting::tsc::opcodes::TSCOpCode;
+use crate::game::scripting::tsc::text_script::TextScript;
+use crate::game::shared_game_state::SharedGameState;
+use crate::game::stage::Stage;
+
+/// Scans `<TRA` targets of every stage script (and Head.tsc) and prints those pointing at stages
+/// that don't exist or at spawn coordinates outside of the target map. Returns the number of broken targets.
+pub fn validate_warps(state: &SharedGameState, ctx: &mut Context) -> GameResult<usize> {
+    let roots = &state.constants.base_paths;
+    let mut map_sizes: HashMap<usize, Option<(u16, u16)>> = HashMap::new();
+    let mut scripts = Vec::new();
+
+    for (id, data) in state.stages.iter().enumerate() {
+        let stage = match Stage::load(roots, data, ctx) {
+            Ok(stage) => stage,
+            Err(_) => {
+                map_sizes.insert(id, None);
+                continue;
+            }
+        };
+
+        map_sizes.insert(id, Some((stage.map.width, stage.map.height)));
+
+        match stage.load_text_script(roots, &state.constants, ctx) {
+            Ok(script) => scripts.push((format!("{} ({})", data.map, id), script)),
+            Err(e) => log::warn!("Failed to load script of stage {} ({}): {}", data.map, id, e),
+        }
+    }
+
+    let mut report = validate_script(state, "Head", &state.textscript_vm.scripts.borrow().global_script, &map_sizes)?;
+    for (source, script) in scripts.iter() {
+        report.extend(validate_script(state, source, script, &map_sizes)?);
+    }
+
+    for line in report.iter() {
+        println!("{}", line);
+    }
+
+    println!("Checked {} scripts, found {} broken warp targets.", scripts.len() + 1, report.len());
+
+    Ok(report.len())
+}
+
+/// Returns a line describing every broken `<TRA` target in the script.
+fn validate_script(
+    state: &SharedGameState,
+    source: &str,
+    script: &TextScript,
+    map_sizes: &HashMap<usize, Option<(u16, u16)>>,
+) -> GameResult<Vec<String>> {
+    let mut report = Vec::new();
+
+    for event in script.get_event_ids() {
+        for args in script.find_opcode_operands(event, TSCOpCode::TRA)? {
+            let (map_id, target_event, x, y) = (args[0], args[1], args[2], args[3]);
+
+            let size = if map_id < 0 { None } else { map_sizes.get(&(map_id as usize)).copied().flatten() };
+            let problem = match size {
+                None => Some("stage does not exist".to_owned()),
+                Some((width, height)) if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 => {
+                    Some(format!("spawn point is out of bounds of {}x{} map", width, height))
+                }
+                _ => None,
+            };
+
+            if let Some(problem) = problem {
+                let target_name = state.stages.get(map_id as usize).map(|s| s.map.as_str()).unwrap_or("?");
+                report.push(format!(
+                    "{} event #{:04}: <TRA{:04}:{:04}:{:04}:{:04} -> {}: {}",
+                    source, event, map_id, target_event, x, y, target_name, problem
+                ));
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[test]
+fn test_validate_script() {
+    use crate::game::scripting::tsc::text_script::TextScriptEncoding;
+    use crate::game::HeadlessRunner;
+
+    let (_, state) = HeadlessRunner::bare_state();
+
+    let script = b"#0090\r\n<TRA0001:0094:0005:0005\r\n\
+        #0091\r\n<KEY<TRA0007:0094:0005:0005\r\n\
+        #0092\r\n<TRA0001:0095:0010:0002\r\n";
+    let script = TextScript::compile(script, true, TextScriptEncoding::UTF8).unwrap();
+    let map_sizes = HashMap::from([(0, Some((20, 20))), (1, Some((10, 10))), (2, None)]);
+
+    let report = validate_script(&state, "Cave (12)", &script, &map_sizes).unwrap();
+    assert_eq!(
+        report,
+        vec![
+            "Cave (12) event #0091: <TRA0007:0094:0005:0005 -> ?: stage does not exist".to_owned(),
+            "Cave (12) event #0092: <TRA0001:0095:0010:0002 -> ?: spawn point is out of bounds of 10x10 map".to_owned(),
+        ]
+    );
+}
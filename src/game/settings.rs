@@ -2,10 +2,12 @@ use crate::framework::context::Context;
 use crate::framework::error::GameResult;
 use crate::framework::filesystem::{user_create, user_open};
 use crate::framework::gamepad::{Axis, AxisDirection, Button, PlayerControllerInputType};
-use crate::framework::graphics::VSyncMode;
+use crate::framework::graphics::{FilterMode, VSyncMode};
 use crate::framework::keyboard::ScanCode;
 use crate::game::player::TargetPlayer;
-use crate::game::shared_game_state::{CutsceneSkipMode, ScreenShakeIntensity, TimingMode, WindowMode};
+use crate::game::shared_game_state::{
+    CanvasMode, CutsceneSkipMode, ScalingMode, ScreenShakeIntensity, TimingMode, WindowMode,
+};
 use crate::input::combined_player_controller::CombinedPlayerController;
 use crate::input::gamepad_player_controller::GamepadController;
 use crate::input::keyboard_player_controller::KeyboardController;
@@ -81,6 +83,66 @@ pub struct Settings {
     pub more_rust: bool,
     #[serde(default = "default_cutscene_skip_mode")]
     pub cutscene_skip_mode: CutsceneSkipMode,
+    #[serde(default = "default_camera_look_shift")]
+    pub camera_look_shift: bool,
+    #[serde(default = "default_deterministic_effects")]
+    pub deterministic_effects: bool,
+    #[serde(default = "default_boot_splash")]
+    pub boot_splash: bool,
+    #[serde(default = "default_transition_invincibility")]
+    pub transition_invincibility: u16,
+    /// Color of the area around the game view, as (r, g, b).
+    #[serde(default = "default_letterbox_color")]
+    pub letterbox_color: (u8, u8, u8),
+    /// Name of a texture tiled over the area around the game view instead of the plain color.
+    #[serde(default = "default_letterbox_texture")]
+    pub letterbox_texture: Option<String>,
+    #[serde(default = "default_water_exit_boost")]
+    pub water_exit_boost: bool,
+    #[serde(default = "default_pixel_snapping")]
+    pub pixel_snapping: bool,
+    #[serde(default = "default_reduced_flashing")]
+    pub reduced_flashing: bool,
+    #[serde(default = "default_scaling_mode")]
+    pub scaling_mode: ScalingMode,
+    #[serde(default = "default_master_volume")]
+    pub master_volume: f32,
+    #[serde(default = "default_screenshot_key")]
+    pub screenshot_key: ScanCode,
+    /// Save screenshots at the internal resolution instead of the window resolution.
+    #[serde(default)]
+    pub screenshot_internal_resolution: bool,
+    /// Lower the music volume while the pause menu is open.
+    #[serde(default)]
+    pub pause_duck_music: bool,
+    /// Pan and attenuate sound effects by where they're played in the world.
+    #[serde(default = "default_true")]
+    pub positional_audio: bool,
+    /// Distance past the edge of the screen at which positioned sound effects become silent, in pixels.
+    #[serde(default = "default_positional_audio_range")]
+    pub positional_audio_range: u16,
+    /// Sample textures with linear filtering instead of nearest neighbour, smooths out non-integer scaling.
+    #[serde(default)]
+    pub linear_filtering: bool,
+    /// Maximum frames drawn per second with V-Sync off, 0 for no limit. Doesn't affect the game's tick rate.
+    #[serde(default)]
+    pub frame_cap: u16,
+    /// Strength of gamepad rumble in percent, 0 turns it off for every player.
+    #[serde(default = "default_rumble_intensity")]
+    pub rumble_intensity: u8,
+    /// Path of a BMFont `.fnt` file in the data directories used instead of the locale's font, empty to disable.
+    /// Allows mods to ship wider glyph sets, TrueType fonts can be converted with the AngelCode BMFont tool.
+    #[serde(default)]
+    pub custom_font: String,
+    #[serde(default = "default_custom_font_scale")]
+    pub custom_font_scale: f32,
+    /// Keys saving and loading a snapshot of the game in a separate slot, without going through the save points.
+    #[serde(default = "default_quicksave_key")]
+    pub quicksave_key: ScanCode,
+    #[serde(default = "default_quickload_key")]
+    pub quickload_key: ScanCode,
+    #[serde(default = "default_canvas_mode")]
+    pub canvas_mode: CanvasMode,
 }
 
 fn default_true() -> bool {
@@ -89,7 +151,7 @@ fn default_true() -> bool {
 
 #[inline(always)]
 fn current_version() -> u32 {
-    21
+    41
 }
 
 #[inline(always)]
@@ -165,6 +227,97 @@ fn default_cutscene_skip_mode() -> CutsceneSkipMode {
     CutsceneSkipMode::Hold
 }
 
+#[inline(always)]
+fn default_camera_look_shift() -> bool {
+    false
+}
+
+#[inline(always)]
+fn default_deterministic_effects() -> bool {
+    false
+}
+
+#[inline(always)]
+fn default_boot_splash() -> bool {
+    false
+}
+
+#[inline(always)]
+fn default_transition_invincibility() -> u16 {
+    0
+}
+
+#[inline(always)]
+fn default_letterbox_color() -> (u8, u8, u8) {
+    (0, 0, 0)
+}
+
+#[inline(always)]
+fn default_letterbox_texture() -> Option<String> {
+    None
+}
+
+#[inline(always)]
+fn default_water_exit_boost() -> bool {
+    false
+}
+
+#[inline(always)]
+fn default_pixel_snapping() -> bool {
+    false
+}
+
+#[inline(always)]
+fn default_reduced_flashing() -> bool {
+    false
+}
+
+#[inline(always)]
+fn default_scaling_mode() -> ScalingMode {
+    ScalingMode::Integer
+}
+
+#[inline(always)]
+fn default_master_volume() -> f32 {
+    1.0
+}
+
+#[inline(always)]
+fn default_screenshot_key() -> ScanCode {
+    // the other function keys are taken by debug hotkeys
+    ScanCode::F1
+}
+
+#[inline(always)]
+fn default_positional_audio_range() -> u16 {
+    160
+}
+
+#[inline(always)]
+fn default_rumble_intensity() -> u8 {
+    100
+}
+
+#[inline(always)]
+fn default_custom_font_scale() -> f32 {
+    1.0
+}
+
+#[inline(always)]
+fn default_quicksave_key() -> ScanCode {
+    ScanCode::F5
+}
+
+#[inline(always)]
+fn default_quickload_key() -> ScanCode {
+    ScanCode::F9
+}
+
+#[inline(always)]
+fn default_canvas_mode() -> CanvasMode {
+    CanvasMode::Extended
+}
+
 impl Settings {
     pub fn load(ctx: &Context) -> GameResult<Settings> {
         if let Ok(file) = user_open(ctx, "/settings.json") {
@@ -177,6 +330,14 @@ impl Settings {
         Ok(Settings::default())
     }
 
+    pub fn texture_filter(&self) -> FilterMode {
+        if self.linear_filtering {
+            FilterMode::Linear
+        } else {
+            FilterMode::Nearest
+        }
+    }
+
     fn upgrade(mut self) -> Self {
         let initial_version = self.version;
 
@@ -327,6 +488,113 @@ impl Settings {
             };
         }
 
+        if self.version == 21 {
+            self.version = 22;
+            self.camera_look_shift = default_camera_look_shift();
+        }
+
+        if self.version == 22 {
+            self.version = 23;
+            self.deterministic_effects = default_deterministic_effects();
+        }
+
+        if self.version == 23 {
+            self.version = 24;
+            self.boot_splash = default_boot_splash();
+        }
+
+        if self.version == 24 {
+            self.version = 25;
+            self.transition_invincibility = default_transition_invincibility();
+        }
+
+        if self.version == 25 {
+            self.version = 26;
+            self.letterbox_color = default_letterbox_color();
+            self.letterbox_texture = default_letterbox_texture();
+        }
+
+        if self.version == 26 {
+            self.version = 27;
+            self.water_exit_boost = default_water_exit_boost();
+        }
+
+        if self.version == 27 {
+            self.version = 28;
+            self.pixel_snapping = default_pixel_snapping();
+        }
+
+        if self.version == 28 {
+            self.version = 29;
+            self.reduced_flashing = default_reduced_flashing();
+        }
+
+        if self.version == 29 {
+            self.version = 30;
+            self.scaling_mode = default_scaling_mode();
+        }
+
+        if self.version == 30 {
+            self.version = 31;
+            self.master_volume = default_master_volume();
+        }
+
+        if self.version == 31 {
+            self.version = 32;
+            self.screenshot_key = default_screenshot_key();
+            self.screenshot_internal_resolution = false;
+        }
+
+        if self.version == 32 {
+            self.version = 33;
+            self.pause_duck_music = false;
+        }
+
+        if self.version == 33 {
+            self.version = 34;
+            self.positional_audio = true;
+            self.positional_audio_range = default_positional_audio_range();
+        }
+
+        if self.version == 34 {
+            self.version = 35;
+            self.linear_filtering = false;
+        }
+
+        if self.version == 35 {
+            self.version = 36;
+            self.frame_cap = 0;
+        }
+
+        if self.version == 36 {
+            self.version = 37;
+            self.rumble_intensity = default_rumble_intensity();
+        }
+
+        if self.version == 37 {
+            self.version = 38;
+            self.custom_font = String::new();
+            self.custom_font_scale = default_custom_font_scale();
+        }
+
+        if self.version == 38 {
+            self.version = 39;
+            self.quicksave_key = default_quicksave_key();
+            self.quickload_key = default_quickload_key();
+        }
+
+        if self.version == 39 {
+            self.version = 40;
+            self.canvas_mode = default_canvas_mode();
+        }
+
+        if self.version == 40 {
+            self.version = 41;
+            if self.screenshot_key == ScanCode::F12 {
+                self.screenshot_key = default_screenshot_key();
+            }
+        }
+
         if self.version != initial_version {
             log::info!("Upgraded configuration file from version {} to {}.", initial_version, self.version);
         }
@@ -432,6 +700,30 @@ impl Default for Settings {
             noclip: false,
             more_rust: false,
             cutscene_skip_mode: CutsceneSkipMode::Hold,
+            camera_look_shift: default_camera_look_shift(),
+            deterministic_effects: default_deterministic_effects(),
+            boot_splash: default_boot_splash(),
+            transition_invincibility: default_transition_invincibility(),
+            letterbox_color: default_letterbox_color(),
+            letterbox_texture: default_letterbox_texture(),
+            water_exit_boost: default_water_exit_boost(),
+            pixel_snapping: default_pixel_snapping(),
+            reduced_flashing: default_reduced_flashing(),
+            scaling_mode: default_scaling_mode(),
+            master_volume: default_master_volume(),
+            screenshot_key: default_screenshot_key(),
+            screenshot_internal_resolution: false,
+            pause_duck_music: false,
+            positional_audio: true,
+            positional_audio_range: default_positional_audio_range(),
+            linear_filtering: false,
+            frame_cap: 0,
+            rumble_intensity: default_rumble_intensity(),
+            custom_font: String::new(),
+            custom_font_scale: default_custom_font_scale(),
+            quicksave_key: default_quicksave_key(),
+            quickload_key: default_quickload_key(),
+            canvas_mode: default_canvas_mode(),
         }
     }
 }
@@ -474,6 +766,27 @@ pub fn p1_default_keymap() -> PlayerKeyMap {
     }
 }
 
+/// Alternative layout for player 1 that keeps movement under the left hand.
+#[inline(always)]
+pub fn p1_wasd_keymap() -> PlayerKeyMap {
+    PlayerKeyMap {
+        left: ScanCode::A,
+        up: ScanCode::W,
+        right: ScanCode::D,
+        down: ScanCode::S,
+        prev_weapon: ScanCode::Q,
+        next_weapon: ScanCode::E,
+        jump: ScanCode::J,
+        shoot: ScanCode::K,
+        skip: ScanCode::Tab,
+        inventory: ScanCode::Tab,
+        map: ScanCode::M,
+        strafe: ScanCode::LShift,
+        menu_ok: ScanCode::J,
+        menu_back: ScanCode::K,
+    }
+}
+
 #[inline(always)]
 pub fn p2_default_keymap() -> PlayerKeyMap {
     PlayerKeyMap {
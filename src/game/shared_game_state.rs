@@ -1,9 +1,11 @@
+use std::collections::VecDeque;
 use std::{cmp, ops::Div};
 
 use chrono::{Datelike, Local};
 
 use crate::common::{ControlFlags, Direction, FadeState};
 use crate::components::draw_common::{draw_number, Alignment};
+use crate::data::sif::SifFile;
 use crate::data::vanilla::VanillaExtractor;
 use crate::engine_constants::EngineConstants;
 use crate::framework::backend::BackendTexture;
@@ -12,7 +14,7 @@ use crate::framework::error::GameResult;
 use crate::framework::graphics::{create_texture_mutable, set_render_target};
 use crate::framework::vfs::OpenOptions;
 use crate::framework::{filesystem, graphics};
-use crate::game::caret::{Caret, CaretType};
+use crate::game::caret::{Caret, CaretPool, CaretType};
 use crate::game::npc::NPCTable;
 use crate::game::profile::GameProfile;
 #[cfg(feature = "scripting-lua")]
@@ -34,6 +36,9 @@ use crate::sound::SoundManager;
 use crate::util::bitvec::BitVec;
 use crate::util::rng::XorShift;
 
+/// Maximum number of carets alive at once, past that the oldest ones get replaced.
+const MAX_CARETS: usize = 512;
+
 #[derive(PartialEq, Eq, Copy, Clone, serde::Serialize, serde::Deserialize)]
 pub enum TimingMode {
     _50Hz,
@@ -82,6 +87,14 @@ impl WindowMode {
         }
     }
 
+    #[cfg(feature = "backend-glutin")]
+    pub fn get_glutin_fullscreen_type(&self) -> Option<glutin::window::Fullscreen> {
+        match self {
+            WindowMode::Windowed => None,
+            WindowMode::Fullscreen => Some(glutin::window::Fullscreen::Borderless(None)),
+        }
+    }
+
     pub fn should_display_mouse_cursor(&self) -> bool {
         match self {
             WindowMode::Windowed => true,
@@ -90,6 +103,24 @@ impl WindowMode {
     }
 }
 
+#[derive(PartialEq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub enum ScalingMode {
+    /// Largest whole multiple of the viewport size that fits in the window.
+    Integer,
+    /// Fills the window, even if it means using a fractional scale.
+    Stretch,
+    /// Fixed scale factor, only settable through the settings file.
+    Fixed(f32),
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub enum CanvasMode {
+    /// The canvas grows with the window, showing more of the map on wide screens.
+    Extended,
+    /// The canvas never exceeds the original 320x240 viewport and is centered in the window, letterboxed on every side.
+    Classic,
+}
+
 #[derive(PartialEq, Eq, Copy, Clone, Debug, num_derive::FromPrimitive)]
 pub enum GameDifficulty {
     Normal = 0,
@@ -171,9 +202,15 @@ impl Fps {
         }
 
         if self.should_draw {
+            // taken before drawing the numbers, which are batches of their own
+            let batch_draws = ctx.batch_draws;
             let first = draw_number(state.canvas_size.0 - 8.0, 8.0, self.fps as usize, Alignment::Right, state, ctx);
             let second = draw_number(state.canvas_size.0 - 8.0, 16.0, self.tps as usize, Alignment::Right, state, ctx);
             self.should_draw = first.is_ok() && second.is_ok();
+
+            if state.settings.debug_outlines {
+                draw_number(state.canvas_size.0 - 8.0, 24.0, batch_draws, Alignment::Right, state, ctx)?;
+            }
         }
 
         Ok(())
@@ -275,6 +312,14 @@ impl Default for PlayerSkinLocation {
     }
 }
 
+/// An effect queued with [`SharedGameState::defer`], applied in order once the current tick is over.
+pub enum DeferredAction {
+    PlaySfx(u8),
+    StopSfx(u8),
+    SetFlag(usize, bool),
+    ChangeScene(Box<dyn Scene>),
+}
+
 pub struct SharedGameState {
     pub control_flags: ControlFlags,
     pub game_flags: BitVec,
@@ -284,14 +329,22 @@ pub struct SharedGameState {
     /// RNG used by game state, using it for anything else might cause unintended side effects and break replays.
     pub game_rng: XorShift,
     /// RNG used by graphics effects that aren't dependent on game's state.
+    ///
+    /// This is an independent stream from `game_rng` - drawing from one never advances the other,
+    /// so a fixed seed yields stable sequences for both regardless of how many effects are spawned.
     pub effect_rng: XorShift,
+    /// Fixed RNG seed, set through `reseed` or the `CAVESTORY_RNG_SEED` environment variable.
+    /// If set, `reset` seeds `game_rng` from it instead of the current time.
+    pub rng_seed: Option<u64>,
     pub tile_size: TileSize,
     pub quake_counter: u16,
     pub super_quake_counter: u16,
     pub quake_rumble_counter: u32,
     pub super_quake_rumble_counter: u32,
     pub teleporter_slots: Vec<(u16, u16)>,
-    pub carets: Vec<Caret>,
+    /// Ticks the world has run since the game was started, kept in the `counter` field of the profile.
+    pub play_counter: u32,
+    pub carets: CaretPool,
     pub touch_controls: TouchControls,
     pub mod_path: Option<String>,
     pub mod_list: ModList,
@@ -304,11 +357,19 @@ pub struct SharedGameState {
     pub frame_time: f64,
     pub debugger: bool,
     pub command_line: bool,
+    /// Debug frame advance, while set the game scene only ticks when `frame_step` is requested.
+    pub frame_advance: bool,
+    pub frame_step: bool,
+    /// Set by the game scene on ticks held back by frame advance, so key taps are kept until the next step.
+    pub frame_held: bool,
     pub scale: f32,
     pub canvas_size: (f32, f32),
+    /// Position of the canvas inside the window in screen pixels, non-zero only when it's centered in classic mode.
+    pub canvas_offset: (f32, f32),
     pub screen_size: (f32, f32),
     pub preferred_viewport_size: (f32, f32),
     pub next_scene: Option<Box<dyn Scene>>,
+    deferred_actions: VecDeque<DeferredAction>,
     pub textscript_vm: TextScriptVM,
     pub creditscript_vm: CreditScriptVM,
     pub lightmap_canvas: Option<Box<dyn BackendTexture>>,
@@ -332,6 +393,12 @@ pub struct SharedGameState {
     pub tutorial_counter: u16,
     pub more_rust: bool,
     pub shutdown: bool,
+    /// Set when flags or items change, cleared when the game is saved or loaded.
+    pub unsaved_progress: bool,
+    /// Set when the window was asked to close while there's unsaved progress.
+    pub quit_requested: bool,
+    /// Human-readable name of the detected game data release, used in build info dumps.
+    pub data_variant: &'static str,
 }
 
 impl SharedGameState {
@@ -361,32 +428,52 @@ impl SharedGameState {
             }
         }
 
+        let mut data_variant = "Freeware";
+
         if filesystem::exists(ctx, "/base/lighting.tbl") {
             log::info!("Cave Story+ (Switch) data files detected.");
+            data_variant = "Cave Story+ (Switch)";
             ctx.size_hint = (854, 480);
             constants.apply_csplus_patches(&mut sound_manager);
             constants.apply_csplus_nx_patches();
             constants.load_nx_stringtable(ctx)?;
         } else if filesystem::exists(ctx, "/base/ogph/SellScreen.bmp") {
             log::info!("WiiWare DEMO data files detected.");
+            data_variant = "WiiWare DEMO";
             constants.apply_csplus_patches(&mut sound_manager);
             constants.apply_csdemo_patches();
         } else if filesystem::exists(ctx, "/base/strap_a_en.bmp") {
             log::info!("WiiWare data files detected."); //Missing Challenges and Remastered Soundtrack but identical to CS+ PC otherwise
+            data_variant = "WiiWare";
             constants.apply_csplus_patches(&mut sound_manager);
         } else if filesystem::exists(ctx, "/root/buid_time.txt") {
             log::error!("DSiWare data files detected. !UNSUPPORTED!"); //Freeware 2.0, sprites are arranged VERY differently + separate drowned carets
+            data_variant = "DSiWare";
         } else if filesystem::exists(ctx, "/darken.tex") || filesystem::exists(ctx, "/darken.png") {
             log::error!("EShop data files detected. !UNSUPPORTED!"); //Ditto, drowned carets finally part of mychar, the turning point towards CS+
+            data_variant = "EShop";
         } else if filesystem::exists(ctx, "/data/stage3d/") {
             log::error!("CS3D data files detected. !UNSUPPORTED!"); //Sprites are technically all there but filenames differ, + no n3ddta support
+            data_variant = "CS3D";
         } else if filesystem::exists(ctx, "/base/Nicalis.bmp") || filesystem::exists(ctx, "/base/Nicalis.png") {
             log::info!("Cave Story+ (PC) data files detected.");
+            data_variant = "Cave Story+ (PC)";
             constants.apply_csplus_patches(&mut sound_manager);
         } else if filesystem::exists(ctx, "/mrmap.bin") {
             log::info!("CSE2E data files detected.");
+            data_variant = "CSE2E";
         } else if filesystem::exists(ctx, "/stage.dat") {
+            // The stage table is read from stage.dat and caret rects from sprites.sif, everything else uses the
+            // original file layout.
             log::info!("NXEngine-evo data files detected.");
+            data_variant = "NXEngine-evo";
+
+            if let Ok(file) = filesystem::open(ctx, "/sprites.sif") {
+                match SifFile::load(file) {
+                    Ok(sif) => constants.apply_sif_patches(&sif),
+                    Err(err) => log::warn!("Failed to load sprites.sif, using the original sprites: {}", err),
+                }
+            }
         }
 
         for soundtrack in constants.soundtracks.iter_mut() {
@@ -403,35 +490,36 @@ impl SharedGameState {
 
         let locale = SharedGameState::get_locale(&constants, &settings.locale).unwrap_or_default();
 
-        let font = BMFont::load(&constants.base_paths, &locale.font.path, ctx, locale.font.scale).or_else(|e| {
-            log::warn!("Failed to load font, using built-in: {}", e);
-            BMFont::load(&vec!["/".to_owned()], "builtin/builtin_font.fnt", ctx, 1.0)
-        })?;
+        let font = SharedGameState::load_font(&constants, &settings, &locale, ctx)?;
 
         let mod_list = ModList::load(ctx, &constants.string_table)?;
 
         for i in 0..0xffu8 {
-            let path = format!("pxt/fx{:02x}.pxt", i);
-            if let Ok(file) = filesystem::open_find(ctx, &constants.base_paths, path) {
-                sound_manager.set_sample_params_from_file(i, file)?;
-                continue;
-            }
-
-            let path = format!("PixTone/{:03}.pxt", i);
-            if let Ok(file) = filesystem::open_find(ctx, &constants.base_paths, path) {
-                sound_manager.set_sample_params_from_file(i, file)?;
-                continue;
+            for path in [format!("pxt/fx{:02x}.pxt", i), format!("PixTone/{:03}.pxt", i)] {
+                if let Ok(file) = filesystem::open_find(ctx, &constants.base_paths, &path) {
+                    // a malformed definition keeps the built-in sound instead of stopping the game from starting
+                    if let Err(err) = sound_manager.set_sample_params_from_file(i, file) {
+                        log::warn!("Failed to load sound effect {}: {}", path, err);
+                    }
+                    break;
+                }
             }
         }
 
+        sound_manager.set_master_volume(settings.master_volume);
         sound_manager.set_song_volume(settings.bgm_volume);
         sound_manager.set_sfx_volume(settings.sfx_volume);
+        sound_manager.set_positional_audio(settings.positional_audio, settings.positional_audio_range);
+
+        let mut texture_set = TextureSet::new();
+        texture_set.set_filter(settings.texture_filter());
 
         let current_time = Local::now();
         let more_rust = (current_time.month() == 7 && current_time.day() == 7) || settings.more_rust;
         let seed = chrono::Local::now().timestamp() as i32;
+        let rng_seed = std::env::var("CAVESTORY_RNG_SEED").ok().and_then(|v| v.parse::<u64>().ok());
 
-        Ok(SharedGameState {
+        let mut state = SharedGameState {
             control_flags: ControlFlags(0),
             game_flags: BitVec::with_size(8000),
             skip_flags: BitVec::with_size(64),
@@ -439,13 +527,15 @@ impl SharedGameState {
             fade_state: FadeState::Hidden,
             game_rng: XorShift::new(seed),
             effect_rng: XorShift::new(123),
+            rng_seed: None,
             tile_size: TileSize::Tile16x16,
             quake_counter: 0,
             super_quake_counter: 0,
             quake_rumble_counter: 0,
             super_quake_rumble_counter: 0,
             teleporter_slots: Vec::with_capacity(8),
-            carets: Vec::with_capacity(32),
+            play_counter: 0,
+            carets: CaretPool::with_capacity(MAX_CARETS),
             touch_controls: TouchControls::new(),
             mod_path: None,
             mod_list,
@@ -458,11 +548,16 @@ impl SharedGameState {
             frame_time: 0.0,
             debugger: false,
             command_line: false,
+            frame_advance: false,
+            frame_step: false,
+            frame_held: false,
             scale: 2.0,
             screen_size: (640.0, 480.0),
             canvas_size: (320.0, 240.0),
+            canvas_offset: (0.0, 0.0),
             preferred_viewport_size: (320.0, 240.0),
             next_scene: None,
+            deferred_actions: VecDeque::new(),
             textscript_vm: TextScriptVM::new(),
             creditscript_vm: CreditScriptVM::new(),
             lightmap_canvas: None,
@@ -470,7 +565,7 @@ impl SharedGameState {
             menu_character: MenuCharacter::Quote,
             constants,
             font,
-            texture_set: TextureSet::new(),
+            texture_set,
             #[cfg(feature = "scripting-lua")]
             lua: LuaScriptingState::new(),
             sound_manager,
@@ -486,7 +581,17 @@ impl SharedGameState {
             tutorial_counter: 0,
             more_rust,
             shutdown: false,
-        })
+            unsaved_progress: false,
+            data_variant,
+            quit_requested: false,
+        };
+
+        if let Some(seed) = rng_seed {
+            log::info!("Using fixed RNG seed {}.", seed);
+            state.reseed(seed);
+        }
+
+        Ok(state)
     }
 
     pub fn reload_resources(&mut self, ctx: &mut Context) -> GameResult {
@@ -496,9 +601,13 @@ impl SharedGameState {
             self.constants.special_treatment_for_csplus_mods(self.mod_path.as_ref());
         }
         self.constants.load_csplus_tables(ctx)?;
+        self.constants.load_bullet_definitions(ctx)?;
+        self.constants.load_npc_spawn_limits(ctx)?;
         self.constants.load_animated_faces(ctx)?;
         self.constants.load_texture_size_hints(ctx)?;
-        let stages = StageData::load_stage_table(ctx, &self.constants.base_paths, self.constants.is_switch)?;
+        self.constants.apply_constant_json_files(ctx)?;
+        let mut stages = StageData::load_stage_table(ctx, &self.constants.base_paths, self.constants.is_switch)?;
+        StageData::load_stage_flags(ctx, &self.constants.base_paths, &mut stages)?;
         self.stages = stages;
 
         let npc_tbl = filesystem::open_find(ctx, &self.constants.base_paths, "npc.tbl")?;
@@ -527,6 +636,8 @@ impl SharedGameState {
         }
 
         self.texture_set.unload_all();
+        // mods can ship their own font, so it has to follow the path list
+        self.font = SharedGameState::load_font(&self.constants, &self.settings, &self.loc, ctx)?;
 
         self.sound_manager.load_custom_sound_effects(ctx, &self.constants.base_paths)?;
 
@@ -543,16 +654,33 @@ impl SharedGameState {
             self.loc = locale;
         }
 
-        let font = BMFont::load(&self.constants.base_paths, &self.loc.font.path, ctx, self.loc.font.scale)
-            .or_else(|e| {
-                log::warn!("Failed to load font, using built-in: {}", e);
-                BMFont::load(&vec!["/".to_owned()], "builtin/builtin_font.fnt", ctx, 1.0)
-            })
-            .unwrap();
+        let font = SharedGameState::load_font(&self.constants, &self.settings, &self.loc, ctx).unwrap();
 
         self.font = font;
     }
 
+    /// Loads the font set in settings if there's one, otherwise the locale's font, falling back to the built-in one.
+    fn load_font(
+        constants: &EngineConstants,
+        settings: &Settings,
+        locale: &Locale,
+        ctx: &mut Context,
+    ) -> GameResult<BMFont> {
+        if !settings.custom_font.is_empty() {
+            match BMFont::load(&constants.base_paths, &settings.custom_font, ctx, settings.custom_font_scale) {
+                Ok(font) => return Ok(font),
+                Err(e) => {
+                    log::warn!("Failed to load custom font {}, using the locale font: {}", settings.custom_font, e)
+                }
+            }
+        }
+
+        BMFont::load(&constants.base_paths, &locale.font.path, ctx, locale.font.scale).or_else(|e| {
+            log::warn!("Failed to load font, using built-in: {}", e);
+            BMFont::load(&vec!["/".to_owned()], "builtin/builtin_font.fnt", ctx, 1.0)
+        })
+    }
+
     pub fn graphics_reset(&mut self) {
         self.texture_set.unload_all();
     }
@@ -610,9 +738,12 @@ impl SharedGameState {
 
     pub fn save_game(&mut self, game_scene: &mut GameScene, ctx: &mut Context) -> GameResult {
         if let Some(save_path) = self.get_save_filename(self.save_slot) {
-            if let Ok(data) = filesystem::open_options(ctx, save_path, OpenOptions::new().write(true).create(true)) {
+            // the original engine rewrites the whole file, leftovers of a longer profile would be read back
+            let options = OpenOptions::new().write(true).create(true).truncate(true);
+            if let Ok(data) = filesystem::open_options(ctx, save_path, options) {
                 let profile = GameProfile::dump(self, game_scene);
                 profile.write_save(data)?;
+                self.unsaved_progress = false;
             } else {
                 log::warn!("Cannot open save file.");
             }
@@ -623,6 +754,52 @@ impl SharedGameState {
         Ok(())
     }
 
+    /// Saves a snapshot of the game to the quicksave slot. The profile is written to a temporary file first
+    /// and moved over the old quicksave afterwards, so a crash while saving can't corrupt it.
+    /// Returns false if the mod has saves disabled.
+    pub fn quick_save(&mut self, game_scene: &mut GameScene, ctx: &mut Context) -> GameResult<bool> {
+        let save_path = if let Some(save_path) = self.get_quicksave_filename() {
+            save_path
+        } else {
+            log::info!("Mod has saves disabled.");
+            return Ok(false);
+        };
+        let temp_path = format!("{}.tmp", save_path);
+
+        let profile = GameProfile::dump(self, game_scene);
+        profile.write_save(filesystem::user_create(ctx, &temp_path)?)?;
+        // the rename can reach the disk before the data does, leaving an empty quicksave after a power loss
+        filesystem::user_sync(ctx, &temp_path)?;
+        filesystem::user_rename(ctx, &temp_path, &save_path)?;
+
+        Ok(true)
+    }
+
+    /// Switches to the stage stored in the quicksave slot and restores the snapshot.
+    /// Returns false if there's no quicksave.
+    pub fn quick_load(&mut self, ctx: &mut Context) -> GameResult<bool> {
+        let data = match self.get_quicksave_filename().and_then(|path| filesystem::user_open(ctx, path).ok()) {
+            Some(data) => data,
+            None => return Ok(false),
+        };
+
+        let profile = GameProfile::load_from_save(data)?;
+        self.reset();
+        let mut next_scene = GameScene::new(self, ctx, profile.current_map as usize)?;
+
+        profile.apply(self, &mut next_scene, ctx);
+        // the regular save slot doesn't have the progress made since it was written
+        self.unsaved_progress = true;
+
+        #[cfg(feature = "scripting-lua")]
+        self.lua.reload_scripts(ctx)?;
+
+        next_scene.toast.show(self.loc.t("game.quickload_done"));
+        self.next_scene = Some(Box::new(next_scene));
+
+        Ok(true)
+    }
+
     pub fn load_or_start_game(&mut self, ctx: &mut Context) -> GameResult {
         if let Some(save_path) = self.get_save_filename(self.save_slot) {
             if let Ok(data) = filesystem::user_open(ctx, save_path) {
@@ -632,6 +809,7 @@ impl SharedGameState {
                         let mut next_scene = GameScene::new(self, ctx, profile.current_map as usize)?;
 
                         profile.apply(self, &mut next_scene, ctx);
+                        self.unsaved_progress = false;
 
                         #[cfg(feature = "scripting-lua")]
                         self.lua.reload_scripts(ctx)?;
@@ -657,22 +835,76 @@ impl SharedGameState {
         self.control_flags.0 = 0;
         self.game_flags = BitVec::with_size(8000);
         self.fade_state = FadeState::Hidden;
-        self.game_rng = XorShift::new(chrono::Local::now().timestamp() as i32);
+        self.game_rng = match self.rng_seed {
+            Some(seed) => XorShift::from_seed(seed),
+            None => XorShift::new(chrono::Local::now().timestamp() as i32),
+        };
         self.teleporter_slots.clear();
+        self.play_counter = 0;
         self.quake_counter = 0;
+        self.super_quake_counter = 0;
         self.carets.clear();
+        self.sound_manager.stop_looping_sfx();
+        self.deferred_actions.clear();
         self.textscript_vm.set_mode(ScriptMode::Map);
         self.textscript_vm.suspend = true;
+        self.unsaved_progress = false;
+    }
+
+    /// Resets both RNGs to a known state derived from `seed` and keeps using it on subsequent `reset` calls.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng_seed = Some(seed);
+        self.game_rng = XorShift::from_seed(seed);
+        self.effect_rng = XorShift::from_seed(seed.wrapping_add(1));
+    }
+
+    /// Reseeds the effect RNG from the map name, so visual effects play out identically on every visit.
+    pub fn reseed_effect_rng(&mut self, map_name: &str) {
+        let seed = map_name.bytes().fold(0x811c9dc5u32, |hash, b| (hash ^ b as u32).wrapping_mul(0x01000193));
+        // xorshift gets stuck on a zero state
+        self.effect_rng = XorShift::new((seed | 1) as i32);
+    }
+
+    /// Whether the backend should swap in `next_scene` now. A running fade-out is allowed to finish first,
+    /// otherwise the new scene would appear abruptly halfway through the transition.
+    pub fn should_switch_scene(&self) -> bool {
+        self.next_scene.is_some() && !self.fade_state.is_fading_out()
     }
 
     pub fn handle_resize(&mut self, ctx: &mut Context) -> GameResult {
         self.screen_size = graphics::screen_size(ctx);
-        let scale_x = self.screen_size.1.div(self.preferred_viewport_size.1).floor().max(1.0);
-        let scale_y = self.screen_size.0.div(self.preferred_viewport_size.0).floor().max(1.0);
-
-        self.scale = f32::min(scale_x, scale_y);
+        let scale_x = self.screen_size.0.div(self.preferred_viewport_size.0);
+        let scale_y = self.screen_size.1.div(self.preferred_viewport_size.1);
+        let fit_scale = f32::min(scale_x, scale_y);
+
+        // windows smaller than the viewport still get drawn at 1x instead of shrinking to nothing
+        self.scale = match self.settings.scaling_mode {
+            ScalingMode::Integer => fit_scale.floor(),
+            ScalingMode::Stretch => fit_scale,
+            ScalingMode::Fixed(scale) => scale,
+        }
+        .max(1.0);
         self.canvas_size = (self.screen_size.0 / self.scale, self.screen_size.1 / self.scale);
 
+        if self.settings.pixel_snapping {
+            // leave the leftover screen pixels to the letterbox instead of drawing partial game pixels
+            self.canvas_size = (self.canvas_size.0.floor(), self.canvas_size.1.floor());
+        }
+
+        self.canvas_offset = (0.0, 0.0);
+
+        if self.settings.canvas_mode == CanvasMode::Classic {
+            // the scale above already fits the viewport into the window, the leftover space is split evenly around it
+            self.canvas_size = (
+                self.canvas_size.0.min(self.preferred_viewport_size.0),
+                self.canvas_size.1.min(self.preferred_viewport_size.1),
+            );
+            self.canvas_offset = (
+                ((self.screen_size.0 - self.canvas_size.0 * self.scale) / 2.0).max(0.0).floor(),
+                ((self.screen_size.1 - self.canvas_size.1 * self.scale) / 2.0).max(0.0).floor(),
+            );
+        }
+
         let (width, height) = (self.screen_size.0 as u16, self.screen_size.1 as u16);
 
         // ensure no texture is bound before destroying them.
@@ -682,20 +914,46 @@ impl SharedGameState {
         Ok(())
     }
 
-    pub fn tick_carets(&mut self) {
-        for caret in &mut self.carets {
-            caret.tick(&self.effect_rng, &self.constants);
-        }
+    /// Converts a position in window pixels into the coordinates the scenes draw in.
+    pub fn window_to_canvas(&self, ctx: &Context, x: f64, y: f64) -> (f64, f64) {
+        let (real_width, real_height) = (ctx.real_screen_size.0.max(1) as f64, ctx.real_screen_size.1.max(1) as f64);
+        let scale = self.scale as f64;
+        let (offset_x, offset_y) = (self.canvas_offset.0 as f64, self.canvas_offset.1 as f64);
 
-        self.carets.retain(|c| !c.is_dead());
+        (
+            (x * ctx.screen_size.0 as f64 / real_width - offset_x) / scale,
+            (y * ctx.screen_size.1 as f64 / real_height - offset_y) / scale,
+        )
+    }
+
+    pub fn tick_carets(&mut self) {
+        self.carets.tick(&self.effect_rng, &self.constants);
     }
 
     pub fn create_caret(&mut self, x: i32, y: i32, ctype: CaretType, direct: Direction) {
         self.carets.push(Caret::new(x, y, ctype, direct, &self.constants));
     }
 
+    /// Queues an action to be executed after the current tick, for handlers that can't apply it right away,
+    /// for example while iterating over the NPC list.
+    pub fn defer(&mut self, action: DeferredAction) {
+        self.deferred_actions.push_back(action);
+    }
+
+    /// Executes all queued actions in the order they were queued.
+    pub fn process_deferred_actions(&mut self) {
+        while let Some(action) = self.deferred_actions.pop_front() {
+            match action {
+                DeferredAction::PlaySfx(id) => self.sound_manager.play_sfx(id),
+                DeferredAction::StopSfx(id) => self.sound_manager.stop_sfx(id),
+                DeferredAction::SetFlag(id, value) => self.set_flag(id, value),
+                DeferredAction::ChangeScene(scene) => self.next_scene = Some(scene),
+            }
+        }
+    }
+
     pub fn set_speed(&mut self, value: f64) {
-        self.settings.speed = value.clamp(0.1, 3.0);
+        self.settings.speed = value.clamp(0.1, 4.0);
         self.frame_time = 0.0;
     }
 
@@ -707,6 +965,15 @@ impl SharedGameState {
         self.shutdown = true;
     }
 
+    /// Quits right away, unless there's unsaved progress the player should be asked about first.
+    pub fn request_quit(&mut self) {
+        if self.unsaved_progress {
+            self.quit_requested = true;
+        } else {
+            self.shutdown();
+        }
+    }
+
     // Stops SFX 40/41/58 (CPS and CSS)
     pub fn stop_noise(&mut self) {
         self.sound_manager.stop_sfx(40);
@@ -717,6 +984,7 @@ impl SharedGameState {
     pub fn set_flag(&mut self, id: usize, value: bool) {
         if id < self.game_flags.len() {
             self.game_flags.set(id, value);
+            self.unsaved_progress = true;
         } else {
             log::warn!("Attempted to set an out-of-bounds flag: {} to {}.", id, value);
         }
@@ -787,6 +1055,19 @@ impl SharedGameState {
         }
     }
 
+    pub fn get_quicksave_filename(&mut self) -> Option<String> {
+        if let Some(mod_path) = &self.mod_path {
+            let save_slot = self.mod_list.get_save_from_path(mod_path.to_string());
+            if save_slot < 0 {
+                return None;
+            } else if save_slot > 0 {
+                return Some(format!("/Mod{}_Quicksave.dat", save_slot));
+            }
+        }
+
+        Some("/Quicksave.dat".to_owned())
+    }
+
     pub fn get_rec_filename(&self) -> String {
         if let Some(mod_path) = &self.mod_path {
             let name = self.mod_list.get_name_from_path(mod_path.to_string());
@@ -855,3 +1136,115 @@ impl SharedGameState {
         return self.loc.tt(key, args);
     }
 }
+
+/// Queues a few actions the first time it's ticked, recording whether any of them got applied right away.
+#[cfg(test)]
+#[derive(Default)]
+struct DeferringScene {
+    applied_during_tick: Option<bool>,
+}
+
+#[cfg(test)]
+impl Scene for DeferringScene {
+    fn tick(&mut self, state: &mut SharedGameState, _ctx: &mut Context) -> GameResult {
+        if self.applied_during_tick.is_none() {
+            state.defer(DeferredAction::SetFlag(10, true));
+            state.defer(DeferredAction::SetFlag(10, false));
+            state.defer(DeferredAction::SetFlag(11, false));
+            state.defer(DeferredAction::SetFlag(11, true));
+            state.defer(DeferredAction::PlaySfx(1));
+            state.defer(DeferredAction::ChangeScene(Box::new(DeferringScene::default())));
+
+            self.applied_during_tick = Some(state.get_flag(11) || state.next_scene.is_some());
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_deferred_actions_order() {
+    let mut runner = crate::game::HeadlessRunner::new(|_| {}).unwrap();
+    runner.set_scene(Box::new(DeferringScene::default()));
+    runner.run_ticks(1).unwrap();
+
+    let state = runner.state();
+    // applied in the order they were queued, so the last value of each flag wins
+    assert!(!state.get_flag(10));
+    assert!(state.get_flag(11));
+    assert!(state.next_scene.is_some());
+    assert!(state.deferred_actions.is_empty());
+
+    let scene: &mut DeferringScene = runner.scene().unwrap();
+    assert_eq!(scene.applied_during_tick, Some(false));
+}
+
+#[test]
+fn test_load_clears_effects() {
+    use downcast::Downcast;
+
+    use crate::framework::filesystem::TempDataDir;
+    use crate::game::stage::Stage;
+    use crate::game::HeadlessRunner;
+
+    let mut pxm = b"PXM\x10".to_vec();
+    pxm.extend_from_slice(&[3, 0, 2, 0]);
+    pxm.extend_from_slice(&[0; 6]);
+
+    let dir = TempDataDir::new("load-clears-effects");
+    dir.write("Stage/Calm.pxm", &pxm);
+    dir.write("Stage/0.pxa", &[0; 0x100]);
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    dir.mount(&mut ctx);
+    dir.mount_user(&mut ctx);
+    state.constants.base_paths = vec!["/".to_owned()];
+
+    // saved in a calm room
+    let mut stage = Stage::blank(3, 2);
+    stage.data.map = "Calm".to_owned();
+    state.stages = vec![stage.data.clone()];
+    let mut scene = GameScene::from_stage(&mut state, &mut ctx, stage, 0).unwrap();
+    state.save_game(&mut scene, &mut ctx).unwrap();
+
+    // then loaded in the middle of a boss fight
+    scene.boss_life_bar.set_boss_target(&scene.boss);
+    state.create_caret(0x2000, 0x2000, CaretType::HurtParticles, Direction::Left);
+    state.quake_counter = 50;
+    state.load_or_start_game(&mut ctx).unwrap();
+
+    assert!(state.carets.is_empty());
+    assert_eq!(state.quake_counter, 0);
+    assert_eq!(state.fade_state, FadeState::Visible);
+
+    let loaded = state.next_scene.as_mut().and_then(|scene| scene.downcast_mut::<GameScene>().ok()).unwrap();
+    assert!(!loaded.boss_life_bar.has_target());
+}
+
+#[test]
+fn test_pixel_snapping() {
+    let (mut ctx, mut state) = crate::game::HeadlessRunner::bare_state();
+    state.settings.canvas_mode = CanvasMode::Extended;
+    state.settings.scaling_mode = ScalingMode::Stretch;
+
+    // 700 / 240 isn't a whole scale
+    ctx.screen_size = (1000.0, 700.0);
+
+    // resizing fails on the lightmap as there's no renderer, but only after the sizes are in place
+    state.settings.pixel_snapping = false;
+    let _ = state.handle_resize(&mut ctx);
+    assert_ne!(state.canvas_size.0.fract(), 0.0);
+
+    // the canvas only holds whole game pixels, the partial one at the edge is left to the letterbox
+    state.settings.pixel_snapping = true;
+    let _ = state.handle_resize(&mut ctx);
+    assert_ne!(state.scale.fract(), 0.0);
+    assert_eq!(state.canvas_size, (342.0, 240.0));
+    assert!(state.canvas_size.0 * state.scale <= state.screen_size.0);
+
+    // forcing integer scaling rounds the scale itself down
+    state.settings.scaling_mode = ScalingMode::Integer;
+    let _ = state.handle_resize(&mut ctx);
+    assert_eq!(state.scale, 2.0);
+    assert_eq!(state.canvas_size, (500.0, 350.0));
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::io::{Cursor, Read};
 use std::str::from_utf8;
 
@@ -208,6 +209,12 @@ pub struct StageData {
     pub background_color: Color,
     pub npc1: NpcType,
     pub npc2: NpcType,
+    /// Fully restores the player's health and ammo whenever the stage is entered.
+    pub refill_on_entry: bool,
+    /// Song started automatically when the stage is entered through `<TRA`.
+    pub music: Option<usize>,
+    /// Stages sharing a music area keep the current song playing when moving between each other.
+    pub music_area: Option<String>,
 }
 
 impl Clone for StageData {
@@ -224,6 +231,9 @@ impl Clone for StageData {
             background_color: self.background_color,
             npc1: self.npc1.clone(),
             npc2: self.npc2.clone(),
+            refill_on_entry: self.refill_on_entry,
+            music: self.music,
+            music_area: self.music_area.clone(),
         }
     }
 }
@@ -284,6 +294,17 @@ fn from_csplus_stagetbl(s: &[u8], is_switch: bool) -> String {
 }
 
 impl StageData {
+    /// Returns the song to start when entering this stage from `previous`, if it needs to change.
+    pub fn entry_song(&self, previous: &StageData) -> Option<usize> {
+        let same_area = self.music_area.is_some() && self.music_area == previous.music_area;
+
+        if same_area {
+            None
+        } else {
+            self.music
+        }
+    }
+
     pub fn load_stage_table(ctx: &mut Context, roots: &Vec<String>, is_switch: bool) -> GameResult<Vec<Self>> {
         let stage_tbl_path = "/stage.tbl";
         let stage_sect_path = "/stage.sect";
@@ -345,6 +366,9 @@ impl StageData {
                             background_color: Color::from_rgb(0, 0, 32),
                             npc1: NpcType::new(&npc1),
                             npc2: NpcType::new(&npc2),
+                            refill_on_entry: false,
+                            music: None,
+                            music_area: None,
                         };
                         new_stages.push(stage);
                     }
@@ -410,6 +434,9 @@ impl StageData {
                     background_color: Color::from_rgb(0, 0, 32),
                     npc1: NpcType::new(&npc1),
                     npc2: NpcType::new(&npc2),
+                    refill_on_entry: false,
+                    music: None,
+                    music_area: None,
                 };
                 stages.push(stage);
             }
@@ -469,6 +496,9 @@ impl StageData {
                     background_color: Color::from_rgb(0, 0, 32),
                     npc1: NpcType::new(&npc1),
                     npc2: NpcType::new(&npc2),
+                    refill_on_entry: false,
+                    music: None,
+                    music_area: None,
                 };
                 stages.push(stage);
             }
@@ -505,13 +535,12 @@ impl StageData {
                 let npc1 = f.read_u8()? as usize;
                 let npc2 = f.read_u8()? as usize;
 
-                let map = from_utf8(&map_buf)
+                // fields are NUL-terminated, anything past the terminator is leftover garbage
+                let map = from_utf8(&map_buf[0..zero_index(&map_buf)])
                     .map_err(|_| ResourceLoadError("UTF-8 error in map field".to_string()))?
-                    .trim_matches('\0')
                     .to_owned();
-                let name = from_utf8(&name_buf)
+                let name = from_utf8(&name_buf[0..zero_index(&name_buf)])
                     .map_err(|_| ResourceLoadError("UTF-8 error in name field".to_string()))?
-                    .trim_matches('\0')
                     .to_owned();
 
                 let stage = StageData {
@@ -526,6 +555,9 @@ impl StageData {
                     background_color: Color::from_rgb(0, 0, 32),
                     npc1: NpcType::new(NXENGINE_NPCS.get(npc1).unwrap_or(&"0")),
                     npc2: NpcType::new(NXENGINE_NPCS.get(npc2).unwrap_or(&"0")),
+                    refill_on_entry: false,
+                    music: None,
+                    music_area: None,
                 };
                 stages.push(stage);
             }
@@ -535,6 +567,39 @@ impl StageData {
 
         Err(ResourceLoadError("No stage table found.".to_string()))
     }
+
+    /// Applies engine-specific stage properties from `stage_flags.json`, which none of the stage table formats
+    /// have room for. Stages are matched by their map name.
+    pub fn load_stage_flags(ctx: &mut Context, roots: &Vec<String>, stages: &mut Vec<StageData>) -> GameResult {
+        if let Ok(file) = filesystem::open_find(ctx, roots, "stage_flags.json") {
+            match serde_json::from_reader::<_, HashMap<String, StageFlagsDefinition>>(file) {
+                Ok(table) => {
+                    for stage in stages.iter_mut() {
+                        if let Some(flags) = table.get(&stage.map) {
+                            stage.refill_on_entry = flags.refill_on_entry;
+                            stage.music = flags.music;
+                            stage.music_area = flags.music_area.clone();
+                        }
+                    }
+
+                    info!("Loaded stage_flags.json.");
+                }
+                Err(err) => log::warn!("Failed to deserialize stage flags: {}", err),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StageFlagsDefinition {
+    #[serde(default)]
+    refill_on_entry: bool,
+    #[serde(default)]
+    music: Option<usize>,
+    #[serde(default)]
+    music_area: Option<String>,
 }
 
 #[derive(Clone)]
@@ -606,6 +671,46 @@ impl Stage {
     }
 }
 
+#[cfg(test)]
+impl Stage {
+    /// Creates an empty 16x16 tile stage for tests, every tile using its own index as the attribute
+    /// so the layout can be written with attributes directly.
+    pub(crate) fn blank(width: u16, height: u16) -> Stage {
+        use crate::game::shared_game_state::TileSize;
+
+        let mut attrib = [0u8; 0x100];
+        for (i, attr) in attrib.iter_mut().enumerate() {
+            *attr = i as u8;
+        }
+
+        Stage {
+            map: Map {
+                width,
+                height,
+                tiles: vec![0; width as usize * height as usize],
+                attrib,
+                tile_size: TileSize::Tile16x16,
+            },
+            data: StageData {
+                name: String::new(),
+                name_jp: String::new(),
+                map: String::new(),
+                boss_no: 0,
+                tileset: Tileset { name: "0".to_string() },
+                pxpack_data: None,
+                background: Background::new("0"),
+                background_type: BackgroundType::Black,
+                background_color: Color::new(0.0, 0.0, 0.0, 0.0),
+                npc1: NpcType::new("0"),
+                npc2: NpcType::new("0"),
+                refill_on_entry: false,
+                music: None,
+                music_area: None,
+            },
+        }
+    }
+}
+
 pub struct StageTexturePaths {
     /// Path to the stage's background texture.
     pub background: String,
@@ -658,4 +763,87 @@ impl StageTexturePaths {
         self.npc1 = ["Npc/", &stage.data.npc1.filename()].join("");
         self.npc2 = ["Npc/", &stage.data.npc2.filename()].join("");
     }
+
+    pub fn names(&self) -> [&str; 6] {
+        [&self.background, &self.tileset_fg, &self.tileset_mg, &self.tileset_bg, &self.npc1, &self.npc2]
+    }
+}
+
+#[test]
+fn test_entry_song() {
+    let mut first_cave = Stage::blank(1, 1).data;
+    first_cave.music = Some(8);
+    first_cave.music_area = Some("cave".to_string());
+
+    let second_cave = first_cave.clone();
+
+    let mut village = Stage::blank(1, 1).data;
+    village.music = Some(3);
+    village.music_area = Some("village".to_string());
+
+    // walking back and forth inside the area keeps the song going
+    assert_eq!(second_cave.entry_song(&first_cave), None);
+    assert_eq!(first_cave.entry_song(&second_cave), None);
+
+    // stepping into a room with its own track switches to it, and back again
+    assert_eq!(village.entry_song(&second_cave), Some(3));
+    assert_eq!(first_cave.entry_song(&village), Some(8));
+
+    // stages outside of any area always restart their song
+    let mut shack = Stage::blank(1, 1).data;
+    shack.music = Some(3);
+    assert_eq!(shack.entry_song(&Stage::blank(1, 1).data), Some(3));
+}
+
+#[test]
+fn test_load_nxengine_first_cave() {
+    use crate::framework::filesystem::TempDataDir;
+
+    fn entry(map: &str, name: &str, ids: [u8; 6]) -> Vec<u8> {
+        let mut entry = vec![0u8; 0x43];
+        entry[..map.len()].copy_from_slice(map.as_bytes());
+        entry[0x20..0x20 + name.len()].copy_from_slice(name.as_bytes());
+        entry.extend_from_slice(&ids);
+        entry
+    }
+
+    // tileset, background, background type, boss, npc1, npc2
+    let mut stage_dat = vec![2u8];
+    stage_dat.extend(entry("0", "", [0; 6]));
+    stage_dat.extend(entry("Cave", "First Cave", [11, 1, 0, 0, 8, 0]));
+
+    let mut pxm = b"PXM\x10".to_vec();
+    pxm.extend_from_slice(&[3, 0, 2, 0]);
+    pxm.extend_from_slice(&[0, 1, 2, 16, 17, 18]);
+
+    let mut pxa = vec![0u8; 0x100];
+    pxa[16] = 0x41;
+    pxa[17] = 0x41;
+
+    let dir = TempDataDir::new("nxengine-stage");
+    dir.write("stage.dat", &stage_dat);
+    dir.write("Stage/Cave.pxm", &pxm);
+    dir.write("Stage/Cave.pxa", &pxa);
+
+    let mut ctx = Context::new();
+    dir.mount(&mut ctx);
+
+    let roots = vec!["/".to_owned()];
+    let stages = StageData::load_stage_table(&mut ctx, &roots, false).unwrap();
+    assert_eq!(stages.len(), 2);
+    assert_eq!(stages[1].name, "First Cave");
+    assert_eq!(stages[1].tileset.name, "Cave");
+
+    let stage = Stage::load(&roots, &stages[1], &mut ctx).unwrap();
+    assert_eq!((stage.map.width, stage.map.height), (3, 2));
+    assert_eq!(stage.map.tiles, vec![0, 1, 2, 16, 17, 18]);
+    assert_eq!(stage.map.get_attribute(0, 1), 0x41);
+    assert_eq!(stage.map.get_attribute(2, 1), 0x00);
+
+    let mut textures = StageTexturePaths::new();
+    textures.update(&stage);
+    assert_eq!(
+        textures.names(),
+        ["bkBlue", "Stage/PrtCave", "Stage/PrtCave", "Stage/PrtCave", "Npc/NpcCemet", "Npc/NpcGuest"]
+    );
 }
@@ -1,7 +1,7 @@
 use num_traits::clamp;
 
 use crate::common::{BulletFlag, Condition, Direction, Flag, Rect};
-use crate::engine_constants::{BulletData, EngineConstants};
+use crate::engine_constants::{BulletData, CustomBulletData, EngineConstants};
 use crate::game::caret::CaretType;
 use crate::game::npc::list::NPCList;
 use crate::game::npc::NPC;
@@ -103,6 +103,9 @@ pub struct Bullet {
     pub target_y: i32,
     pub prev_x: i32,
     pub prev_y: i32,
+    /// Position the bullet was fired from, used for damage falloff.
+    pub origin_x: i32,
+    pub origin_y: i32,
     pub life: u16,
     pub lifetime: u16,
     pub damage: i16,
@@ -156,6 +159,8 @@ impl Bullet {
             target_y: 0,
             prev_x: x,
             prev_y: y,
+            origin_x: x,
+            origin_y: y,
             life: bullet.life as u16,
             lifetime: bullet.lifetime,
             damage: bullet.damage as i16,
@@ -189,6 +194,21 @@ impl Bullet {
         }
     }
 
+    /// Returns damage dealt on hit, lowered by the distance travelled if the bullet type has a falloff defined.
+    pub fn hit_damage(&self, constants: &EngineConstants) -> i16 {
+        if let Some(falloff) = constants.weapon.damage_falloff.get(&self.btype) {
+            let distance = ((self.x - self.origin_x) as f64).hypot((self.y - self.origin_y) as f64) / 512.0;
+            let past_start = distance - falloff.start as f64;
+
+            if past_start > 0.0 && falloff.step > 0 {
+                let lost = (past_start / falloff.step as f64) as i16;
+                return self.damage.saturating_sub(lost).max((falloff.min_damage as i16).min(self.damage));
+            }
+        }
+
+        self.damage
+    }
+
     #[inline]
     pub fn is_dead(&self) -> bool {
         !self.cond.alive()
@@ -295,6 +315,37 @@ impl Bullet {
         let _ = npc_list.spawn(0x100, npc);
     }
 
+    fn tick_custom(&mut self, bullet_data: &CustomBulletData) {
+        self.action_counter += 1;
+        if self.action_counter > self.lifetime {
+            self.cond.set_alive(false);
+            return;
+        }
+
+        if self.action_num == 0 {
+            self.action_num = 1;
+
+            match self.direction {
+                Direction::Left => self.vel_x = -bullet_data.speed,
+                Direction::Up => self.vel_y = -bullet_data.speed,
+                Direction::Right => self.vel_x = bullet_data.speed,
+                Direction::Bottom => self.vel_y = bullet_data.speed,
+                Direction::FacingPlayer => unreachable!(),
+            }
+        } else {
+            self.x += self.vel_x;
+            self.y += self.vel_y;
+        }
+
+        self.anim_counter += 1;
+        if self.anim_counter > bullet_data.anim_speed {
+            self.anim_counter = 0;
+            self.anim_num = (self.anim_num + 1) % bullet_data.frames.len() as u16;
+        }
+
+        self.anim_rect = bullet_data.frames[self.anim_num as usize];
+    }
+
     fn tick_polar_star(&mut self, state: &mut SharedGameState) {
         self.action_counter += 1;
         if self.action_counter > self.lifetime {
@@ -388,7 +439,7 @@ impl Bullet {
         {
             self.cond.set_alive(false);
             state.create_caret(self.x, self.y, CaretType::ProjectileDissipation, Direction::Left);
-            state.sound_manager.play_sfx(28);
+            state.sound_manager.play_sfx_at(28, self.x, self.y);
             return;
         }
 
@@ -448,7 +499,7 @@ impl Bullet {
             self.y += self.vel_y;
 
             if self.flags.hit_left_wall() || self.flags.hit_right_wall() || self.flags.hit_bottom_wall() {
-                state.sound_manager.play_sfx(34);
+                state.sound_manager.play_sfx_at(34, self.x, self.y);
             }
         }
 
@@ -705,7 +756,7 @@ impl Bullet {
                 _ => 0,
             };
 
-            state.sound_manager.play_sfx(44);
+            state.sound_manager.play_sfx_at(44, self.x, self.y);
         }
 
         if self.action_counter % 3 == 0 {
@@ -852,7 +903,7 @@ impl Bullet {
         if self.action_counter > 100 || !player.controller.shoot() {
             self.cond.set_alive(false);
             state.create_caret(self.x, self.y, CaretType::ProjectileDissipation, Direction::Left);
-            state.sound_manager.play_sfx(100);
+            state.sound_manager.play_sfx_at(100, self.x, self.y);
 
             match () {
                 _ if player.up => {
@@ -1032,7 +1083,7 @@ impl Bullet {
         }
 
         if self.action_counter % 5 == 1 {
-            state.sound_manager.play_sfx(34);
+            state.sound_manager.play_sfx_at(34, self.x, self.y);
         }
 
         if self.action_num == 0 {
@@ -1077,7 +1128,7 @@ impl Bullet {
         }
 
         if self.action_counter % 7 == 1 {
-            state.sound_manager.play_sfx(106);
+            state.sound_manager.play_sfx_at(106, self.x, self.y);
         }
 
         if self.action_num == 0 {
@@ -1135,7 +1186,7 @@ impl Bullet {
 
                 self.action_counter += 1;
                 if self.action_counter % 4 == 1 {
-                    state.sound_manager.play_sfx(106);
+                    state.sound_manager.play_sfx_at(106, self.x, self.y);
 
                     self.counter1 += 1;
                     let direction = if self.counter1 % 2 != 0 { Direction::Left } else { Direction::Right };
@@ -1160,7 +1211,7 @@ impl Bullet {
 
                 self.action_counter += 1;
                 if self.rng.range(-1..1) == 0 {
-                    state.sound_manager.play_sfx(106);
+                    state.sound_manager.play_sfx_at(106, self.x, self.y);
 
                     let x = self.rng.range(-64..64) * 0x200 + self.x;
                     let y = self.rng.range(-64..64) * 0x200 + self.y;
@@ -1347,7 +1398,7 @@ impl Bullet {
                 _ => 0,
             };
 
-            state.sound_manager.play_sfx(44);
+            state.sound_manager.play_sfx_at(44, self.x, self.y);
         }
 
         if self.action_counter % 3 == 0 {
@@ -1685,7 +1736,17 @@ impl Bullet {
             37 | 38 | 39 => self.tick_spur(state, new_bullets),
             40 | 41 | 42 => self.tick_spur_trail(state),
             43 => self.tick_nemesis_curly(state, npc_list),
-            _ => self.cond.set_alive(false),
+            _ => {
+                if let Some(bullet_data) = state.constants.weapon.custom_bullets.get(&self.btype) {
+                    self.tick_custom(bullet_data);
+
+                    if !self.cond.alive() {
+                        state.create_caret(self.x, self.y, CaretType::Shoot, Direction::Left);
+                    }
+                } else {
+                    self.cond.set_alive(false);
+                }
+            }
         }
     }
 
@@ -1693,7 +1754,7 @@ impl Bullet {
         match self.btype {
             // spur is a special case
             37 | 38 | 39 => state.create_caret(self.x, self.y, CaretType::ProjectileDissipation, Direction::Up),
-            _ => state.sound_manager.play_sfx(28),
+            _ => state.sound_manager.play_sfx_at(28, self.x, self.y),
         }
 
         self.cond.set_alive(false);
@@ -1948,7 +2009,7 @@ impl PhysicalEntity for Bullet {
                         }
 
                         state.create_caret(self.x, self.y, CaretType::ProjectileDissipation, Direction::Left);
-                        state.sound_manager.play_sfx(12);
+                        state.sound_manager.play_sfx_at(12, self.x, self.y);
 
                         let mut npc = NPC::create(4, &state.npc_table);
                         npc.cond.set_alive(true);
@@ -2004,3 +2065,69 @@ impl PhysicalEntity for Bullet {
         self.test_hit_block_destructible(x, y, &hit_attribs, state);
     }
 }
+
+#[test]
+fn test_bullet_definition_overrides() {
+    use crate::framework::filesystem::TempDataDir;
+    use crate::game::HeadlessRunner;
+
+    let dir = TempDataDir::new("bullet-overrides");
+    dir.write(
+        "bullets.json",
+        r#"{"bullets": {
+            "4": {"damage": 4, "life": 1, "lifetime": 8, "enemy_hit": [8, 8], "block_hit": [6, 3], "display_bounds": [8, 8, 8, 8]},
+            "50": {"damage": 1, "life": 1, "lifetime": 30, "enemy_hit": [2, 2], "block_hit": [2, 2], "display_bounds": [4, 4, 4, 4],
+                   "speed": 2048, "frames": [[0, 0, 8, 8]]}
+        }}"#,
+    );
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    dir.mount(&mut ctx);
+    state.constants.load_bullet_definitions(&mut ctx).unwrap();
+
+    // overriding a built-in bullet changes the hitbox of every new one
+    let bullet = Bullet::new(0, 0, 4, TargetPlayer::Player1, Direction::Right, &state.constants);
+    let bounds = &bullet.hit_bounds;
+    assert_eq!((bounds.left, bounds.top, bounds.right, bounds.bottom), (0xc00, 0x600, 0xc00, 0x600));
+    assert_eq!((bullet.enemy_hit_width, bullet.enemy_hit_height), (0x1000, 0x1000));
+
+    // and a bullet type of its own flies off at the defined speed
+    let player = Player::new(&mut state, &mut ctx);
+    let mut bullet = Bullet::new(0x10000, 0x10000, 50, TargetPlayer::Player1, Direction::Right, &state.constants);
+    for _ in 0..2 {
+        bullet.tick(&mut state, [&player, &player], &NPCList::new(), &mut Vec::new());
+    }
+    assert_eq!(bullet.vel_x, 0x800);
+    assert_eq!(bullet.x, 0x10800);
+}
+
+#[test]
+fn test_bullet_damage_falloff() {
+    use crate::framework::filesystem::TempDataDir;
+    use crate::game::HeadlessRunner;
+
+    let dir = TempDataDir::new("bullet-falloff");
+    dir.write(
+        "bullets.json",
+        r#"{"bullets": {
+            "4": {"damage": 4, "life": 1, "lifetime": 8, "enemy_hit": [4, 4], "block_hit": [2, 2], "display_bounds": [8, 8, 8, 8],
+                  "falloff": {"start": 32, "step": 16, "min_damage": 2}}
+        }}"#,
+    );
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    dir.mount(&mut ctx);
+    state.constants.load_bullet_definitions(&mut ctx).unwrap();
+
+    // full damage for the first 32 pixels, one point less every 16 after that, but never below 2
+    let mut bullet = Bullet::new(0x10000, 0x10000, 4, TargetPlayer::Player1, Direction::Right, &state.constants);
+    for (distance, damage) in [(0, 4), (32, 4), (47, 4), (48, 3), (64, 2), (400, 2)] {
+        bullet.x = 0x10000 + distance * 0x200;
+        assert_eq!(bullet.hit_damage(&state.constants), damage, "{} pixels", distance);
+    }
+
+    // bullets without a falloff hit just as hard from anywhere
+    let mut bullet = Bullet::new(0x10000, 0x10000, 5, TargetPlayer::Player1, Direction::Right, &state.constants);
+    bullet.x += 400 * 0x200;
+    assert_eq!(bullet.hit_damage(&state.constants), bullet.damage);
+}
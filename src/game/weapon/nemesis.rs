@@ -8,7 +8,7 @@ use crate::game::weapon::bullet::BulletManager;
 impl Weapon {
     pub(crate) fn tick_nemesis(
         &mut self,
-        player: &Player,
+        player: &mut Player,
         player_id: TargetPlayer,
         bullet_manager: &mut BulletManager,
         state: &mut SharedGameState,
@@ -76,5 +76,7 @@ impl Weapon {
             WeaponLevel::Level3 => state.sound_manager.play_sfx(60),
             _ => unreachable!(),
         }
+
+        player.controller.set_rumble(0x6000, 0x3000, 8);
     }
 }
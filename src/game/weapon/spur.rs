@@ -6,6 +6,13 @@ use crate::game::weapon::{Weapon, WeaponLevel};
 use crate::game::weapon::bullet::BulletManager;
 
 impl Weapon {
+    /// Drops the charge accumulated by holding the fire button, so it isn't released after switching weapons.
+    pub(crate) fn reset_charge(&mut self) {
+        self.counter1 = 0;
+        self.counter2 = 0;
+        self.reset_xp();
+    }
+
     pub(crate) fn tick_spur(
         &mut self,
         player: &mut Player,
@@ -160,6 +167,17 @@ impl Weapon {
             };
 
             state.sound_manager.play_sfx(sound);
+
+            let rumble = match btype {
+                37 => 0x3000,
+                38 => 0x5000,
+                39 => 0x7000,
+                _ => 0,
+            };
+
+            if rumble > 0 {
+                player.controller.set_rumble(rumble, rumble / 2, 10);
+            }
         }
     }
 }
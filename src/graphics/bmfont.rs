@@ -149,13 +149,13 @@ impl Font for BMFont {
 
                 if let Some((_, rect)) = rect_map_entry {
                     offset_x += rect.width() as f32;
-                } else if let Some(glyph) = self.font.chars.get(&chr) {
+                } else if let Some(glyph) = self.get_glyph(chr) {
                     offset_x += glyph.x_advance as f32 * self.font_scale;
                 }
             }
         } else {
             for chr in text {
-                if let Some(glyph) = self.font.chars.get(&chr) {
+                if let Some(glyph) = self.get_glyph(chr) {
                     offset_x += glyph.x_advance as f32 * self.font_scale;
                 }
             }
@@ -255,6 +255,17 @@ impl BMFont {
         Ok(Self { font, font_scale, pages })
     }
 
+    /// Looks up the glyph of a character, characters missing from the font are drawn as U+FFFD or `?`,
+    /// so text of translations the font doesn't fully cover doesn't silently lose letters.
+    fn get_glyph(&self, chr: char) -> Option<&BMChar> {
+        let glyph = self.font.chars.get(&chr);
+        if glyph.is_some() || chr.is_control() {
+            return glyph;
+        }
+
+        self.font.chars.get(&'\u{fffd}').or_else(|| self.font.chars.get(&'?'))
+    }
+
     fn draw_text_line(
         &self,
         iter: &mut dyn Iterator<Item = char>,
@@ -278,7 +289,7 @@ impl BMFont {
                 let mut offset_x = x;
 
                 for chr in iter {
-                    if let Some(glyph) = self.font.chars.get(&chr) {
+                    if let Some(glyph) = self.get_glyph(chr) {
                         let rect_map_entry = syms.symbols.iter().find(|(c, _)| *c == chr);
 
                         if let Some((_, rect)) = rect_map_entry {
@@ -314,13 +325,13 @@ impl BMFont {
                 let mut chars = Vec::new();
 
                 for chr in iter {
-                    if let Some(glyph) = self.font.chars.get(&chr) {
+                    if let Some(glyph) = self.get_glyph(chr) {
                         pages.insert(glyph.page);
                         chars.push((chr, glyph));
                     }
                 }
 
-                for page in pages {
+                for (page_idx, page) in pages.into_iter().enumerate() {
                     let page_tex = if let Some(p) = self.pages.get(page as usize) {
                         p
                     } else {
@@ -334,7 +345,14 @@ impl BMFont {
                         let rect_map_entry = syms.symbols.iter().find(|(c, _)| *c == *chr);
 
                         if let Some((_, rect)) = rect_map_entry {
-                            RECTS_BUF.push((offset_x, y + self.line_height() / 2.0 - rect.height() as f32 / 2.0, rect));
+                            // the layout is redone for every page, symbols only need to be queued once
+                            if page_idx == 0 {
+                                RECTS_BUF.push((
+                                    offset_x,
+                                    y + self.line_height() / 2.0 - rect.height() as f32 / 2.0,
+                                    rect,
+                                ));
+                            }
                             offset_x += rect.width() as f32;
                         } else {
                             if glyph.page == page {
@@ -0,0 +1,43 @@
+use image::imageops::FilterType;
+use image::{ImageOutputFormat, RgbaImage};
+
+use crate::framework::context::Context;
+use crate::framework::error::{GameError, GameResult};
+use crate::framework::filesystem;
+use crate::framework::graphics;
+use crate::game::shared_game_state::SharedGameState;
+
+/// Captures the frame drawn so far and saves it as a PNG in the `screenshots` directory of user data.
+/// Only the readback happens on the calling thread, encoding and writing the file is done in the background
+/// where threads are available.
+pub fn take_screenshot(state: &SharedGameState, ctx: &mut Context) -> GameResult {
+    let (width, height, pixels) = graphics::read_screen_pixels(ctx)?;
+    let mut image = RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| GameError::RenderError("Invalid screen buffer size.".to_owned()))?;
+
+    if state.settings.screenshot_internal_resolution && state.scale > 1.0 {
+        let scaled_width = (width as f32 / state.scale).round().max(1.0) as u32;
+        let scaled_height = (height as f32 / state.scale).round().max(1.0) as u32;
+        image = image::imageops::resize(&image, scaled_width, scaled_height, FilterType::Nearest);
+    }
+
+    if !filesystem::user_is_dir(ctx, "/screenshots") {
+        filesystem::user_create_dir(ctx, "/screenshots")?;
+    }
+
+    let path = format!("/screenshots/{}.png", chrono::Local::now().format("%Y-%m-%d_%H-%M-%S%.3f"));
+    let mut file = filesystem::user_create(ctx, &path)?;
+
+    let save = move || match image.write_to(&mut file, ImageOutputFormat::Png) {
+        Ok(()) => log::info!("Saved screenshot to {}.", path),
+        Err(err) => log::error!("Failed to save screenshot {}: {}", path, err),
+    };
+
+    // there are no threads to offload the work to in the browser
+    #[cfg(target_arch = "wasm32")]
+    save();
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::spawn(save);
+
+    Ok(())
+}
@@ -78,6 +78,9 @@ pub trait SpriteBatch {
 
     fn draw_filtered(&mut self, _filter: FilterMode, _ctx: &mut Context) -> GameResult;
 
+    /// Sets the filter used by `draw`.
+    fn set_filter(&mut self, filter: FilterMode);
+
     fn get_texture(&self) -> Option<&Box<dyn BackendTexture>>;
 }
 
@@ -148,14 +151,18 @@ impl SpriteBatch for DummyBatch {
         _rect: &Rect<u16>,
     ) {}
 
-    fn draw(&mut self, _ctx: &mut Context) -> GameResult {
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        ctx.batch_draws += 1;
         Ok(())
     }
 
-    fn draw_filtered(&mut self, _filter: FilterMode, _ctx: &mut Context) -> GameResult {
+    fn draw_filtered(&mut self, _filter: FilterMode, ctx: &mut Context) -> GameResult {
+        ctx.batch_draws += 1;
         Ok(())
     }
 
+    fn set_filter(&mut self, _filter: FilterMode) {}
+
     fn get_texture(&self) -> Option<&Box<dyn BackendTexture>> {
         None
     }
@@ -169,6 +176,7 @@ pub struct SubBatch {
     real_height: u16,
     scale_x: f32,
     scale_y: f32,
+    filter: FilterMode,
 }
 
 pub struct CombinedBatch {
@@ -362,16 +370,21 @@ impl SpriteBatch for SubBatch {
 
     #[inline(always)]
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        self.draw_filtered(FilterMode::Nearest, ctx)
+        self.draw_filtered(self.filter, ctx)
     }
 
-    fn draw_filtered(&mut self, _filter: FilterMode, _ctx: &mut Context) -> GameResult {
-        //self.batch.set_filter(filter);
+    fn draw_filtered(&mut self, filter: FilterMode, ctx: &mut Context) -> GameResult {
+        self.batch.set_filter(filter);
         self.batch.draw()?;
         self.batch.clear();
+        ctx.batch_draws += 1;
         Ok(())
     }
 
+    fn set_filter(&mut self, filter: FilterMode) {
+        self.filter = filter;
+    }
+
     fn get_texture(&self) -> Option<&Box<dyn BackendTexture>> {
         Some(&self.batch)
     }
@@ -470,6 +483,14 @@ impl SpriteBatch for CombinedBatch {
         self.main_batch.draw_filtered(filter, ctx)
     }
 
+    fn set_filter(&mut self, filter: FilterMode) {
+        self.main_batch.set_filter(filter);
+
+        if let Some(glow_batch) = &mut self.glow_batch {
+            glow_batch.set_filter(filter);
+        }
+    }
+
     fn get_texture(&self) -> Option<&Box<dyn BackendTexture>> {
         self.main_batch.get_texture()
     }
@@ -478,15 +499,63 @@ impl SpriteBatch for CombinedBatch {
 pub struct TextureSet {
     pub tex_map: HashMap<String, Box<dyn SpriteBatch>>,
     dummy_batch: Box<dyn SpriteBatch>,
+    /// Tilesets, backgrounds and NPC sheets of the currently active stage.
+    stage_textures: Vec<String>,
+    filter: FilterMode,
 }
 
 impl TextureSet {
     pub fn new() -> TextureSet {
-        TextureSet { tex_map: HashMap::new(), dummy_batch: Box::new(DummyBatch) }
+        TextureSet {
+            tex_map: HashMap::new(),
+            dummy_batch: Box::new(DummyBatch),
+            stage_textures: Vec::new(),
+            filter: FilterMode::Nearest,
+        }
+    }
+
+    /// Sets how stage textures are sampled when the window scale isn't a whole number. Fonts and the rest of the UI
+    /// always stay sharp. Applies to already loaded textures as well as ones loaded later.
+    pub fn set_filter(&mut self, filter: FilterMode) {
+        self.filter = filter;
+
+        for (name, batch) in self.tex_map.iter_mut() {
+            if self.stage_textures.contains(name) {
+                batch.set_filter(filter);
+            }
+        }
+    }
+
+    fn filter_for(&self, name: &str) -> FilterMode {
+        if self.stage_textures.iter().any(|stage_texture| stage_texture == name) {
+            self.filter
+        } else {
+            FilterMode::Nearest
+        }
     }
 
     pub fn unload_all(&mut self) {
         self.tex_map.clear();
+        self.stage_textures.clear();
+    }
+
+    /// Unloads textures of the previous stage that the new one doesn't use,
+    /// textures shared between both stay loaded.
+    pub fn set_stage_textures(&mut self, names: &[&str]) {
+        for old in self.stage_textures.iter() {
+            if !names.contains(&old.as_str()) {
+                log::debug!("Unloading stage texture {}", old);
+                self.tex_map.remove(old);
+            }
+        }
+
+        self.stage_textures = names.iter().map(|&name| name.to_owned()).collect();
+
+        for (name, batch) in self.tex_map.iter_mut() {
+            if self.stage_textures.contains(name) {
+                batch.set_filter(self.filter);
+            }
+        }
     }
 
     fn make_transparent(rgba: &mut RgbaImage) {
@@ -563,6 +632,7 @@ impl TextureSet {
                 scale_y: scale,
                 real_width: size.0 as _,
                 real_height: size.1 as _,
+                filter: FilterMode::Nearest,
             }
         }
 
@@ -587,10 +657,64 @@ impl TextureSet {
         }
 
         if !self.tex_map.contains_key(name) {
-            let batch = self.load_texture(ctx, constants, name)?;
+            let mut batch = self.load_texture(ctx, constants, name)?;
+            batch.set_filter(self.filter_for(name));
             self.tex_map.insert(name.to_owned(), batch);
         }
 
         Ok(self.tex_map.get_mut(name).unwrap())
     }
 }
+
+#[test]
+fn test_stage_textures_shared_between_stages() {
+    let mut set = TextureSet::new();
+    set.tex_map.insert("TextBox".to_owned(), Box::new(DummyBatch));
+
+    let corridor = ["bkBlue", "Stage/PrtEggs", "Stage/PrtEggs", "Stage/PrtEggs", "Npc/NpcEggs1", "Npc/NpcEggs2"];
+    let observation = ["bkGreen", "Stage/PrtEggs", "Stage/PrtEggs", "Stage/PrtEggs", "Npc/NpcEggs1", "Npc/NpcGuest"];
+
+    // loads whatever the stage needs that isn't loaded yet, like drawing it with get_or_load_batch would
+    let enter_stage = |set: &mut TextureSet, names: &[&str]| {
+        set.set_stage_textures(names);
+
+        let mut loaded = Vec::new();
+        for &name in names {
+            if !set.tex_map.contains_key(name) {
+                set.tex_map.insert(name.to_owned(), Box::new(DummyBatch));
+                loaded.push(name.to_owned());
+            }
+        }
+        loaded
+    };
+
+    assert_eq!(enter_stage(&mut set, &corridor), ["bkBlue", "Stage/PrtEggs", "Npc/NpcEggs1", "Npc/NpcEggs2"]);
+
+    // going back and forth only swaps what differs, the shared tileset and NPC sheet stay loaded
+    assert_eq!(enter_stage(&mut set, &observation), ["bkGreen", "Npc/NpcGuest"]);
+    assert_eq!(enter_stage(&mut set, &corridor), ["bkBlue", "Npc/NpcEggs2"]);
+    assert_eq!(enter_stage(&mut set, &observation), ["bkGreen", "Npc/NpcGuest"]);
+
+    assert!(set.tex_map.contains_key("TextBox"));
+    assert!(!set.tex_map.contains_key("bkBlue") && !set.tex_map.contains_key("Npc/NpcEggs2"));
+}
+
+#[test]
+fn test_filter_only_applies_to_stage_textures() {
+    let mut set = TextureSet::new();
+    set.set_stage_textures(&["Stage/PrtCave", "bkBlue", "Npc/NpcCemet"]);
+    set.set_filter(FilterMode::Linear);
+
+    assert_eq!(set.filter_for("Stage/PrtCave"), FilterMode::Linear);
+    assert_eq!(set.filter_for("Npc/NpcCemet"), FilterMode::Linear);
+
+    // font pages and UI textures stay sharp
+    for name in ["csfont_0", "builtin/builtin_font_0", "TextBox", "ArmsImage"] {
+        assert_eq!(set.filter_for(name), FilterMode::Nearest);
+    }
+
+    // textures of the previous stage no longer count
+    set.set_stage_textures(&["Stage/PrtEggs"]);
+    assert_eq!(set.filter_for("Stage/PrtCave"), FilterMode::Nearest);
+    assert_eq!(set.filter_for("Stage/PrtEggs"), FilterMode::Linear);
+}
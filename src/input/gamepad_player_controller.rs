@@ -6,6 +6,10 @@ use crate::game::shared_game_state::SharedGameState;
 use crate::input::player_controller::PlayerController;
 use crate::game::player::TargetPlayer;
 
+/// Minimum amount of ticks between two rumble events of the same or lower strength,
+/// so rapid fire weapons don't keep the motors buzzing continuously.
+const RUMBLE_MIN_INTERVAL: u32 = 10;
+
 bitfield! {
     #[derive(Clone, Copy)]
     pub struct KeyState(u16);
@@ -37,6 +41,8 @@ pub struct GamepadController {
     trigger: KeyState,
     rumble_state: Option<RumbleState>,
     rumble_enabled: bool,
+    rumble_cooldown: u32,
+    rumble_strength: u16,
 }
 
 #[derive(Clone)]
@@ -56,6 +62,8 @@ impl GamepadController {
             trigger: KeyState(0),
             rumble_state: None,
             rumble_enabled: false,
+            rumble_cooldown: 0,
+            rumble_strength: 0,
         }
     }
 
@@ -103,6 +111,8 @@ impl PlayerController for GamepadController {
             self.rumble_state = None;
         }
 
+        self.rumble_cooldown = self.rumble_cooldown.saturating_sub(1);
+
         Ok(())
     }
 
@@ -266,10 +276,14 @@ impl PlayerController for GamepadController {
             return;
         }
 
-        if !self.rumble_state.is_none() {
+        // a stronger event (eg. taking damage while firing) replaces the current one
+        let strength = low_freq.max(hi_freq);
+        if self.rumble_cooldown > 0 && strength <= self.rumble_strength {
             return;
         }
 
         self.rumble_state = Some(RumbleState { low_freq, hi_freq, ticks });
+        self.rumble_cooldown = ticks.max(RUMBLE_MIN_INTERVAL);
+        self.rumble_strength = strength;
     }
 }
@@ -51,22 +51,22 @@ impl PlayerController for KeyboardController {
             TargetPlayer::Player2 => &state.settings.player2_key_map,
         };
 
-        self.state.set_left(keyboard::is_key_pressed(ctx, keymap.left));
-        self.state.set_up(keyboard::is_key_pressed(ctx, keymap.up));
-        self.state.set_right(keyboard::is_key_pressed(ctx, keymap.right));
-        self.state.set_down(keyboard::is_key_pressed(ctx, keymap.down));
-        self.state.set_map(keyboard::is_key_pressed(ctx, keymap.map));
-        self.state.set_inventory(keyboard::is_key_pressed(ctx, keymap.inventory));
-        self.state.set_jump(keyboard::is_key_pressed(ctx, keymap.jump));
-        self.state.set_shoot(keyboard::is_key_pressed(ctx, keymap.shoot));
-        self.state.set_skip(keyboard::is_key_pressed(ctx, keymap.skip));
-        self.state.set_prev_weapon(keyboard::is_key_pressed(ctx, keymap.prev_weapon));
-        self.state.set_next_weapon(keyboard::is_key_pressed(ctx, keymap.next_weapon));
-        self.state.set_enter(keyboard::is_key_pressed(ctx, ScanCode::Return));
-        self.state.set_escape(keyboard::is_key_pressed(ctx, ScanCode::Escape));
-        self.state.set_strafe(keyboard::is_key_pressed(ctx, keymap.strafe));
-        self.state.set_menu_ok(keyboard::is_key_pressed(ctx, keymap.menu_ok));
-        self.state.set_menu_back(keyboard::is_key_pressed(ctx, keymap.menu_back));
+        self.state.set_left(keyboard::was_key_pressed(ctx, keymap.left));
+        self.state.set_up(keyboard::was_key_pressed(ctx, keymap.up));
+        self.state.set_right(keyboard::was_key_pressed(ctx, keymap.right));
+        self.state.set_down(keyboard::was_key_pressed(ctx, keymap.down));
+        self.state.set_map(keyboard::was_key_pressed(ctx, keymap.map));
+        self.state.set_inventory(keyboard::was_key_pressed(ctx, keymap.inventory));
+        self.state.set_jump(keyboard::was_key_pressed(ctx, keymap.jump));
+        self.state.set_shoot(keyboard::was_key_pressed(ctx, keymap.shoot));
+        self.state.set_skip(keyboard::was_key_pressed(ctx, keymap.skip));
+        self.state.set_prev_weapon(keyboard::was_key_pressed(ctx, keymap.prev_weapon));
+        self.state.set_next_weapon(keyboard::was_key_pressed(ctx, keymap.next_weapon));
+        self.state.set_enter(keyboard::was_key_pressed(ctx, ScanCode::Return));
+        self.state.set_escape(keyboard::was_key_pressed(ctx, ScanCode::Escape));
+        self.state.set_strafe(keyboard::was_key_pressed(ctx, keymap.strafe));
+        self.state.set_menu_ok(keyboard::was_key_pressed(ctx, keymap.menu_ok));
+        self.state.set_menu_back(keyboard::was_key_pressed(ctx, keymap.menu_back));
 
         Ok(())
     }
@@ -228,3 +228,27 @@ impl PlayerController for KeyboardController {
 
     fn set_rumble(&mut self, _low_freq: u16, _hi_freq: u16, _ticks: u32) {}
 }
+
+#[test]
+fn test_short_tap_triggers() {
+    use crate::game::HeadlessRunner;
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    let jump = state.settings.player1_key_map.jump;
+    let mut controller = KeyboardController::new(TargetPlayer::Player1);
+
+    // a 5ms tap, pressed and released again before the next tick polls the keyboard
+    ctx.keyboard_context.set_key(jump, true);
+    ctx.keyboard_context.set_key(jump, false);
+    assert!(!keyboard::is_key_pressed(&ctx, jump));
+
+    controller.update(&mut state, &mut ctx).unwrap();
+    controller.update_trigger();
+    assert!(controller.trigger_jump());
+    ctx.keyboard_context.clear_tapped_keys();
+
+    // and it only counts once
+    controller.update(&mut state, &mut ctx).unwrap();
+    controller.update_trigger();
+    assert!(!controller.jump() && !controller.trigger_jump());
+}
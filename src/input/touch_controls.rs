@@ -12,6 +12,9 @@ pub enum TouchControlType {
     Controls,
 }
 
+/// Touch point id used for mouse clicks, so they're handled the same way as taps.
+const MOUSE_POINT_ID: u64 = u64::MAX;
+
 #[derive(Copy, Clone)]
 pub struct TouchPoint {
     pub id: u64,
@@ -39,6 +42,25 @@ impl TouchControls {
         }
     }
 
+    /// Registers a mouse click at given canvas position, which menus can consume the same way as a tap.
+    /// The click stays around until the end of the next tick even if the button is released before it runs,
+    /// unless a menu consumes it or another click replaces it first.
+    pub fn mouse_press(&mut self, position: (f64, f64)) {
+        self.touch_id_counter = self.touch_id_counter.wrapping_add(1);
+        self.clicks.retain(|p| p.id != MOUSE_POINT_ID);
+        self.clicks.push(TouchPoint {
+            id: MOUSE_POINT_ID,
+            touch_id: self.touch_id_counter,
+            position,
+            last_position: position,
+        });
+    }
+
+    /// Forgets mouse clicks that weren't consumed yet.
+    pub fn drop_mouse_clicks(&mut self) {
+        self.clicks.retain(|p| p.id != MOUSE_POINT_ID);
+    }
+
     pub fn point_in(&self, bounds: Rect) -> Option<u64> {
         for point in &self.points {
             if (point.position.0 as isize) > bounds.left
@@ -143,7 +165,7 @@ impl TouchControls {
                 );
 
                 // Pause
-                batch.add_rect_tinted(4.0, 4.0, color, &Rect::new_size(32, 3 * 32, 32, 32));
+                batch.add_rect_tinted(4.0 + left, 4.0 + top, color, &Rect::new_size(32, 3 * 32, 32, 32));
 
                 batch.draw(ctx)?;
             }
@@ -152,3 +174,14 @@ impl TouchControls {
         Ok(())
     }
 }
+
+#[test]
+fn test_mouse_click_kept_until_consumed() {
+    let mut controls = TouchControls::new();
+    let bounds = Rect::new(0, 0, 20, 20);
+
+    controls.mouse_press((10.0, 10.0));
+    assert!(!controls.consume_click_in(Rect::new(30, 30, 50, 50)));
+    assert!(controls.consume_click_in(bounds));
+    assert!(!controls.consume_click_in(bounds));
+}
@@ -76,10 +76,10 @@ impl PlayerController for TouchPlayerController {
                 );
             }
             TouchControlType::Controls => {
-                let (left, _top, right, bottom) = screen_insets_scaled(ctx, state.scale);
+                let (left, top, right, bottom) = screen_insets_scaled(ctx, state.scale);
 
                 let left = 4 + left as isize;
-                let top = 4 + bottom as isize;
+                let top = 4 + top as isize;
                 let bottom = 4 + bottom as isize;
                 let right = 4 + right as isize;
 
@@ -235,7 +235,8 @@ impl PlayerController for TouchPlayerController {
                 );
 
                 self.state.set_pause(
-                    self.state.pause() || state.touch_controls.point_in(Rect::new_size(0, 0, 40, 40)).is_some(),
+                    self.state.pause()
+                        || state.touch_controls.point_in(Rect::new_size(left - 4, top - 4, 40, 40)).is_some(),
                 );
             }
         }
@@ -23,6 +23,10 @@ mod macros;
 mod menu;
 mod mod_list;
 mod mod_requirements;
+#[cfg(feature = "netplay")]
+pub mod netplay;
 mod scene;
 mod sound;
 mod util;
+
+pub use crate::sound::organya;
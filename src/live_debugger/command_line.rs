@@ -20,6 +20,9 @@ pub enum CommandLineCommand {
     AddXP(u16),
     RemoveXP(u16),
     SetMaxHP(u16),
+    SetHP(u16),
+    SetFlag(u16, bool),
+    Warp(u16, u16, u16),
     SpawnNPC(u16),
     TeleportPlayer(f32, f32),
     TSC(String),
@@ -136,6 +139,44 @@ impl CommandLineCommand {
                     return Some(CommandLineCommand::SetMaxHP(hp_count));
                 }
             }
+            "set_hp" => {
+                if components.len() < 2 {
+                    return None;
+                }
+
+                let hp_count = components[1].parse::<u16>();
+                if let Ok(hp_count) = hp_count {
+                    return Some(CommandLineCommand::SetHP(hp_count));
+                }
+            }
+            "set_flag" => {
+                if components.len() < 2 {
+                    return None;
+                }
+
+                let flag_id = components[1].parse::<u16>();
+                let value = match components.get(2) {
+                    Some(&"0") | Some(&"false") => false,
+                    Some(&"1") | Some(&"true") | None => true,
+                    Some(_) => return None,
+                };
+
+                if let Ok(flag_id) = flag_id {
+                    return Some(CommandLineCommand::SetFlag(flag_id, value));
+                }
+            }
+            "warp" => {
+                if components.len() < 4 {
+                    return None;
+                }
+
+                let stage_id = components[1].parse::<u16>();
+                let x = components[2].parse::<u16>();
+                let y = components[3].parse::<u16>();
+                if let (Ok(stage_id), Ok(x), Ok(y)) = (stage_id, x, y) {
+                    return Some(CommandLineCommand::Warp(stage_id, x, y));
+                }
+            }
             "spawn_npc" => {
                 if components.len() < 2 {
                     return None;
@@ -231,6 +272,42 @@ impl CommandLineCommand {
                 game_scene.player1.max_life = hp_count;
                 game_scene.player1.life = hp_count;
             }
+            CommandLineCommand::SetHP(hp_count) => {
+                if hp_count > game_scene.player1.max_life {
+                    return Err(CommandLineError(format!("HP can't exceed max HP of {}", game_scene.player1.max_life)));
+                }
+
+                game_scene.player1.life = hp_count;
+            }
+            CommandLineCommand::SetFlag(flag_id, value) => {
+                if flag_id as usize >= state.game_flags.len() {
+                    return Err(CommandLineError(format!(
+                        "Flag {} is out of range (0..{})",
+                        flag_id,
+                        state.game_flags.len()
+                    )));
+                }
+
+                state.set_flag(flag_id as usize, value);
+            }
+            CommandLineCommand::Warp(stage_id, x, y) => {
+                if stage_id as usize >= state.stages.len() {
+                    return Err(CommandLineError(format!("Invalid stage id {}", stage_id)));
+                }
+
+                // go through <TRA, so the transition runs the same way as in scripts
+                let script = format!("#9999\n<TRA{:04}:0094:{:04}:{:04}<END", stage_id, x, y);
+                match TextScript::compile(script.as_bytes(), true, TextScriptEncoding::UTF8) {
+                    Ok(text_script) => {
+                        state.textscript_vm.set_debug_script(text_script);
+                        state.textscript_vm.set_mode(ScriptMode::Debug);
+                        state.textscript_vm.start_script(9999);
+                    }
+                    Err(err) => {
+                        return Err(CommandLineError(format!("Error compiling TSC: {}", err)));
+                    }
+                };
+            }
             CommandLineCommand::SpawnNPC(id) => {
                 let mut npc = NPC::create(id, &state.npc_table);
                 npc.cond.set_alive(true);
@@ -276,6 +353,9 @@ impl CommandLineCommand {
             CommandLineCommand::AddXP(xp_count) => format!("/add_xp {}", xp_count),
             CommandLineCommand::RemoveXP(xp_count) => format!("/remove_xp {}", xp_count),
             CommandLineCommand::SetMaxHP(hp_count) => format!("/set_max_hp {}", hp_count),
+            CommandLineCommand::SetHP(hp_count) => format!("/set_hp {}", hp_count),
+            CommandLineCommand::SetFlag(flag_id, value) => format!("/set_flag {} {}", flag_id, *value as u8),
+            CommandLineCommand::Warp(stage_id, x, y) => format!("/warp {} {} {}", stage_id, x, y),
             CommandLineCommand::SpawnNPC(npc_id) => format!("/spawn_npc {}", npc_id),
             CommandLineCommand::TeleportPlayer(x, y) => format!("/teleport_player {} {}", x, y),
             CommandLineCommand::TSC(script) => format!("/tsc {}", script.replace("\n", "\\n")),
@@ -299,6 +379,9 @@ impl CommandLineCommand {
             CommandLineCommand::AddXP(xp_count) => format!("Added {} XP to current weapon.", xp_count),
             CommandLineCommand::RemoveXP(xp_count) => format!("Removed {} XP from current weapon.", xp_count),
             CommandLineCommand::SetMaxHP(hp_count) => format!("Set max HP of player to {}.", hp_count),
+            CommandLineCommand::SetHP(hp_count) => format!("Set HP of player to {}.", hp_count),
+            CommandLineCommand::SetFlag(flag_id, value) => format!("Set flag {} to {}.", flag_id, value),
+            CommandLineCommand::Warp(stage_id, x, y) => format!("Warped to stage {} at ({}, {}).", stage_id, x, y),
             CommandLineCommand::SpawnNPC(npc_id) => format!("Spawned NPC ID {} in front of player.", npc_id),
             CommandLineCommand::TeleportPlayer(x, y) => format!("Teleported players to ({}, {}).", x, y),
             CommandLineCommand::TSC(_) => "Executed TSC script.".to_string(),
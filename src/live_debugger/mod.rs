@@ -1,8 +1,10 @@
 use imgui::{CollapsingHeader, Condition, ImStr, ImString, Slider, Window};
 use itertools::Itertools;
 
+use crate::common::BUILD_INFO;
 use crate::framework::context::Context;
 use crate::framework::error::GameResult;
+use crate::framework::keyboard::ScanCode;
 use crate::game::scripting::tsc::text_script::TextScriptExecutionState;
 use crate::game::shared_game_state::SharedGameState;
 use crate::scene::game_scene::GameScene;
@@ -35,6 +37,7 @@ pub struct LiveDebugger {
     selected_event: i32,
     text_windows: Vec<(u32, ImString, ImString)>,
     error: Option<ImString>,
+    reload_script_key_held: bool,
 }
 
 impl LiveDebugger {
@@ -54,6 +57,14 @@ impl LiveDebugger {
             selected_event: -1,
             text_windows: Vec::new(),
             error: None,
+            reload_script_key_held: false,
+        }
+    }
+
+    fn reload_stage_script(&mut self, game_scene: &mut GameScene, state: &mut SharedGameState, ctx: &mut Context) {
+        if let Err(err) = game_scene.reload_stage_script(state, ctx) {
+            log::error!("Error reloading stage script: {:?}", err);
+            self.error = Some(ImString::new(err.to_string()));
         }
     }
 
@@ -70,6 +81,20 @@ impl LiveDebugger {
             self.selected_event = -1;
         }
 
+        // handled here instead of with the rest of debug keys, so parse errors can be shown in the error window
+        #[cfg(not(debug_assertions))]
+        let debug_keys_enabled = state.settings.debug_mode;
+        #[cfg(debug_assertions)]
+        let debug_keys_enabled = true;
+
+        let reload_script_key =
+            ctx.keyboard_context.is_key_pressed(ScanCode::R) && ctx.keyboard_context.active_mods().ctrl();
+        if debug_keys_enabled && reload_script_key && !self.reload_script_key_held {
+            self.reload_stage_script(game_scene, state, ctx);
+            self.events.clear();
+        }
+        self.reload_script_key_held = reload_script_key;
+
         if state.command_line {
             let width = state.screen_size.0;
             let height = 85.0;
@@ -111,7 +136,10 @@ impl LiveDebugger {
                                 }
                             },
                             None => {
-                                self.command_line_parser.last_feedback = "Invalid command".to_string();
+                                self.command_line_parser.last_feedback = format!(
+                                    "Unknown command or invalid arguments: {}",
+                                    self.command_line_parser.buffer
+                                );
                                 self.command_line_parser.last_feedback_color = [1.0, 0.0, 0.0, 1.0];
                                 state.sound_manager.play_sfx(12);
                             }
@@ -132,7 +160,7 @@ impl LiveDebugger {
             .resizable(false)
             .collapsed(true, Condition::FirstUseEver)
             .position([5.0, 5.0], Condition::FirstUseEver)
-            .size([400.0, 265.0], Condition::FirstUseEver)
+            .size([400.0, 280.0], Condition::FirstUseEver)
             .build(ui, || {
                 ui.text(format!(
                     "Player position: ({:.1},{:.1}), velocity: ({:.1},{:.1})",
@@ -159,9 +187,11 @@ impl LiveDebugger {
                     game_scene.player1.booster_fuel
                 ));
 
+                ui.text(format!("{}, data: {}", *BUILD_INFO, state.data_variant));
+
                 ui.text(format!("Game speed ({:.1} TPS):", state.current_tps()));
                 let mut speed = state.settings.speed;
-                Slider::new("", 0.1, 3.0).build(ui, &mut speed);
+                Slider::new("", 0.1, 4.0).build(ui, &mut speed);
                 ui.same_line();
                 if ui.button("Reset") {
                     speed = 1.0
@@ -186,6 +216,12 @@ impl LiveDebugger {
                     self.flags_visible = !self.flags_visible;
                 }
 
+                ui.same_line();
+                if ui.button("Reload TSC") {
+                    self.reload_stage_script(game_scene, state, ctx);
+                    self.events.clear();
+                }
+
                 #[cfg(feature = "scripting-lua")]
                 {
                     ui.same_line();
@@ -487,6 +523,7 @@ impl LiveDebugger {
                         "` > Toggle Command Line",
                         "Ctrl + F3 > Reload Sound Manager",
                         "Ctrl + S > Quick Save",
+                        "Ctrl + R > Reload Stage Script",
                     ];
                     for hotkeys in key.iter() {
                         match hotkeys {
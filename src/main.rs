@@ -3,23 +3,18 @@
 use std::process::exit;
 
 fn main() {
-    let args = std::env::args();
-    let mut options = doukutsu_rs::game::LaunchOptions { server_mode: false, editor: false };
-
-    for arg in args {
-        if arg == "--server-mode" {
-            options.server_mode = true;
-        }
-
-        if arg == "--editor" {
-            options.editor = true;
-        }
+    if std::env::args().any(|arg| arg == "--version") {
+        println!("{}", doukutsu_rs::game::build_info());
+        return;
     }
 
-    if options.server_mode && options.editor {
-        eprintln!("Cannot run in server mode and editor mode at the same time.");
-        exit(1);
-    }
+    let options = match doukutsu_rs::game::LaunchOptions::parse(std::env::args()) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(1);
+        }
+    };
 
     let result = doukutsu_rs::game::init(options);
 
@@ -3,7 +3,7 @@ use crate::framework::error::GameResult;
 use crate::framework::gamepad::{self, Axis, AxisDirection, Button, PlayerControllerInputType};
 use crate::framework::keyboard::ScanCode;
 use crate::game::settings::{
-    p1_default_keymap, p2_default_keymap, player_default_controller_button_map, ControllerType,
+    p1_default_keymap, p1_wasd_keymap, p2_default_keymap, player_default_controller_button_map, ControllerType,
     PlayerControllerButtonMap, PlayerKeyMap,
 };
 use crate::game::shared_game_state::SharedGameState;
@@ -26,6 +26,26 @@ const FORBIDDEN_SCANCODES: [ScanCode; 12] = [
     ScanCode::F12,
 ];
 
+/// Controls that can't share a key with each other. Skip shares its key with the inventory by default,
+/// and menu controls with jump and shoot, so those are kept in separate groups.
+const GAMEPLAY_CONTROLS: [ControlEntry; 11] = [
+    ControlEntry::Left,
+    ControlEntry::Up,
+    ControlEntry::Right,
+    ControlEntry::Down,
+    ControlEntry::PrevWeapon,
+    ControlEntry::NextWeapon,
+    ControlEntry::Jump,
+    ControlEntry::Shoot,
+    ControlEntry::Inventory,
+    ControlEntry::Map,
+    ControlEntry::Strafe,
+];
+
+const MENU_CONTROLS: [ControlEntry; 2] = [ControlEntry::MenuOk, ControlEntry::MenuBack];
+
+const RUMBLE_INTENSITY_STEPS: [u8; 5] = [0, 25, 50, 75, 100];
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 #[repr(u8)]
 enum CurrentMenu {
@@ -42,6 +62,7 @@ enum MainMenuEntry {
     Controller,
     Rebind,
     Rumble,
+    RumbleIntensity,
     Back,
 }
 
@@ -67,6 +88,7 @@ impl Default for SelectControllerMenuEntry {
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 enum RebindMenuEntry {
     Control(ControlEntry),
+    WasdLayout,
     Reset,
     Back,
 }
@@ -156,6 +178,8 @@ pub struct ControlsMenu {
     selected_player: Player,
     selected_controller: ControllerType,
     selected_control: Option<ControlEntry>,
+    /// Whether confirming the reset menu applies the WASD layout instead of the default bindings.
+    reset_to_wasd: bool,
 
     player1_key_map: Vec<(ControlEntry, ScanCode)>,
     player2_key_map: Vec<(ControlEntry, ScanCode)>,
@@ -184,6 +208,7 @@ impl ControlsMenu {
             selected_player: Player::Player1,
             selected_controller: ControllerType::Keyboard,
             selected_control: None,
+            reset_to_wasd: false,
 
             player1_key_map: Vec::new(),
             player2_key_map: Vec::new(),
@@ -216,6 +241,7 @@ impl ControlsMenu {
             MenuEntry::Active(state.loc.t("menus.controls_menu.rebind").to_owned()),
         );
         self.main.push_entry(MainMenuEntry::Rumble, MenuEntry::Hidden);
+        self.main.push_entry(MainMenuEntry::RumbleIntensity, MenuEntry::Hidden);
         self.main.push_entry(MainMenuEntry::Back, MenuEntry::Active(state.loc.t("common.back").to_owned()));
 
         self.confirm_reset.push_entry(
@@ -383,6 +409,15 @@ impl ControlsMenu {
             }
         }
 
+        // only offered to player 1: it moves them off the arrow keys, away from player 2's `, . / L`,
+        // while a WASD player 2 would clash with player 1's default A/S/Q/W keys
+        if self.selected_player == Player::Player1 && self.selected_controller == ControllerType::Keyboard {
+            self.rebind.push_entry(
+                RebindMenuEntry::WasdLayout,
+                MenuEntry::Active(state.loc.t("menus.controls_menu.wasd_layout").to_owned()),
+            );
+        }
+
         self.rebind.push_entry(
             RebindMenuEntry::Reset,
             MenuEntry::Active(state.loc.t("menus.controls_menu.reset_confirm").to_owned()),
@@ -438,16 +473,35 @@ impl ControlsMenu {
             if index as usize >= available_gamepads {
                 self.selected_controller = ControllerType::Keyboard;
                 self.main.set_entry(MainMenuEntry::Rumble, MenuEntry::Hidden);
+                self.main.set_entry(MainMenuEntry::RumbleIntensity, MenuEntry::Hidden);
             } else {
                 self.selected_controller = controller_type;
                 self.main.set_entry(
                     MainMenuEntry::Rumble,
                     MenuEntry::Toggle(state.loc.t("menus.controls_menu.rumble").to_owned(), rumble),
                 );
+
+                let intensity = RUMBLE_INTENSITY_STEPS
+                    .iter()
+                    .position(|&step| step == state.settings.rumble_intensity)
+                    .unwrap_or(RUMBLE_INTENSITY_STEPS.len() - 1);
+                let steps = RUMBLE_INTENSITY_STEPS
+                    .iter()
+                    .map(|&step| if step == 0 { state.loc.t("common.off").to_owned() } else { format!("{}%", step) })
+                    .collect();
+                self.main.set_entry(
+                    MainMenuEntry::RumbleIntensity,
+                    MenuEntry::Options(
+                        state.loc.t("menus.controls_menu.rumble_intensity").to_owned(),
+                        intensity,
+                        steps,
+                    ),
+                );
             }
         } else {
             self.selected_controller = controller_type;
             self.main.set_entry(MainMenuEntry::Rumble, MenuEntry::Hidden);
+            self.main.set_entry(MainMenuEntry::RumbleIntensity, MenuEntry::Hidden);
         }
 
         match self.selected_controller {
@@ -483,7 +537,8 @@ impl ControlsMenu {
         match self.selected_player {
             Player::Player1 => {
                 if self.selected_controller == ControllerType::Keyboard {
-                    state.settings.player1_key_map = p1_default_keymap();
+                    state.settings.player1_key_map =
+                        if self.reset_to_wasd { p1_wasd_keymap() } else { p1_default_keymap() };
                     self.player1_key_map = self.init_key_map(&state.settings.player1_key_map);
                 } else {
                     state.settings.player1_controller_button_map = player_default_controller_button_map();
@@ -521,146 +576,60 @@ impl ControlsMenu {
         false
     }
 
-    fn set_key(&mut self, state: &mut SharedGameState, scan_code: ScanCode, ctx: &Context) -> GameResult {
-        if self.selected_control.is_none() {
-            return Ok(());
+    fn key_map_entry(key_map: &mut PlayerKeyMap, entry: ControlEntry) -> &mut ScanCode {
+        match entry {
+            ControlEntry::Left => &mut key_map.left,
+            ControlEntry::Up => &mut key_map.up,
+            ControlEntry::Right => &mut key_map.right,
+            ControlEntry::Down => &mut key_map.down,
+            ControlEntry::PrevWeapon => &mut key_map.prev_weapon,
+            ControlEntry::NextWeapon => &mut key_map.next_weapon,
+            ControlEntry::Jump => &mut key_map.jump,
+            ControlEntry::Shoot => &mut key_map.shoot,
+            ControlEntry::Skip => &mut key_map.skip,
+            ControlEntry::Inventory => &mut key_map.inventory,
+            ControlEntry::Map => &mut key_map.map,
+            ControlEntry::Strafe => &mut key_map.strafe,
+            ControlEntry::MenuOk => &mut key_map.menu_ok,
+            ControlEntry::MenuBack => &mut key_map.menu_back,
         }
+    }
 
-        let mut did_swap_controls = false;
-
-        match self.selected_control.unwrap() {
-            ControlEntry::Left => match self.selected_player {
-                Player::Player1 => state.settings.player1_key_map.left = scan_code,
-                Player::Player2 => state.settings.player2_key_map.left = scan_code,
-            },
-            ControlEntry::Up => match self.selected_player {
-                Player::Player1 => state.settings.player1_key_map.up = scan_code,
-                Player::Player2 => state.settings.player2_key_map.up = scan_code,
-            },
-            ControlEntry::Right => match self.selected_player {
-                Player::Player1 => state.settings.player1_key_map.right = scan_code,
-                Player::Player2 => state.settings.player2_key_map.right = scan_code,
-            },
-            ControlEntry::Down => match self.selected_player {
-                Player::Player1 => state.settings.player1_key_map.down = scan_code,
-                Player::Player2 => state.settings.player2_key_map.down = scan_code,
-            },
-            ControlEntry::PrevWeapon => match self.selected_player {
-                Player::Player1 => state.settings.player1_key_map.prev_weapon = scan_code,
-                Player::Player2 => state.settings.player2_key_map.prev_weapon = scan_code,
-            },
-            ControlEntry::NextWeapon => match self.selected_player {
-                Player::Player1 => state.settings.player1_key_map.next_weapon = scan_code,
-                Player::Player2 => state.settings.player2_key_map.next_weapon = scan_code,
-            },
-            ControlEntry::Jump => match self.selected_player {
-                Player::Player1 => {
-                    did_swap_controls = self.swap_if_same(
-                        &mut state.settings.player1_key_map.jump,
-                        &mut state.settings.player1_key_map.shoot,
-                        scan_code,
-                    );
-                }
-                Player::Player2 => {
-                    did_swap_controls = self.swap_if_same(
-                        &mut state.settings.player2_key_map.jump,
-                        &mut state.settings.player2_key_map.shoot,
-                        scan_code,
-                    );
-                }
-            },
-            ControlEntry::Shoot => match self.selected_player {
-                Player::Player1 => {
-                    did_swap_controls = self.swap_if_same(
-                        &mut state.settings.player1_key_map.shoot,
-                        &mut state.settings.player1_key_map.jump,
-                        scan_code,
-                    );
-                }
-                Player::Player2 => {
-                    did_swap_controls = self.swap_if_same(
-                        &mut state.settings.player2_key_map.shoot,
-                        &mut state.settings.player2_key_map.jump,
-                        scan_code,
-                    );
-                }
-            },
-            ControlEntry::Skip => match self.selected_player {
-                Player::Player1 => state.settings.player1_key_map.skip = scan_code,
-                Player::Player2 => state.settings.player2_key_map.skip = scan_code,
-            },
-            ControlEntry::Inventory => match self.selected_player {
-                Player::Player1 => state.settings.player1_key_map.inventory = scan_code,
-                Player::Player2 => state.settings.player2_key_map.inventory = scan_code,
-            },
-            ControlEntry::Map => match self.selected_player {
-                Player::Player1 => state.settings.player1_key_map.map = scan_code,
-                Player::Player2 => state.settings.player2_key_map.map = scan_code,
-            },
-            ControlEntry::Strafe => match self.selected_player {
-                Player::Player1 => state.settings.player1_key_map.strafe = scan_code,
-                Player::Player2 => state.settings.player2_key_map.strafe = scan_code,
-            },
-            ControlEntry::MenuOk => match self.selected_player {
-                Player::Player1 => {
-                    did_swap_controls = self.swap_if_same(
-                        &mut state.settings.player1_key_map.menu_ok,
-                        &mut state.settings.player1_key_map.menu_back,
-                        scan_code,
-                    );
-                }
-                Player::Player2 => {
-                    did_swap_controls = self.swap_if_same(
-                        &mut state.settings.player2_key_map.menu_ok,
-                        &mut state.settings.player2_key_map.menu_back,
-                        scan_code,
-                    );
-                }
-            },
-            ControlEntry::MenuBack => match self.selected_player {
-                Player::Player1 => {
-                    did_swap_controls = self.swap_if_same(
-                        &mut state.settings.player1_key_map.menu_back,
-                        &mut state.settings.player1_key_map.menu_ok,
-                        scan_code,
-                    );
-                }
-                Player::Player2 => {
-                    did_swap_controls = self.swap_if_same(
-                        &mut state.settings.player2_key_map.menu_back,
-                        &mut state.settings.player2_key_map.menu_ok,
-                        scan_code,
-                    );
-                }
-            },
-        }
+    fn set_key(&mut self, state: &mut SharedGameState, scan_code: ScanCode, ctx: &Context) -> GameResult {
+        let selected_control = match self.selected_control {
+            Some(control) => control,
+            None => return Ok(()),
+        };
 
-        state.settings.save(ctx)?;
+        let key_map = match self.selected_player {
+            Player::Player1 => &mut state.settings.player1_key_map,
+            Player::Player2 => &mut state.settings.player2_key_map,
+        };
 
-        let keymap = match self.selected_player {
-            Player::Player1 => &mut self.player1_key_map,
-            Player::Player2 => &mut self.player2_key_map,
+        let exclusive_group: &[ControlEntry] = if MENU_CONTROLS.contains(&selected_control) {
+            &MENU_CONTROLS
+        } else if GAMEPLAY_CONTROLS.contains(&selected_control) {
+            &GAMEPLAY_CONTROLS
+        } else {
+            &[]
         };
 
-        for (entry, value) in keymap.iter_mut() {
-            if *entry == self.selected_control.unwrap() {
-                *value = scan_code;
+        // the action that already used this key gets the old key of the rebound one, so a key never does two things
+        let old_key = *Self::key_map_entry(key_map, selected_control);
+        for &entry in exclusive_group {
+            let key = Self::key_map_entry(key_map, entry);
+            if entry != selected_control && *key == scan_code {
+                *key = old_key;
             }
+        }
 
-            if did_swap_controls {
-                let map = match self.selected_player {
-                    Player::Player1 => &state.settings.player1_key_map,
-                    Player::Player2 => &state.settings.player2_key_map,
-                };
+        *Self::key_map_entry(key_map, selected_control) = scan_code;
 
-                match *entry {
-                    ControlEntry::Jump => *value = map.jump,
-                    ControlEntry::Shoot => *value = map.shoot,
-                    ControlEntry::MenuOk => *value = map.menu_ok,
-                    ControlEntry::MenuBack => *value = map.menu_back,
-                    _ => {}
-                }
-            }
+        state.settings.save(ctx)?;
+
+        match self.selected_player {
+            Player::Player1 => self.player1_key_map = self.init_key_map(&state.settings.player1_key_map),
+            Player::Player2 => self.player2_key_map = self.init_key_map(&state.settings.player2_key_map),
         }
 
         Ok(())
@@ -956,6 +925,21 @@ impl ControlsMenu {
                         state.settings.save(ctx)?;
                     }
                 }
+                MenuSelectionResult::Selected(MainMenuEntry::RumbleIntensity, toggle)
+                | MenuSelectionResult::Right(MainMenuEntry::RumbleIntensity, toggle, _) => {
+                    if let MenuEntry::Options(_, value, _) = toggle {
+                        *value = (*value + 1) % RUMBLE_INTENSITY_STEPS.len();
+                        state.settings.rumble_intensity = RUMBLE_INTENSITY_STEPS[*value];
+                        state.settings.save(ctx)?;
+                    }
+                }
+                MenuSelectionResult::Left(MainMenuEntry::RumbleIntensity, toggle, _) => {
+                    if let MenuEntry::Options(_, value, _) = toggle {
+                        *value = (*value + RUMBLE_INTENSITY_STEPS.len() - 1) % RUMBLE_INTENSITY_STEPS.len();
+                        state.settings.rumble_intensity = RUMBLE_INTENSITY_STEPS[*value];
+                        state.settings.save(ctx)?;
+                    }
+                }
                 MenuSelectionResult::Selected(MainMenuEntry::Back, _) | MenuSelectionResult::Canceled => exit_action(),
                 _ => {}
             },
@@ -1021,7 +1005,23 @@ impl ControlsMenu {
                         self.current = CurrentMenu::ConfirmRebindMenu;
                     }
                 }
+                MenuSelectionResult::Selected(RebindMenuEntry::WasdLayout, _) => {
+                    self.reset_to_wasd = true;
+                    self.confirm_reset.set_entry(
+                        ConfirmResetMenuEntry::Title,
+                        MenuEntry::Disabled(
+                            state.loc.t("menus.controls_menu.wasd_layout_confirm_menu_title").to_owned(),
+                        ),
+                    );
+                    self.confirm_reset.selected = ConfirmResetMenuEntry::default();
+                    self.current = CurrentMenu::ConfirmResetMenu;
+                }
                 MenuSelectionResult::Selected(RebindMenuEntry::Reset, _) => {
+                    self.reset_to_wasd = false;
+                    self.confirm_reset.set_entry(
+                        ConfirmResetMenuEntry::Title,
+                        MenuEntry::Disabled(state.loc.t("menus.controls_menu.reset_confirm_menu_title").to_owned()),
+                    );
                     self.confirm_reset.selected = ConfirmResetMenuEntry::default();
                     self.current = CurrentMenu::ConfirmResetMenu;
                 }
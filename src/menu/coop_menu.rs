@@ -153,11 +153,10 @@ impl PlayerCountMenu {
                         if state.player2_skin_location.offset * 2 * 16 >= tex_size.1 {
                             state.player2_skin_location.offset = 0;
 
-                            if state.player2_skin_location.texture_index == 1 {
-                                state.player2_skin_location.texture_index = 0;
-                            } else {
-                                state.player2_skin_location.texture_index = 1;
-                            }
+                            // move on to the next spritesheet, wrapping around to the first one
+                            let sheet_count = state.constants.player_skin_paths.len();
+                            state.player2_skin_location.texture_index =
+                                (state.player2_skin_location.texture_index + 1) % sheet_count as u16;
                         }
                     }
                 }
@@ -153,6 +153,22 @@ impl PauseMenu {
     pub fn pause(&mut self, state: &mut SharedGameState) {
         self.is_paused = true;
         state.sound_manager.play_sfx(5);
+        state.sound_manager.set_song_ducked(state.settings.pause_duck_music);
+    }
+
+    /// Pauses the game and asks whether to quit, warning about unsaved progress if there's any.
+    pub fn open_quit_confirm(&mut self, state: &mut SharedGameState) {
+        let label = if state.unsaved_progress {
+            "menus.pause_menu.quit_confirm_unsaved"
+        } else {
+            "menus.pause_menu.quit_confirm"
+        };
+
+        self.confirm_menu.set_entry(ConfirmMenuEntry::Empty, MenuEntry::Disabled(state.loc.t(label).to_owned()));
+        self.pause_menu.selected = PauseMenuEntry::Quit;
+        self.current_menu = CurrentMenu::ConfirmMenu;
+        self.is_paused = true;
+        state.sound_manager.set_song_ducked(state.settings.pause_duck_music);
     }
 
     pub fn is_paused(&mut self) -> bool {
@@ -168,6 +184,7 @@ impl PauseMenu {
         // Shortcut for quick restart
         if ctx.keyboard_context.is_key_pressed(ScanCode::F2) {
             state.stop_noise();
+            state.sound_manager.set_song_ducked(false);
             state.sound_manager.play_song(0, &state.constants, &state.settings, ctx)?;
             state.load_or_start_game(ctx)?;
         }
@@ -184,10 +201,12 @@ impl PauseMenu {
                     if self.tick >= 3 {
                         self.tick = 0;
                         self.is_paused = false;
+                        state.sound_manager.set_song_ducked(false);
                     }
                 }
                 MenuSelectionResult::Selected(PauseMenuEntry::Retry, _) => {
                     state.stop_noise();
+                    state.sound_manager.set_song_ducked(false);
                     state.sound_manager.play_song(0, &state.constants, &state.settings, ctx)?;
                     state.load_or_start_game(ctx)?;
                 }
@@ -216,11 +235,7 @@ impl PauseMenu {
                     self.current_menu = CurrentMenu::ConfirmMenu;
                 }
                 MenuSelectionResult::Selected(PauseMenuEntry::Quit, _) => {
-                    self.confirm_menu.set_entry(
-                        ConfirmMenuEntry::Empty,
-                        MenuEntry::Disabled(state.loc.t("menus.pause_menu.quit_confirm").to_owned()),
-                    );
-                    self.current_menu = CurrentMenu::ConfirmMenu;
+                    self.open_quit_confirm(state);
                 }
                 _ => (),
             },
@@ -253,6 +268,7 @@ impl PauseMenu {
                 MenuSelectionResult::Selected(ConfirmMenuEntry::Yes, _) => match self.pause_menu.selected {
                     PauseMenuEntry::Title => {
                         state.stop_noise();
+                        state.sound_manager.set_song_ducked(false);
                         state.textscript_vm.flags.set_cutscene_skip(false);
                         state.next_scene = Some(Box::new(TitleScene::new()));
                     }
@@ -4,7 +4,9 @@ use crate::framework::context::Context;
 use crate::framework::error::GameResult;
 use crate::framework::graphics::VSyncMode;
 use crate::framework::{filesystem, graphics};
-use crate::game::shared_game_state::{CutsceneSkipMode, ScreenShakeIntensity, SharedGameState, TimingMode, WindowMode};
+use crate::game::shared_game_state::{
+    CanvasMode, CutsceneSkipMode, ScalingMode, ScreenShakeIntensity, SharedGameState, TimingMode, WindowMode,
+};
 use crate::graphics::font::Font;
 use crate::input::combined_menu_controller::CombinedMenuController;
 use crate::menu::MenuEntry;
@@ -49,7 +51,10 @@ impl Default for MainMenuEntry {
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 enum GraphicsMenuEntry {
     WindowMode,
+    ScalingMode,
+    CanvasMode,
     VSyncMode,
+    FrameCap,
     LightingEffects,
     WeaponLightCone,
     ScreenShake,
@@ -58,6 +63,9 @@ enum GraphicsMenuEntry {
     OriginalTextures,
     SeasonalTextures,
     Renderer,
+    PixelSnapping,
+    ReducedFlashing,
+    LinearFiltering,
     Back,
 }
 
@@ -69,16 +77,19 @@ impl Default for GraphicsMenuEntry {
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 enum SoundMenuEntry {
+    MasterVolume,
     MusicVolume,
     EffectsVolume,
     BGMInterpolation,
+    PauseDuckMusic,
+    PositionalAudio,
     Soundtrack,
     Back,
 }
 
 impl Default for SoundMenuEntry {
     fn default() -> Self {
-        SoundMenuEntry::MusicVolume
+        SoundMenuEntry::MasterVolume
     }
 }
 
@@ -112,6 +123,10 @@ enum BehaviorMenuEntry {
     GameTiming,
     PauseOnFocusLoss,
     CutsceneSkipMode,
+    CameraLookShift,
+    BootSplash,
+    TransitionInvincibility,
+    WaterExitBoost,
     Back,
 }
 
@@ -147,6 +162,21 @@ pub struct SettingsMenu {
     pub on_title: bool,
 }
 
+/// Grace periods (in ticks) selectable for the transition invincibility option.
+const TRANSITION_INVINCIBILITY_STEPS: [u16; 4] = [0, 25, 50, 100];
+/// Frame rate limits selectable in the graphics menu, 0 meaning none.
+const FRAME_CAP_STEPS: [u16; 6] = [0, 30, 60, 120, 144, 240];
+/// Scaling modes selectable in the graphics menu, other fixed scales can only be set in the settings file.
+const SCALING_MODE_STEPS: [ScalingMode; 7] = [
+    ScalingMode::Integer,
+    ScalingMode::Stretch,
+    ScalingMode::Fixed(1.0),
+    ScalingMode::Fixed(2.0),
+    ScalingMode::Fixed(3.0),
+    ScalingMode::Fixed(4.0),
+    ScalingMode::Fixed(5.0),
+];
+
 static DISCORD_LINK: &str = "https://discord.gg/fbRsNNB";
 static GITHUB_LINK: &str = "https://github.com/doukutsu-rs/doukutsu-rs";
 static DOCS_LINK: &str = "https://doukutsu-rs.gitbook.io/docs/";
@@ -195,6 +225,44 @@ impl SettingsMenu {
             ),
         );
 
+        let mut scaling_modes: Vec<String> = SCALING_MODE_STEPS
+            .iter()
+            .map(|mode| match mode {
+                ScalingMode::Integer => state.loc.t("menus.options_menu.graphics_menu.scaling_mode.integer").to_owned(),
+                ScalingMode::Stretch => state.loc.t("menus.options_menu.graphics_menu.scaling_mode.stretch").to_owned(),
+                ScalingMode::Fixed(scale) => format!("{}x", scale),
+            })
+            .collect();
+        // a custom scale from the settings file is shown as an extra option past the regular ones
+        let scaling_mode = match SCALING_MODE_STEPS.iter().position(|&mode| mode == state.settings.scaling_mode) {
+            Some(idx) => idx,
+            None => {
+                scaling_modes.push(state.loc.t("menus.options_menu.graphics_menu.scaling_mode.fixed").to_owned());
+                SCALING_MODE_STEPS.len()
+            }
+        };
+
+        self.graphics.push_entry(
+            GraphicsMenuEntry::ScalingMode,
+            MenuEntry::Options(
+                state.loc.t("menus.options_menu.graphics_menu.scaling_mode.entry").to_owned(),
+                scaling_mode,
+                scaling_modes,
+            ),
+        );
+
+        self.graphics.push_entry(
+            GraphicsMenuEntry::CanvasMode,
+            MenuEntry::Options(
+                state.loc.t("menus.options_menu.graphics_menu.canvas_mode.entry").to_owned(),
+                state.settings.canvas_mode as usize,
+                vec![
+                    state.loc.t("menus.options_menu.graphics_menu.canvas_mode.extended").to_owned(),
+                    state.loc.t("menus.options_menu.graphics_menu.canvas_mode.classic").to_owned(),
+                ],
+            ),
+        );
+
         self.graphics.push_entry(
             GraphicsMenuEntry::VSyncMode,
             MenuEntry::DescriptiveOptions(
@@ -216,6 +284,19 @@ impl SettingsMenu {
                 ],
             ),
         );
+
+        self.graphics.push_entry(
+            GraphicsMenuEntry::FrameCap,
+            MenuEntry::Options(
+                state.loc.t("menus.options_menu.graphics_menu.frame_cap").to_owned(),
+                FRAME_CAP_STEPS.iter().position(|&cap| cap == state.settings.frame_cap).unwrap_or(0),
+                FRAME_CAP_STEPS
+                    .iter()
+                    .map(|&cap| if cap == 0 { state.loc.t("common.off").to_owned() } else { cap.to_string() })
+                    .collect(),
+            ),
+        );
+
         self.graphics.push_entry(
             GraphicsMenuEntry::LightingEffects,
             MenuEntry::Toggle(
@@ -294,6 +375,30 @@ impl SettingsMenu {
             )),
         );
 
+        self.graphics.push_entry(
+            GraphicsMenuEntry::PixelSnapping,
+            MenuEntry::Toggle(
+                state.loc.t("menus.options_menu.graphics_menu.pixel_snapping").to_owned(),
+                state.settings.pixel_snapping,
+            ),
+        );
+
+        self.graphics.push_entry(
+            GraphicsMenuEntry::ReducedFlashing,
+            MenuEntry::Toggle(
+                state.loc.t("menus.options_menu.graphics_menu.reduced_flashing").to_owned(),
+                state.settings.reduced_flashing,
+            ),
+        );
+
+        self.graphics.push_entry(
+            GraphicsMenuEntry::LinearFiltering,
+            MenuEntry::Toggle(
+                state.loc.t("menus.options_menu.graphics_menu.linear_filtering").to_owned(),
+                state.settings.linear_filtering,
+            ),
+        );
+
         self.graphics.push_entry(GraphicsMenuEntry::Back, MenuEntry::Active(state.loc.t("common.back").to_owned()));
 
         self.main.push_entry(
@@ -352,6 +457,13 @@ impl SettingsMenu {
 
         self.main.push_entry(MainMenuEntry::Back, MenuEntry::Active(state.loc.t("common.back").to_owned()));
 
+        self.sound.push_entry(
+            SoundMenuEntry::MasterVolume,
+            MenuEntry::OptionsBar(
+                state.loc.t("menus.options_menu.sound_menu.master_volume").to_owned(),
+                state.settings.master_volume,
+            ),
+        );
         self.sound.push_entry(
             SoundMenuEntry::MusicVolume,
             MenuEntry::OptionsBar(
@@ -388,6 +500,20 @@ impl SettingsMenu {
                 ],
             ),
         );
+        self.sound.push_entry(
+            SoundMenuEntry::PauseDuckMusic,
+            MenuEntry::Toggle(
+                state.loc.t("menus.options_menu.sound_menu.pause_duck_music").to_owned(),
+                state.settings.pause_duck_music,
+            ),
+        );
+        self.sound.push_entry(
+            SoundMenuEntry::PositionalAudio,
+            MenuEntry::Toggle(
+                state.loc.t("menus.options_menu.sound_menu.positional_audio").to_owned(),
+                state.settings.positional_audio,
+            ),
+        );
         self.sound.push_entry(
             SoundMenuEntry::Soundtrack,
             MenuEntry::Active(
@@ -462,6 +588,47 @@ impl SettingsMenu {
             ),
         );
 
+        self.behavior.push_entry(
+            BehaviorMenuEntry::CameraLookShift,
+            MenuEntry::Toggle(
+                state.loc.t("menus.options_menu.behavior_menu.camera_look_shift").to_owned(),
+                state.settings.camera_look_shift,
+            ),
+        );
+
+        self.behavior.push_entry(
+            BehaviorMenuEntry::BootSplash,
+            MenuEntry::Toggle(
+                state.loc.t("menus.options_menu.behavior_menu.boot_splash").to_owned(),
+                state.settings.boot_splash,
+            ),
+        );
+
+        self.behavior.push_entry(
+            BehaviorMenuEntry::TransitionInvincibility,
+            MenuEntry::Options(
+                state.loc.t("menus.options_menu.behavior_menu.transition_invincibility.entry").to_owned(),
+                TRANSITION_INVINCIBILITY_STEPS
+                    .iter()
+                    .position(|&t| t == state.settings.transition_invincibility)
+                    .unwrap_or(0),
+                vec![
+                    state.loc.t("menus.options_menu.behavior_menu.transition_invincibility.off").to_owned(),
+                    state.loc.t("menus.options_menu.behavior_menu.transition_invincibility.short").to_owned(),
+                    state.loc.t("menus.options_menu.behavior_menu.transition_invincibility.medium").to_owned(),
+                    state.loc.t("menus.options_menu.behavior_menu.transition_invincibility.long").to_owned(),
+                ],
+            ),
+        );
+
+        self.behavior.push_entry(
+            BehaviorMenuEntry::WaterExitBoost,
+            MenuEntry::Toggle(
+                state.loc.t("menus.options_menu.behavior_menu.water_exit_boost").to_owned(),
+                state.settings.water_exit_boost,
+            ),
+        );
+
         self.behavior.push_entry(BehaviorMenuEntry::Back, MenuEntry::Active(state.loc.t("common.back").to_owned()));
 
         self.links.push_entry(LinksMenuEntry::Back, MenuEntry::Active(state.loc.t("common.back").to_owned()));
@@ -559,6 +726,44 @@ impl SettingsMenu {
                         let _ = state.settings.save(ctx);
                     }
                 }
+                MenuSelectionResult::Selected(GraphicsMenuEntry::ScalingMode, toggle)
+                | MenuSelectionResult::Right(GraphicsMenuEntry::ScalingMode, toggle, _) => {
+                    if let MenuEntry::Options(_, value, options) = toggle {
+                        // a custom scale sits past the last regular option and is dropped once cycled away from
+                        *value = if *value + 1 < SCALING_MODE_STEPS.len() { *value + 1 } else { 0 };
+                        options.truncate(SCALING_MODE_STEPS.len());
+                        state.settings.scaling_mode = SCALING_MODE_STEPS[*value];
+                        let _ = state.handle_resize(ctx);
+
+                        let _ = state.settings.save(ctx);
+                    }
+                }
+                MenuSelectionResult::Left(GraphicsMenuEntry::ScalingMode, toggle, _) => {
+                    if let MenuEntry::Options(_, value, options) = toggle {
+                        *value = if *value > 0 { *value - 1 } else { SCALING_MODE_STEPS.len() - 1 };
+                        options.truncate(SCALING_MODE_STEPS.len());
+                        state.settings.scaling_mode = SCALING_MODE_STEPS[*value];
+                        let _ = state.handle_resize(ctx);
+
+                        let _ = state.settings.save(ctx);
+                    }
+                }
+                MenuSelectionResult::Selected(GraphicsMenuEntry::CanvasMode, toggle)
+                | MenuSelectionResult::Right(GraphicsMenuEntry::CanvasMode, toggle, _)
+                | MenuSelectionResult::Left(GraphicsMenuEntry::CanvasMode, toggle, _) => {
+                    if let MenuEntry::Options(_, value, _) = toggle {
+                        let (new_mode, new_value) = match *value {
+                            0 => (CanvasMode::Classic, 1),
+                            _ => (CanvasMode::Extended, 0),
+                        };
+
+                        *value = new_value;
+                        state.settings.canvas_mode = new_mode;
+                        let _ = state.handle_resize(ctx);
+
+                        let _ = state.settings.save(ctx);
+                    }
+                }
                 MenuSelectionResult::Selected(GraphicsMenuEntry::VSyncMode, toggle)
                 | MenuSelectionResult::Right(GraphicsMenuEntry::VSyncMode, toggle, _) => {
                     if let MenuEntry::DescriptiveOptions(_, value, _, _) = toggle {
@@ -594,6 +799,21 @@ impl SettingsMenu {
                         let _ = state.settings.save(ctx);
                     }
                 }
+                MenuSelectionResult::Selected(GraphicsMenuEntry::FrameCap, toggle)
+                | MenuSelectionResult::Right(GraphicsMenuEntry::FrameCap, toggle, _) => {
+                    if let MenuEntry::Options(_, value, _) = toggle {
+                        *value = (*value + 1) % FRAME_CAP_STEPS.len();
+                        state.settings.frame_cap = FRAME_CAP_STEPS[*value];
+                        let _ = state.settings.save(ctx);
+                    }
+                }
+                MenuSelectionResult::Left(GraphicsMenuEntry::FrameCap, toggle, _) => {
+                    if let MenuEntry::Options(_, value, _) = toggle {
+                        *value = (*value + FRAME_CAP_STEPS.len() - 1) % FRAME_CAP_STEPS.len();
+                        state.settings.frame_cap = FRAME_CAP_STEPS[*value];
+                        let _ = state.settings.save(ctx);
+                    }
+                }
                 MenuSelectionResult::Selected(GraphicsMenuEntry::LightingEffects, toggle) => {
                     if let MenuEntry::Toggle(_, value) = toggle {
                         state.settings.shader_effects = !state.settings.shader_effects;
@@ -677,12 +897,48 @@ impl SettingsMenu {
                         *value = state.settings.seasonal_textures;
                     }
                 }
+                MenuSelectionResult::Selected(GraphicsMenuEntry::PixelSnapping, toggle) => {
+                    if let MenuEntry::Toggle(_, value) = toggle {
+                        state.settings.pixel_snapping = !state.settings.pixel_snapping;
+                        let _ = state.settings.save(ctx);
+                        let _ = state.handle_resize(ctx);
+
+                        *value = state.settings.pixel_snapping;
+                    }
+                }
+                MenuSelectionResult::Selected(GraphicsMenuEntry::ReducedFlashing, toggle) => {
+                    if let MenuEntry::Toggle(_, value) = toggle {
+                        state.settings.reduced_flashing = !state.settings.reduced_flashing;
+                        let _ = state.settings.save(ctx);
+
+                        *value = state.settings.reduced_flashing;
+                    }
+                }
+                MenuSelectionResult::Selected(GraphicsMenuEntry::LinearFiltering, toggle) => {
+                    if let MenuEntry::Toggle(_, value) = toggle {
+                        state.settings.linear_filtering = !state.settings.linear_filtering;
+                        state.texture_set.set_filter(state.settings.texture_filter());
+                        let _ = state.settings.save(ctx);
+
+                        *value = state.settings.linear_filtering;
+                    }
+                }
                 MenuSelectionResult::Selected(GraphicsMenuEntry::Back, _) | MenuSelectionResult::Canceled => {
                     self.current = CurrentMenu::MainMenu
                 }
                 _ => (),
             },
             CurrentMenu::SoundMenu => match self.sound.tick(controller, state) {
+                MenuSelectionResult::Left(SoundMenuEntry::MasterVolume, master, direction)
+                | MenuSelectionResult::Right(SoundMenuEntry::MasterVolume, master, direction) => {
+                    if let MenuEntry::OptionsBar(_, value) = master {
+                        *value = (*value * 10.0 + (direction as f32)).clamp(0.0, 10.0) / 10.0;
+                        state.settings.master_volume = *value;
+                        state.sound_manager.set_master_volume(*value);
+
+                        let _ = state.settings.save(ctx);
+                    }
+                }
                 MenuSelectionResult::Left(SoundMenuEntry::MusicVolume, bgm, direction)
                 | MenuSelectionResult::Right(SoundMenuEntry::MusicVolume, bgm, direction) => {
                     if let MenuEntry::OptionsBar(_, value) = bgm {
@@ -736,6 +992,26 @@ impl SettingsMenu {
                         let _ = state.settings.save(ctx);
                     }
                 }
+                MenuSelectionResult::Selected(SoundMenuEntry::PauseDuckMusic, toggle) => {
+                    if let MenuEntry::Toggle(_, value) = toggle {
+                        state.settings.pause_duck_music = !state.settings.pause_duck_music;
+                        let _ = state.settings.save(ctx);
+
+                        *value = state.settings.pause_duck_music;
+                    }
+                }
+                MenuSelectionResult::Selected(SoundMenuEntry::PositionalAudio, toggle) => {
+                    if let MenuEntry::Toggle(_, value) = toggle {
+                        state.settings.positional_audio = !state.settings.positional_audio;
+                        state.sound_manager.set_positional_audio(
+                            state.settings.positional_audio,
+                            state.settings.positional_audio_range,
+                        );
+                        let _ = state.settings.save(ctx);
+
+                        *value = state.settings.positional_audio;
+                    }
+                }
                 MenuSelectionResult::Selected(SoundMenuEntry::Soundtrack, _) => {
                     let mut active_soundtrack = SoundtrackMenuEntry::Soundtrack(0);
 
@@ -853,6 +1129,37 @@ impl SettingsMenu {
                         let _ = state.settings.save(ctx);
                     }
                 }
+                MenuSelectionResult::Selected(BehaviorMenuEntry::CameraLookShift, toggle) => {
+                    if let MenuEntry::Toggle(_, value) = toggle {
+                        state.settings.camera_look_shift = !state.settings.camera_look_shift;
+                        let _ = state.settings.save(ctx);
+
+                        *value = state.settings.camera_look_shift;
+                    }
+                }
+                MenuSelectionResult::Selected(BehaviorMenuEntry::BootSplash, toggle) => {
+                    if let MenuEntry::Toggle(_, value) = toggle {
+                        state.settings.boot_splash = !state.settings.boot_splash;
+                        let _ = state.settings.save(ctx);
+
+                        *value = state.settings.boot_splash;
+                    }
+                }
+                MenuSelectionResult::Selected(BehaviorMenuEntry::TransitionInvincibility, toggle) => {
+                    if let MenuEntry::Options(_, value, _) = toggle {
+                        *value = (*value + 1) % TRANSITION_INVINCIBILITY_STEPS.len();
+                        state.settings.transition_invincibility = TRANSITION_INVINCIBILITY_STEPS[*value];
+                        let _ = state.settings.save(ctx);
+                    }
+                }
+                MenuSelectionResult::Selected(BehaviorMenuEntry::WaterExitBoost, toggle) => {
+                    if let MenuEntry::Toggle(_, value) = toggle {
+                        state.settings.water_exit_boost = !state.settings.water_exit_boost;
+                        let _ = state.settings.save(ctx);
+
+                        *value = state.settings.water_exit_boost;
+                    }
+                }
                 MenuSelectionResult::Selected(BehaviorMenuEntry::Back, _) | MenuSelectionResult::Canceled => {
                     self.current = CurrentMenu::MainMenu;
                 }
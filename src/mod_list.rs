@@ -149,34 +149,43 @@ impl ModList {
                     }
                 }
 
-                let mut valid = false;
-                let mut name = String::new();
-                let mut description = String::new();
-                let mut save_slot = -1;
-
-                if let Ok(file) = filesystem::open(ctx, [&path, "/mod.txt"].join("")) {
-                    valid = true;
-                    let reader = BufReader::new(file);
-                    let mut lines = reader.lines();
-                    if let Some(line) = lines.nth(1) {
-                        save_slot = line.unwrap_or("-1".to_string()).parse::<i32>().unwrap_or(-1);
-                    }
-                    if let Some(line) = lines.next() {
-                        let read_name = line.unwrap_or("No Mod Name".to_string()).to_string();
-                        name = string_table.get(&read_name).unwrap_or(&read_name).to_string();
-                    }
-                    if let Some(line) = lines.next() {
-                        description = line.unwrap_or("No Description".to_string()).to_string();
-                    }
-                } else {
-                    name = path.clone();
-                    description = "mod.txt not found".to_string();
-                }
+                let (valid, save_slot, name, description) = match read_mod_txt(ctx, &path, string_table) {
+                    Some((save_slot, name, description)) => (true, save_slot, name, description),
+                    None => (false, -1, path.clone(), "mod.txt not found".to_string()),
+                };
 
                 mods.push(ModInfo { id, requirement, priority, save_slot, path, name, description, valid })
             }
         }
 
+        // Data directories dropped into mods/<name>/ are layered over the base data when selected.
+        if let Ok(dir) = filesystem::read_dir(ctx, "/mods/") {
+            let mut entries: Vec<_> = dir.filter(|entry| filesystem::is_dir(ctx, entry)).collect();
+            entries.sort();
+
+            for entry in entries {
+                let dir_name = entry.file_name().unwrap_or_default().to_string_lossy().to_string();
+                let path = format!("/mods/{}/", dir_name);
+                if mods.iter().any(|m| m.path.trim_end_matches('/') == path.trim_end_matches('/')) {
+                    continue;
+                }
+
+                let (save_slot, name, description) = read_mod_txt(ctx, &path, string_table)
+                    .unwrap_or_else(|| (dir_mod_save_slot(&dir_name), dir_name.clone(), String::new()));
+
+                mods.push(ModInfo {
+                    id: format!("dirmod_{}", dir_name),
+                    requirement: Requirement::Unlocked,
+                    priority: u32::MAX,
+                    save_slot,
+                    path,
+                    name,
+                    description,
+                    valid: true,
+                });
+            }
+        }
+
         mods.sort_by(|a, b| a.priority.cmp(&b.priority));
 
         Ok(ModList { mods })
@@ -198,3 +207,62 @@ impl ModList {
         }
     }
 }
+
+/// Directory mods without a mod.txt don't have a save slot, so one is derived from the directory name.
+/// It's kept well above the slots handed out in mods.txt, so the saves don't end up mixed up.
+fn dir_mod_save_slot(dir_name: &str) -> i32 {
+    let hash = dir_name.bytes().fold(0x811c9dc5u32, |hash, b| (hash ^ b as u32).wrapping_mul(0x01000193));
+
+    10000 + (hash % 1_000_000) as i32
+}
+
+/// Reads the save slot, name and description from the mod.txt file in given mod directory.
+fn read_mod_txt(
+    ctx: &mut Context,
+    path: &str,
+    string_table: &HashMap<String, String>,
+) -> Option<(i32, String, String)> {
+    let file = filesystem::open(ctx, [path, "/mod.txt"].join("")).ok()?;
+    let reader = BufReader::new(file);
+    let mut lines = reader.lines();
+    let mut save_slot = -1;
+    let mut name = String::new();
+    let mut description = String::new();
+
+    if let Some(line) = lines.nth(1) {
+        save_slot = line.unwrap_or("-1".to_string()).parse::<i32>().unwrap_or(-1);
+    }
+    if let Some(line) = lines.next() {
+        let read_name = line.unwrap_or("No Mod Name".to_string()).to_string();
+        name = string_table.get(&read_name).unwrap_or(&read_name).to_string();
+    }
+    if let Some(line) = lines.next() {
+        description = line.unwrap_or("No Description".to_string()).to_string();
+    }
+
+    Some((save_slot, name, description))
+}
+
+#[test]
+fn test_mod_list_directory_mods() {
+    use crate::framework::filesystem::TempDataDir;
+
+    let dir = TempDataDir::new("mod-list");
+    std::fs::create_dir_all(dir.root.join("mods/dropped")).unwrap();
+    dir.write("mods.txt", "=MOD LIST START=\nlisted R+ P1 /mods/listed\n");
+    dir.write("mods/listed/mod.txt", "\n2\nListed\nA listed mod\n");
+
+    let mut ctx = Context::new();
+    dir.mount(&mut ctx);
+    let mod_list = ModList::load(&mut ctx, &HashMap::new()).unwrap();
+
+    // the directory of the listed mod isn't picked up a second time
+    assert_eq!(mod_list.mods.len(), 2);
+    assert_eq!(mod_list.mods[0].id, "csmod_listed");
+    assert_eq!(mod_list.mods[0].save_slot, 2);
+    assert_eq!(mod_list.mods[1].id, "dirmod_dropped");
+    assert_eq!(mod_list.mods[1].name, "dropped");
+    assert_eq!(mod_list.get_save_from_path("/mods/dropped/".to_string()), dir_mod_save_slot("dropped"));
+    assert!(dir_mod_save_slot("dropped") > 0);
+    assert_ne!(dir_mod_save_slot("dropped"), dir_mod_save_slot("other"));
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use crate::framework::error::{GameError, GameResult};
+
+pub use self::session::{LockstepSession, INPUT_DELAY};
+
+mod session;
+
+/// Bumped whenever the message format or the simulation changes in a way that would make peers desync.
+pub const PROTOCOL_VERSION: u16 = 1;
+
+/// Messages exchanged between two peers playing in lockstep. The game logic is deterministic as long as
+/// both sides start from the same `game_rng` state, so only the inputs for every tick have to be sent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetMessage {
+    /// Sent by the joining peer, the host replies with `Welcome` if the versions match.
+    Hello {
+        version: u16,
+    },
+    /// Sent by the host, carries the RNG state both peers start the simulation from.
+    Welcome {
+        rng_seed: u64,
+    },
+    /// Key state of the sending player for given tick, in the same layout as replay key states.
+    Input {
+        tick: u32,
+        keys: u16,
+    },
+    Disconnect,
+}
+
+impl NetMessage {
+    pub fn encode(&self) -> GameResult<Vec<u8>> {
+        serde_cbor::to_vec(self).map_err(|err| GameError::ParseError(err.to_string()))
+    }
+
+    pub fn decode(data: &[u8]) -> GameResult<NetMessage> {
+        serde_cbor::from_slice(data).map_err(|err| GameError::ParseError(err.to_string()))
+    }
+}
+
+#[test]
+fn test_message_round_trip() {
+    let messages = [
+        NetMessage::Hello { version: PROTOCOL_VERSION },
+        NetMessage::Welcome { rng_seed: 0x1234_5678_9abc_def0 },
+        NetMessage::Input { tick: 600, keys: 0b0100_0011 },
+        NetMessage::Disconnect,
+    ];
+
+    for message in messages {
+        assert_eq!(NetMessage::decode(&message.encode().unwrap()).unwrap(), message);
+    }
+
+    assert!(NetMessage::decode(&[0xff, 0x00]).is_err());
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use crate::framework::error::GameResult;
+use crate::netplay::{NetMessage, PROTOCOL_VERSION};
+
+/// Local inputs are scheduled this many ticks ahead, so the peer's keys usually arrive before they're needed.
+pub const INPUT_DELAY: u32 = 2;
+
+/// How long to wait for the peer before sending the last messages again.
+const RESEND_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long to wait for a peer that stopped responding, unless overridden with `LockstepSession::timeout`.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Two peers running the same simulation in lockstep over UDP. Every tick both sides exchange their keys
+/// and only advance once they have the other one's, so packets that get lost are simply sent again.
+pub struct LockstepSession {
+    socket: UdpSocket,
+    peer: SocketAddr,
+    is_host: bool,
+    rng_seed: u64,
+    tick: u32,
+    local_inputs: HashMap<u32, u16>,
+    remote_inputs: HashMap<u32, u16>,
+    /// Gives up on the peer after not hearing from it for this long.
+    pub timeout: Duration,
+}
+
+impl LockstepSession {
+    fn new(socket: UdpSocket, peer: SocketAddr, is_host: bool, rng_seed: u64) -> LockstepSession {
+        // nobody has pressed anything during the first ticks covered by the input delay
+        let idle: HashMap<u32, u16> = (0..INPUT_DELAY).map(|tick| (tick, 0)).collect();
+
+        LockstepSession {
+            socket,
+            peer,
+            is_host,
+            rng_seed,
+            tick: 0,
+            local_inputs: idle.clone(),
+            remote_inputs: idle,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Waits for a peer to join on `socket` and tells it the seed to start the simulation from.
+    pub fn host(socket: UdpSocket, rng_seed: u64) -> GameResult<LockstepSession> {
+        socket.set_read_timeout(Some(RESEND_INTERVAL))?;
+
+        loop {
+            let (message, peer) = match recv(&socket)? {
+                Some(received) => received,
+                None => continue,
+            };
+
+            match message {
+                NetMessage::Hello { version } if version == PROTOCOL_VERSION => {
+                    send(&socket, peer, &NetMessage::Welcome { rng_seed })?;
+                    return Ok(LockstepSession::new(socket, peer, true, rng_seed));
+                }
+                NetMessage::Hello { version } => {
+                    log::warn!("Rejected peer {} using protocol version {}.", peer, version);
+                    send(&socket, peer, &NetMessage::Disconnect)?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Joins the game hosted at `host`, returns once the host has replied with the seed.
+    pub fn join(socket: UdpSocket, host: SocketAddr) -> GameResult<LockstepSession> {
+        socket.set_read_timeout(Some(RESEND_INTERVAL))?;
+
+        let started = Instant::now();
+        let hello = NetMessage::Hello { version: PROTOCOL_VERSION };
+        send(&socket, host, &hello)?;
+
+        loop {
+            match recv(&socket)? {
+                Some((NetMessage::Welcome { rng_seed }, peer)) if peer == host => {
+                    return Ok(LockstepSession::new(socket, host, false, rng_seed));
+                }
+                Some((NetMessage::Disconnect, peer)) if peer == host => {
+                    return Err(io::Error::new(io::ErrorKind::ConnectionRefused, "host rejected the connection").into());
+                }
+                Some(_) => {}
+                None if started.elapsed() >= DEFAULT_TIMEOUT => {
+                    return Err(io::Error::new(io::ErrorKind::TimedOut, "host did not respond").into());
+                }
+                None => send(&socket, host, &hello)?,
+            }
+        }
+    }
+
+    /// The host controls player 1, the peer that joined player 2.
+    pub fn is_host(&self) -> bool {
+        self.is_host
+    }
+
+    /// Game RNG seed both peers have to start the simulation with.
+    pub fn rng_seed(&self) -> u64 {
+        self.rng_seed
+    }
+
+    /// Index of the tick the next call to `advance` returns the keys for.
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+
+    /// Schedules `local_keys` for `INPUT_DELAY` ticks from now, then waits for the peer's keys for the current
+    /// tick and returns the keys of both players for it, local ones first.
+    pub fn advance(&mut self, local_keys: u16) -> GameResult<(u16, u16)> {
+        let tick = self.tick;
+        let input_tick = tick + INPUT_DELAY;
+        self.local_inputs.insert(input_tick, local_keys);
+        send(&self.socket, self.peer, &NetMessage::Input { tick: input_tick, keys: local_keys })?;
+
+        let mut last_heard = Instant::now();
+        while !self.remote_inputs.contains_key(&tick) {
+            match recv(&self.socket)? {
+                Some((message, peer)) if peer == self.peer => {
+                    last_heard = Instant::now();
+
+                    match message {
+                        NetMessage::Input { tick, keys } => {
+                            self.remote_inputs.insert(tick, keys);
+                        }
+                        NetMessage::Disconnect => {
+                            return Err(io::Error::new(io::ErrorKind::ConnectionAborted, "peer disconnected").into());
+                        }
+                        // the host's welcome got lost, the peer is still trying to join
+                        NetMessage::Hello { .. } if self.is_host => {
+                            send(&self.socket, peer, &NetMessage::Welcome { rng_seed: self.rng_seed })?;
+                        }
+                        _ => {}
+                    }
+                }
+                Some(_) => {}
+                None if last_heard.elapsed() >= self.timeout => {
+                    return Err(io::Error::new(io::ErrorKind::TimedOut, "peer stopped responding").into());
+                }
+                None => self.resend_inputs()?,
+            }
+        }
+
+        let keys = (self.local_inputs[&tick], self.remote_inputs[&tick]);
+
+        // the peer can't be further behind than the input delay, older keys won't be asked for again
+        let oldest = (tick + 1).saturating_sub(INPUT_DELAY + 1);
+        self.local_inputs.retain(|&t, _| t >= oldest);
+        self.remote_inputs.retain(|&t, _| t > tick);
+        self.tick += 1;
+
+        Ok(keys)
+    }
+
+    /// Sends every input the peer might still be waiting for, in case some of them got lost.
+    fn resend_inputs(&self) -> GameResult {
+        for (&tick, &keys) in self.local_inputs.iter() {
+            send(&self.socket, self.peer, &NetMessage::Input { tick, keys })?;
+        }
+
+        Ok(())
+    }
+
+    /// Tells the peer the session is over, it's not waited for to take notice.
+    pub fn disconnect(self) -> GameResult {
+        send(&self.socket, self.peer, &NetMessage::Disconnect)
+    }
+}
+
+fn send(socket: &UdpSocket, peer: SocketAddr, message: &NetMessage) -> GameResult {
+    socket.send_to(&message.encode()?, peer)?;
+
+    Ok(())
+}
+
+/// Returns `None` once the read timeout passes without a message, garbage packets are dropped.
+fn recv(socket: &UdpSocket) -> GameResult<Option<(NetMessage, SocketAddr)>> {
+    let mut buf = [0u8; 64];
+
+    match socket.recv_from(&mut buf) {
+        Ok((len, peer)) => Ok(NetMessage::decode(&buf[..len]).ok().map(|message| (message, peer))),
+        Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[test]
+fn test_loopback_session() {
+    let host_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let host_addr = host_socket.local_addr().unwrap();
+
+    let host = std::thread::spawn(move || {
+        let mut session = LockstepSession::host(host_socket, 0xdead_beef).unwrap();
+        assert!(session.is_host());
+
+        (0..30).map(|tick| session.advance(tick).unwrap()).collect::<Vec<_>>()
+    });
+
+    let join_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut session = LockstepSession::join(join_socket, host_addr).unwrap();
+    assert!(!session.is_host());
+    assert_eq!(session.rng_seed(), 0xdead_beef);
+
+    let join_keys: Vec<(u16, u16)> = (0..30).map(|tick| session.advance(tick + 1000).unwrap()).collect();
+    let host_keys = host.join().unwrap();
+
+    // both peers see the same keys for every tick, delayed by the input delay
+    for tick in 0..30u16 {
+        let (host_local, host_remote) = host_keys[tick as usize];
+        assert_eq!(join_keys[tick as usize], (host_remote, host_local));
+
+        if tick < INPUT_DELAY as u16 {
+            assert_eq!(host_keys[tick as usize], (0, 0));
+        } else {
+            let sent = tick - INPUT_DELAY as u16;
+            assert_eq!(host_keys[tick as usize], (sent, sent + 1000));
+        }
+    }
+}
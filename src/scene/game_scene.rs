@@ -4,7 +4,8 @@ use std::rc::Rc;
 
 use log::info;
 
-use crate::common::{interpolate_fix9_scale, Color, Direction, Rect};
+use crate::common::{interpolate_fix9_scale, Color, Direction, FadeState, Rect};
+use crate::components::ambient_emitters::AmbientEmitters;
 use crate::components::background::Background;
 use crate::components::boss_life_bar::BossLifeBar;
 use crate::components::credits::Credits;
@@ -20,20 +21,21 @@ use crate::components::replay::Replay;
 use crate::components::stage_select::StageSelect;
 use crate::components::text_boxes::TextBoxes;
 use crate::components::tilemap::{TileLayer, Tilemap};
+use crate::components::toast::Toast;
 use crate::components::water_renderer::{WaterLayer, WaterRenderer};
 use crate::components::whimsical_star::WhimsicalStar;
 use crate::entity::GameEntity;
 use crate::framework::backend::SpriteBatchCommand;
 use crate::framework::context::Context;
 use crate::framework::error::GameResult;
-use crate::framework::graphics::{draw_rect, BlendMode, FilterMode};
-use crate::framework::keyboard::ScanCode;
+use crate::framework::graphics::{draw_rect, BlendMode};
+use crate::framework::keyboard::{self, ScanCode};
 use crate::framework::ui::Components;
 use crate::framework::{filesystem, gamepad, graphics};
 use crate::game::caret::CaretType;
 use crate::game::frame::{Frame, UpdateTarget};
 use crate::game::inventory::{Inventory, TakeExperienceResult};
-use crate::game::map::WaterParams;
+use crate::game::map::{AmbientEmitterParams, WaterParams};
 use crate::game::npc::boss::BossNPC;
 use crate::game::npc::list::NPCList;
 use crate::game::npc::{NPCLayer, NPC};
@@ -42,12 +44,16 @@ use crate::game::player::{ControlMode, Player, TargetPlayer};
 use crate::game::scripting::tsc::credit_script::CreditScriptVM;
 use crate::game::scripting::tsc::text_script::{ScriptMode, TextScriptExecutionState, TextScriptVM};
 use crate::game::settings::ControllerType;
-use crate::game::shared_game_state::{CutsceneSkipMode, PlayerCount, ReplayState, SharedGameState, TileSize};
+use crate::game::shared_game_state::{
+    CutsceneSkipMode, DeferredAction, PlayerCount, ReplayState, SharedGameState, TileSize,
+};
 use crate::game::stage::{BackgroundType, Stage, StageTexturePaths};
 use crate::game::weapon::bullet::BulletManager;
 use crate::game::weapon::{Weapon, WeaponType};
 use crate::graphics::font::{Font, Symbols};
 use crate::graphics::texture_set::SpriteBatch;
+use crate::input::dummy_player_controller::DummyPlayerController;
+use crate::input::player_controller::PlayerController;
 use crate::input::touch_controls::TouchControlType;
 use crate::menu::pause_menu::PauseMenu;
 use crate::scene::title_scene::TitleScene;
@@ -59,6 +65,7 @@ pub struct GameScene {
     pub stage: Stage,
     pub water_params: WaterParams,
     pub water_renderer: WaterRenderer,
+    pub ambient_emitters: AmbientEmitters,
     pub boss_life_bar: BossLifeBar,
     pub stage_select: StageSelect,
     pub flash: Flash,
@@ -88,9 +95,13 @@ pub struct GameScene {
     pub pause_menu: PauseMenu,
     pub stage_textures: Rc<RefCell<StageTexturePaths>>,
     pub replay: Replay,
+    pub toast: Toast,
     map_name_counter: u16,
     skip_counter: u16,
     inventory_dim: f32,
+    quicksave_key_held: bool,
+    quickload_key_held: bool,
+    frame_held_controller: Box<dyn PlayerController>,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -116,6 +127,11 @@ impl GameScene {
         let mut water_params = WaterParams::new();
         let mut water_renderer = WaterRenderer::new();
         let mut tilemap = Tilemap::new();
+        let mut ambient_emitters = AmbientEmitters::new();
+
+        if state.settings.deterministic_effects {
+            state.reseed_effect_rng(&stage.data.map);
+        }
 
         if !state.settings.original_textures {
             if let Ok(water_param_file) = filesystem::open_find(
@@ -132,6 +148,18 @@ impl GameScene {
             }
         }
 
+        if let Ok(emitter_param_file) = filesystem::open_find(
+            ctx,
+            &state.constants.base_paths,
+            ["Stage/", &state.stages[id].tileset.name, ".pxam"].join(""),
+        ) {
+            let mut emitter_params = AmbientEmitterParams::new();
+            emitter_params.load_from(emitter_param_file)?;
+            info!("Loaded ambient emitter parameters file.");
+
+            ambient_emitters.initialize(state, &emitter_params, &stage);
+        }
+
         let stage_textures = {
             let mut textures = StageTexturePaths::new();
             textures.update(&stage);
@@ -139,6 +167,7 @@ impl GameScene {
         };
 
         let mut player2 = Player::new(state, ctx);
+        player2.respawn_on_death = true;
 
         if state.player2_skin_location.texture_index != 0 {
             let skinsheet_name =
@@ -151,6 +180,7 @@ impl GameScene {
             stage,
             water_params,
             water_renderer,
+            ambient_emitters,
             player1: Player::new(state, ctx),
             player2: player2,
             inventory_player1: Inventory::new(),
@@ -183,6 +213,11 @@ impl GameScene {
             skip_counter: 0,
             inventory_dim: 0.0,
             replay: Replay::new(),
+            toast: Toast::new(),
+            // treated as held until released, so holding the quickload key doesn't keep reloading the new scene
+            quicksave_key_held: true,
+            quickload_key_held: true,
+            frame_held_controller: Box::new(DummyPlayerController::new()),
         })
     }
 
@@ -190,6 +225,76 @@ impl GameScene {
         self.map_name_counter = ticks;
     }
 
+    /// Whether a gamepad one of the players was using got unplugged, in which case the game pauses
+    /// instead of carrying on without them.
+    fn lost_player_gamepad(&self, state: &SharedGameState, ctx: &mut Context) -> bool {
+        gamepad::take_disconnected_gamepads(ctx).iter().any(|&index| {
+            state.settings.player1_controller_type == ControllerType::Gamepad(index)
+                || (state.player_count == PlayerCount::Two
+                    && state.settings.player2_controller_type == ControllerType::Gamepad(index))
+        })
+    }
+
+    /// Quicksaves are only made while the player is in control and no script is running,
+    /// the profile doesn't store script state so it couldn't be resumed on load.
+    fn can_quicksave(&self, state: &SharedGameState) -> bool {
+        !self.intro_mode
+            && state.replay_state == ReplayState::None
+            && state.textscript_vm.mode == ScriptMode::Map
+            && state.textscript_vm.state == TextScriptExecutionState::Ended
+            && state.control_flags.control_enabled()
+            && state.fade_state == FadeState::Visible
+            && self.player1.cond.alive()
+    }
+
+    fn tick_quicksave(&mut self, state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
+        let save_pressed = keyboard::is_key_pressed(ctx, state.settings.quicksave_key);
+        let load_pressed = keyboard::is_key_pressed(ctx, state.settings.quickload_key);
+        let save_triggered = save_pressed && !self.quicksave_key_held;
+        let load_triggered = load_pressed && !self.quickload_key_held;
+        self.quicksave_key_held = save_pressed;
+        self.quickload_key_held = load_pressed;
+
+        if save_triggered && self.can_quicksave(state) {
+            match state.quick_save(self, ctx) {
+                Ok(true) => self.toast.show(state.loc.t("game.quicksave_done")),
+                Ok(false) => {}
+                Err(e) => log::error!("Failed to quicksave: {}", e),
+            }
+        } else if load_triggered && !self.intro_mode && state.replay_state == ReplayState::None {
+            match state.quick_load(ctx) {
+                Ok(true) => {}
+                Ok(false) => self.toast.show(state.loc.t("game.quickload_missing")),
+                Err(e) => log::error!("Failed to quickload: {}", e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The part of a tick that keeps running while frame advance holds the world still. Player controllers aren't
+    /// updated, so whatever gets tapped in between reaches the world on the next step. A separate controller with
+    /// player 1's mapping watches for the pause button in the meantime.
+    fn tick_frame_held(&mut self, state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
+        self.frame_held_controller.update(state, ctx)?;
+        self.frame_held_controller.update_trigger();
+
+        if self.frame_held_controller.trigger_menu_pause() || self.lost_player_gamepad(state, ctx) {
+            self.pause_menu.pause(state);
+        }
+
+        self.tick_quicksave(state, ctx)
+    }
+
+    /// Removes every enemy in the room with a single flash and explosion sound, used to end enemy waves.
+    /// Bosses live in a separate list and aren't affected.
+    pub fn clear_room(&mut self, state: &mut SharedGameState) {
+        if self.npc_list.remove_enemies(state) != 0 {
+            self.flash.set_blink();
+            state.sound_manager.play_sfx(44);
+        }
+    }
+
     pub fn add_player2(&mut self, state: &mut SharedGameState, ctx: &mut Context) {
         self.player2.cond.set_alive(true);
         self.player2.cond.set_hidden(self.player1.cond.hidden());
@@ -207,9 +312,40 @@ impl GameScene {
 
     pub fn drop_player2(&mut self) {
         self.player2.cond.set_alive(false);
+        self.player2.respawn_counter = 0;
+    }
+
+    /// Reloads the stage's TSC from disk, so script edits can be tested without restarting the game.
+    pub fn reload_stage_script(&self, state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
+        let script = self.stage.load_text_script(&state.constants.base_paths, &state.constants, ctx)?;
+        state.textscript_vm.reload_scene_script(script);
+        log::info!("Reloaded the script of stage {}.", self.stage.data.map);
+
+        Ok(())
+    }
+
+    /// Brings back player 2 next to player 1 with full health after they died in co-op.
+    fn respawn_player2(&mut self, state: &mut SharedGameState) {
+        self.player2.cond.set_alive(true);
+        self.player2.cond.set_hidden(self.player1.cond.hidden());
+        self.player2.life = self.player2.max_life;
+        self.player2.shock_counter = state.constants.player.invulnerability_ticks;
+        self.player2.x = self.player1.x;
+        self.player2.y = self.player1.y;
+        self.player2.vel_x = 0;
+        self.player2.vel_y = 0;
+
+        let mut npc = NPC::create(4, &state.npc_table);
+        npc.x = self.player2.x;
+        npc.y = self.player2.y;
+        npc.cond.set_alive(true);
+
+        let _ = self.npc_list.spawn(0x100, npc);
     }
 
     fn draw_npc_layer(&self, state: &mut SharedGameState, ctx: &mut Context, layer: NPCLayer) -> GameResult {
+        let mut pending_sheet = None;
+
         for npc in self.npc_list.iter_alive() {
             if npc.layer != layer
                 || npc.x < (self.frame.x - 128 * 0x200 - npc.display_bounds.width() as i32 * 0x200)
@@ -226,7 +362,28 @@ impl GameScene {
                 continue;
             }
 
-            npc.draw(state, ctx, &self.frame)?;
+            // consecutive NPCs sharing a spritesheet are submitted as a single batch, which keeps the draw order intact
+            if npc.is_batchable(state) {
+                if pending_sheet != Some(npc.spritesheet_id) {
+                    Self::flush_npc_batch(state, ctx, pending_sheet)?;
+                    pending_sheet = Some(npc.spritesheet_id);
+                }
+
+                npc.draw_into_batch(state, ctx, &self.frame)?;
+            } else {
+                Self::flush_npc_batch(state, ctx, pending_sheet.take())?;
+                npc.draw(state, ctx, &self.frame)?;
+            }
+        }
+
+        Self::flush_npc_batch(state, ctx, pending_sheet)
+    }
+
+    fn flush_npc_batch(state: &mut SharedGameState, ctx: &mut Context, spritesheet_id: Option<u16>) -> GameResult {
+        if let Some(spritesheet_id) = spritesheet_id {
+            let texture_ref = state.npc_table.get_texture_ref(spritesheet_id);
+            let batch = state.texture_set.get_or_load_batch(ctx, &state.constants, &*texture_ref)?;
+            batch.draw(ctx)?;
         }
 
         Ok(())
@@ -296,20 +453,30 @@ impl GameScene {
     fn draw_carets(&self, state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
         let batch = state.texture_set.get_or_load_batch(ctx, &state.constants, "Caret")?;
 
+        let (canvas_w, canvas_h) = state.canvas_size;
+
         for caret in state.carets.iter() {
-            batch.add_rect(
-                interpolate_fix9_scale(
-                    caret.prev_x - caret.offset_x - self.frame.prev_x,
-                    caret.x - caret.offset_x - self.frame.x,
-                    state.frame_time,
-                ),
-                interpolate_fix9_scale(
-                    caret.prev_y - caret.offset_y - self.frame.prev_y,
-                    caret.y - caret.offset_y - self.frame.y,
-                    state.frame_time,
-                ),
-                &caret.anim_rect,
+            let x = interpolate_fix9_scale(
+                caret.prev_x - caret.offset_x - self.frame.prev_x,
+                caret.x - caret.offset_x - self.frame.x,
+                state.frame_time,
+            );
+            let y = interpolate_fix9_scale(
+                caret.prev_y - caret.offset_y - self.frame.prev_y,
+                caret.y - caret.offset_y - self.frame.y,
+                state.frame_time,
             );
+
+            // skip off-screen carets instead of pushing their quads into the batch
+            if x + caret.anim_rect.width() as f32 < 0.0
+                || y + caret.anim_rect.height() as f32 < 0.0
+                || x > canvas_w
+                || y > canvas_h
+            {
+                continue;
+            }
+
+            batch.add_rect(x, y, &caret.anim_rect);
         }
 
         batch.draw(ctx)?;
@@ -1070,7 +1237,8 @@ impl GameScene {
                 }
             }
 
-            batch.draw_filtered(FilterMode::Linear, ctx)?;
+            // unfiltered like it always was, the texture filter option only covers stage textures
+            batch.draw(ctx)?;
         }
 
         graphics::set_blend_mode(ctx, BlendMode::Multiply)?;
@@ -1163,18 +1331,21 @@ impl GameScene {
                     continue;
                 }
 
+                let damage = bullet.hit_damage(&state.constants);
+
                 if npc.npc_flags.shootable() {
-                    npc.life = (npc.life as i32).saturating_sub(bullet.damage as i32).clamp(0, u16::MAX as i32) as u16;
+                    npc.life = (npc.life as i32).saturating_sub(damage as i32).clamp(0, u16::MAX as i32) as u16;
 
                     if npc.life == 0 {
                         if npc.npc_flags.show_damage() {
-                            npc.popup.add_value(-bullet.damage);
+                            npc.popup.add_value(-damage);
                         }
 
                         if self.player1.cond.alive() && npc.npc_flags.event_when_killed() {
                             state.control_flags.set_tick_world(true);
                             state.control_flags.set_interactions_disabled(true);
                             state.textscript_vm.start_script(npc.event_num);
+                            state.textscript_vm.executor_npc = Some(npc.id);
                         } else {
                             npc.cond.set_explode_die(true);
                         }
@@ -1197,7 +1368,7 @@ impl GameScene {
                         }
 
                         if npc.npc_flags.show_damage() {
-                            npc.popup.add_value_throttled(-bullet.damage);
+                            npc.popup.add_value_throttled(-damage);
                         }
                     }
                 } else if !bullet.weapon_flags.no_proj_dissipation()
@@ -1260,16 +1431,18 @@ impl GameScene {
                     continue;
                 }
 
+                let damage = bullet.hit_damage(&state.constants);
+
                 if npc.npc_flags.shootable() {
                     if npc.cond.damage_boss() {
                         idx = 0;
                         npc = unsafe { self.boss.parts.get_unchecked_mut(0) };
                     }
 
-                    npc.life = (npc.life as i32).saturating_sub(bullet.damage as i32).clamp(0, u16::MAX as i32) as u16;
+                    npc.life = (npc.life as i32).saturating_sub(damage as i32).clamp(0, u16::MAX as i32) as u16;
 
                     if npc.npc_flags.show_damage() {
-                        npc.popup.add_value(-bullet.damage);
+                        npc.popup.add_value(-damage);
                     }
 
                     if npc.life == 0 {
@@ -1325,6 +1498,7 @@ impl GameScene {
     }
 
     fn tick_world(&mut self, state: &mut SharedGameState) -> GameResult {
+        state.play_counter = state.play_counter.wrapping_add(1);
         self.nikumaru.tick(state, &self.player1)?;
         self.background.tick()?;
         self.hud_player1.visible = self.player1.cond.alive();
@@ -1348,6 +1522,15 @@ impl GameScene {
         };
         self.player1.tick(state, &self.npc_list)?;
         self.player2.tick(state, &self.npc_list)?;
+
+        if self.player2.respawn_counter > 0 {
+            self.player2.respawn_counter -= 1;
+
+            if self.player2.respawn_counter == 0 && self.player1.cond.alive() && state.player_count == PlayerCount::Two
+            {
+                self.respawn_player2(state);
+            }
+        }
         state.textscript_vm.reset_invicibility = false;
 
         self.whimsical_star.tick(state, (&self.player1, &mut self.bullet_manager))?;
@@ -1455,6 +1638,7 @@ impl GameScene {
         }
 
         self.bullet_manager.tick_bullets(state, [&self.player1, &self.player2], &self.npc_list);
+        self.ambient_emitters.tick(state, &self.frame)?;
         state.tick_carets();
 
         match self.frame.update_target {
@@ -1468,8 +1652,9 @@ impl GameScene {
                     self.frame.target_x = (self.player1.target_x * 2 + self.player2.target_x) / 3;
                     self.frame.target_y = (self.player1.target_y * 2 + self.player2.target_y) / 3;
 
+                    // keep player 1 near the center of the screen on both axes
                     self.frame.target_x = self.frame.target_x.clamp(self.player1.x - 0x8000, self.player1.x + 0x8000);
-                    self.frame.target_y = self.frame.target_y.clamp(self.player1.y, self.player1.y);
+                    self.frame.target_y = self.frame.target_y.clamp(self.player1.y - 0x8000, self.player1.y + 0x8000);
                 } else {
                     self.frame.target_x = self.player1.target_x;
                     self.frame.target_y = self.player1.target_y;
@@ -1519,8 +1704,35 @@ impl GameScene {
 
         self.tilemap.tick()?;
 
+        let look_direction = if state.settings.camera_look_shift
+            && self.frame.update_target == UpdateTarget::Player
+            && state.control_flags.control_enabled()
+            && self.player1.control_mode == ControlMode::Normal
+            && self.player1.flags.hit_bottom_wall()
+            && !self.player1.controller.move_left()
+            && !self.player1.controller.move_right()
+        {
+            match (self.player1.controller.move_up(), self.player1.controller.move_down()) {
+                (true, false) => -1,
+                (false, true) => 1,
+                _ => 0,
+            }
+        } else {
+            0
+        };
+        self.frame.tick_look(look_direction);
+
         self.frame.update(state, &self.stage);
 
+        let half_width = state.canvas_size.0 as i32 * 0x100;
+        let half_height = state.canvas_size.1 as i32 * 0x100;
+        state.sound_manager.set_listener_position(
+            self.frame.x + half_width,
+            self.frame.y + half_height,
+            half_width,
+            half_height,
+        );
+
         if state.control_flags.control_enabled() {
             self.hud_player1.tick(state, (&self.player1, &mut self.inventory_player1))?;
             self.hud_player2.tick(state, (&self.player2, &mut self.inventory_player2))?;
@@ -1548,6 +1760,8 @@ impl GameScene {
             self.map_name_counter -= 1;
         }
 
+        self.toast.tick(state, ())?;
+
         Ok(())
     }
 
@@ -1568,16 +1782,8 @@ impl GameScene {
         }
 
         {
-            let hit_rect_size = entity.hit_rect_size().clamp(1, 4);
-            let hit_rect_size = if state.tile_size == TileSize::Tile8x8 {
-                4 * hit_rect_size * hit_rect_size
-            } else {
-                hit_rect_size * hit_rect_size
-            };
-
+            let (x, y, hit_rect_size) = entity.collision_tiles(state.tile_size);
             let tile_size = state.tile_size.as_int() * 0x200;
-            let x = (entity.x() + entity.offset_x()) / tile_size;
-            let y = (entity.y() + entity.offset_y()) / tile_size;
 
             let batch = state.texture_set.get_or_load_batch(ctx, &state.constants, "Caret")?;
 
@@ -1633,7 +1839,49 @@ impl GameScene {
             self.draw_debug_npc(boss, state, ctx)?;
         }
 
-        self.draw_debug_object(&self.player1, state, ctx)?;
+        self.draw_debug_player(&self.player1, state, ctx)?;
+        self.draw_debug_player(&self.player2, state, ctx)?;
+
+        Ok(())
+    }
+
+    /// Draws the player's hit rectangle with its head, feet and side points, and outlines the tiles map collisions
+    /// are tested against.
+    fn draw_debug_player(&self, player: &Player, state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
+        if !player.cond.alive() || player.cond.hidden() {
+            return Ok(());
+        }
+
+        self.draw_debug_object(player, state, ctx)?;
+
+        let to_screen = |x: i32, y: i32| {
+            (
+                ((x - self.frame.x) as f32 / 512.0 * state.scale) as isize,
+                ((y - self.frame.y) as f32 / 512.0 * state.scale) as isize,
+            )
+        };
+
+        let bounds = &player.hit_bounds;
+        let (left, top) = to_screen(player.x - bounds.left as i32, player.y - bounds.top as i32);
+        let (right, bottom) = to_screen(player.x + bounds.right as i32, player.y + bounds.bottom as i32);
+        graphics::draw_outline_rect(ctx, Rect::new(left, top, right, bottom), 1, Color::from_rgba(0, 255, 0, 200))?;
+
+        // the same tiles `tick_map_collisions` tests, starting from the offset position
+        let tile_size = state.tile_size.as_int() * 0x200;
+        let half_tile = tile_size / 2;
+        let (tile_x, tile_y, count) = player.collision_tiles(state.tile_size);
+
+        for &(ox, oy) in OFFSETS.iter().take(count) {
+            let (center_x, center_y) = ((tile_x + ox) * tile_size, (tile_y + oy) * tile_size);
+            let (left, top) = to_screen(center_x - half_tile, center_y - half_tile);
+            let (right, bottom) = to_screen(center_x + half_tile, center_y + half_tile);
+            graphics::draw_outline_rect(ctx, Rect::new(left, top, right, bottom), 1, Color::from_rgba(255, 0, 0, 160))?;
+        }
+
+        for (x, y) in player.probe_points() {
+            let (x, y) = to_screen(x, y);
+            graphics::draw_rect(ctx, Rect::new(x - 1, y - 1, x + 2, y + 2), Color::from_rgb(255, 0, 0))?;
+        }
 
         Ok(())
     }
@@ -1657,19 +1905,28 @@ impl Scene for GameScene {
         }
 
         self.npc_list.set_rng_seed(state.game_rng.next());
+        self.npc_list.set_spawn_limits(&state.constants.npc_spawn_limits);
         self.boss.init_rng(state.game_rng.next());
         state.textscript_vm.set_scene_script(self.stage.load_text_script(
             &state.constants.base_paths,
             &state.constants,
             ctx,
         )?);
-        state.textscript_vm.suspend = false;
         state.tile_size = self.stage.map.tile_size;
         #[cfg(feature = "scripting-lua")]
         state.lua.set_game_scene(self as *mut _);
 
         self.player1.controller = state.settings.create_player1_controller();
         self.player2.controller = state.settings.create_player2_controller();
+        self.frame_held_controller = state.settings.create_player1_controller();
+
+        if self.stage.data.refill_on_entry {
+            // same as running <LI+ with the max life and <AE+
+            self.player1.life = self.player1.max_life;
+            self.player2.life = self.player2.max_life;
+            self.inventory_player1.refill_all_ammo();
+            self.inventory_player2.refill_all_ammo();
+        }
 
         let npcs = self.stage.load_npcs(&state.constants.base_paths, ctx)?;
         for npc_data in npcs.iter() {
@@ -1692,6 +1949,7 @@ impl Scene for GameScene {
         }
 
         state.npc_table.stage_textures = self.stage_textures.clone();
+        state.texture_set.set_stage_textures(&self.stage_textures.borrow().names());
 
         self.boss.boss_type = self.stage.data.boss_no as u16;
         self.player1.target_x = self.player1.x;
@@ -1737,12 +1995,26 @@ impl Scene for GameScene {
         };
 
         self.pause_menu.init(state, ctx)?;
+        // gamepads unplugged before the scene started don't matter anymore
+        gamepad::take_disconnected_gamepads(ctx);
         self.whimsical_star.init(&self.player1);
 
+        // the event queued by <TRA can only start once everything in the new stage is in place
+        state.textscript_vm.suspend = false;
+
         Ok(())
     }
 
     fn tick(&mut self, state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
+        if state.frame_advance && !self.pause_menu.is_paused() {
+            if !state.frame_step {
+                state.frame_held = true;
+                return self.tick_frame_held(state, ctx);
+            }
+
+            state.frame_step = false;
+        }
+
         if !self.pause_menu.is_paused() {
             if let ReplayState::Playback(_) = state.replay_state {
                 self.replay.tick(state, (ctx, &mut self.player1))?;
@@ -1777,21 +2049,25 @@ impl Scene for GameScene {
         if self.intro_mode {
             state.touch_controls.control_type = TouchControlType::Dialog;
 
-            if let TextScriptExecutionState::WaitTicks(_, _, 9999) = state.textscript_vm.state {
-                state.next_scene = Some(Box::new(TitleScene::new()));
-            }
-
-            if self.player1.controller.trigger_menu_ok() {
-                state.next_scene = Some(Box::new(TitleScene::new()));
+            let intro_over = matches!(state.textscript_vm.state, TextScriptExecutionState::WaitTicks(_, _, 9999));
+            if intro_over || self.player1.controller.trigger_menu_ok() {
+                state.defer(DeferredAction::ChangeScene(Box::new(TitleScene::new())));
             }
         }
 
-        if self.player1.controller.trigger_menu_pause() {
+        let lost_gamepad = self.lost_player_gamepad(state, ctx);
+        if self.player1.controller.trigger_menu_pause() || (lost_gamepad && !self.pause_menu.is_paused()) {
             self.pause_menu.pause(state);
         }
 
         if self.pause_menu.is_paused() {
             self.pause_menu.tick(state, ctx)?;
+
+            // leaving through the pause menu mid-fade would otherwise hold the scene switch forever
+            if state.next_scene.is_some() {
+                self.fade.tick(state, ())?;
+            }
+
             return Ok(());
         }
 
@@ -1799,6 +2075,8 @@ impl Scene for GameScene {
             self.replay.tick(state, (ctx, &mut self.player1))?;
         }
 
+        self.tick_quicksave(state, ctx)?;
+
         match state.textscript_vm.state {
             TextScriptExecutionState::Running(_, _)
             | TextScriptExecutionState::WaitTicks(_, _, _)
@@ -2163,6 +2441,8 @@ impl Scene for GameScene {
             self.credits.draw(state, ctx, &self.frame)?;
         }
 
+        self.toast.draw(state, ctx, &self.frame)?;
+
         self.falling_island.draw(state, ctx, &self.frame)?;
         self.text_boxes.draw(state, ctx, &self.frame)?;
 
@@ -2271,6 +2551,17 @@ impl Scene for GameScene {
                 .draw(debug_name, ctx, &state.constants, &mut state.texture_set)?;
         }
 
+        if state.frame_advance {
+            let debug_name = "FRAME ADVANCE";
+            state
+                .font
+                .builder()
+                .x(state.canvas_size.0 - state.font.builder().compute_width(debug_name) - 10.0)
+                .y(68.0)
+                .shadow(true)
+                .draw(debug_name, ctx, &state.constants, &mut state.texture_set)?;
+        }
+
         self.replay.draw(state, ctx, &self.frame)?;
 
         self.pause_menu.draw(state, ctx)?;
@@ -2302,12 +2593,37 @@ impl Scene for GameScene {
             return Ok(());
         }
 
+        // quicksave keys take precedence over the debug hotkeys bound to the same keys
+        if key_code == state.settings.quicksave_key || key_code == state.settings.quickload_key {
+            return Ok(());
+        }
+
         #[cfg(not(debug_assertions))]
         if !state.settings.debug_mode {
             return Ok(());
         }
 
+        if ctx.keyboard_context.active_mods().ctrl() {
+            let speed = match key_code {
+                ScanCode::Key1 => Some(0.25),
+                ScanCode::Key2 => Some(0.5),
+                ScanCode::Key3 => Some(2.0),
+                ScanCode::Key4 => Some(4.0),
+                _ => None,
+            };
+
+            if let Some(speed) = speed {
+                state.set_speed(speed);
+                return Ok(());
+            }
+        }
+
         match key_code {
+            ScanCode::Pause => {
+                state.frame_advance = !state.frame_advance;
+                state.frame_step = false;
+            }
+            ScanCode::Backslash if state.frame_advance => state.frame_step = true,
             ScanCode::F3 => state.settings.god_mode = !state.settings.god_mode,
             ScanCode::F4 => state.settings.infinite_booster = !state.settings.infinite_booster,
             ScanCode::F5 => state.settings.subpixel_coords = !state.settings.subpixel_coords,
@@ -2319,7 +2635,7 @@ impl Scene for GameScene {
                 }
             }
             ScanCode::F9 => {
-                if state.settings.speed < 3.0 {
+                if state.settings.speed < 4.0 {
                     state.set_speed(state.settings.speed + 0.1);
                 }
             }
@@ -2333,3 +2649,220 @@ impl Scene for GameScene {
         Ok(())
     }
 }
+
+#[test]
+fn test_piercing_bullet() {
+    use crate::game::HeadlessRunner;
+    use crate::game::weapon::bullet::Bullet;
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+
+    let stage = Stage::blank(20, 15);
+    state.stages.push(stage.data.clone());
+    let mut scene = GameScene::from_stage(&mut state, &mut ctx, stage, 0).unwrap();
+
+    // four enemies lined up two tiles apart
+    let tile_size = 16 * 0x200;
+    let mut enemy = NPC::empty();
+    enemy.cond.set_alive(true);
+    enemy.npc_flags.set_shootable(true);
+    enemy.hit_bounds = Rect::new(0x1000, 0x1000, 0x1000, 0x1000);
+    enemy.life = 100;
+    enemy.y = 5 * tile_size;
+    for i in 0..4 {
+        enemy.x = (4 + i * 2) * tile_size;
+        scene.npc_list.spawn(0x100, enemy.clone()).unwrap();
+    }
+
+    // Polar Star level 1 made to pierce through 3 enemies, like `"pierce": 3` in bullets.json
+    state.constants.weapon.bullet_table[4].life = 3;
+    let bullet = Bullet::new(4 * tile_size, 5 * tile_size, 4, TargetPlayer::Player1, Direction::Right, &state.constants);
+    scene.bullet_manager.push_bullet(bullet);
+
+    for i in 0..4 {
+        if let Some(bullet) = scene.bullet_manager.bullets.first_mut() {
+            bullet.x = (4 + i * 2) * tile_size;
+        }
+
+        scene.tick_npc_bullet_collissions(&mut state);
+        scene.bullet_manager.tick_bullets(&mut state, [&scene.player1, &scene.player2], &scene.npc_list);
+    }
+
+    assert!(scene.bullet_manager.bullets.is_empty());
+    let lives: Vec<u16> = scene.npc_list.iter_alive().map(|npc| npc.life).collect();
+    assert_eq!(lives, vec![99, 99, 99, 100]);
+}
+
+#[test]
+fn test_frame_advance() {
+    use crate::game::HeadlessRunner;
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+
+    let stage = Stage::blank(20, 15);
+    state.stages.push(stage.data.clone());
+    let mut scene = GameScene::from_stage(&mut state, &mut ctx, stage, 0).unwrap();
+    state.control_flags.set_tick_world(true);
+
+    scene.player1.cond.set_alive(true);
+    scene.player1.x = 10 * 16 * 0x200;
+    scene.player1.y = 5 * 16 * 0x200;
+
+    state.frame_advance = true;
+
+    // held ticks leave the world where it was
+    for _ in 0..3 {
+        scene.tick(&mut state, &mut ctx).unwrap();
+        assert!(std::mem::take(&mut state.frame_held));
+    }
+    assert_eq!(scene.tick, 0);
+    assert_eq!((scene.player1.x, scene.player1.y), (10 * 16 * 0x200, 5 * 16 * 0x200));
+
+    // a step runs exactly one tick before holding again
+    state.frame_step = true;
+    scene.tick(&mut state, &mut ctx).unwrap();
+    assert!(!std::mem::take(&mut state.frame_held));
+    assert!(!state.frame_step);
+    assert_eq!(scene.tick, 1);
+    assert_ne!(scene.player1.y, 5 * 16 * 0x200);
+
+    scene.tick(&mut state, &mut ctx).unwrap();
+    assert!(state.frame_held);
+    assert_eq!(scene.tick, 1);
+}
+
+#[test]
+fn test_tra_event_waits_for_new_stage() {
+    use downcast::Downcast;
+
+    use crate::framework::filesystem::TempDataDir;
+    use crate::game::scripting::tsc::text_script::{TextScript, TextScriptEncoding};
+    use crate::game::HeadlessRunner;
+
+    let mut pxm = b"PXM\x10".to_vec();
+    pxm.extend_from_slice(&[3, 0, 2, 0]);
+    pxm.extend_from_slice(&[0; 6]);
+
+    // a single NPC of type 60, the entry event only sets flag 1 if it's already there
+    let mut pxe = b"PXE\x00".to_vec();
+    pxe.extend_from_slice(&1u32.to_le_bytes());
+    for value in [1u16, 1, 0, 0, 60, 0] {
+        pxe.extend_from_slice(&value.to_le_bytes());
+    }
+
+    let dir = TempDataDir::new("tra-event-order");
+    dir.write("Stage/Room.pxm", &pxm);
+    dir.write("Stage/Room.pxe", &pxe);
+    dir.write("Stage/Room.tsc", b"#0200\r\n<NCJ0060:0201<END\r\n#0201\r\n<FL+0001<END\r\n");
+    dir.write("Stage/0.pxa", &[0; 0x100]);
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    dir.mount(&mut ctx);
+    state.constants.base_paths = vec!["/".to_owned()];
+    state.constants.textscript.encrypted = false;
+
+    let door = Stage::blank(3, 2);
+    let mut room = Stage::blank(3, 2);
+    room.data.map = "Room".to_owned();
+    state.stages = vec![door.data.clone(), room.data];
+    let mut scene = GameScene::from_stage(&mut state, &mut ctx, door, 0).unwrap();
+    // started by hand, without going through `init`
+    state.textscript_vm.suspend = false;
+    let script = TextScript::compile(b"#0100\r\n<TRA0001:0200:0001:0001\r\n", true, TextScriptEncoding::UTF8).unwrap();
+    state.textscript_vm.set_scene_script(script);
+
+    state.textscript_vm.start_script(100);
+    TextScriptVM::run(&mut state, &mut scene, &mut ctx).unwrap();
+
+    let mut next_scene = state.next_scene.take().unwrap();
+    let room = next_scene.downcast_mut::<GameScene>().ok().unwrap();
+
+    // the entry event doesn't run before the room is set up
+    TextScriptVM::run(&mut state, room, &mut ctx).unwrap();
+    assert_eq!(state.textscript_vm.state, TextScriptExecutionState::Running(200, 0));
+
+    room.init(&mut state, &mut ctx).unwrap();
+    TextScriptVM::run(&mut state, room, &mut ctx).unwrap();
+    assert!(state.get_flag(1));
+}
+
+#[test]
+fn test_npc_draw_batching() {
+    use crate::game::HeadlessRunner;
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+
+    let stage = Stage::blank(20, 15);
+    state.stages.push(stage.data.clone());
+    let mut scene = GameScene::from_stage(&mut state, &mut ctx, stage, 0).unwrap();
+
+    // two runs of NPCs sharing a spritesheet, all of them on screen
+    for i in 0..48 {
+        let mut npc = NPC::empty();
+        npc.cond.set_alive(true);
+        npc.layer = NPCLayer::Middleground;
+        npc.spritesheet_id = if i < 30 { 20 } else { 21 };
+        npc.x = (16 + (i % 12) * 16) * 0x200;
+        npc.y = (16 + (i / 12) * 16) * 0x200;
+        scene.npc_list.spawn(0x100, npc).unwrap();
+    }
+
+    // one submitted batch per NPC when they're drawn one by one
+    ctx.batch_draws = 0;
+    for npc in scene.npc_list.iter_alive() {
+        npc.draw(&mut state, &mut ctx, &scene.frame).unwrap();
+    }
+    assert_eq!(ctx.batch_draws, 48);
+
+    // one per run of the same spritesheet when batched
+    ctx.batch_draws = 0;
+    scene.draw_npc_layer(&mut state, &mut ctx, NPCLayer::Middleground).unwrap();
+    assert_eq!(ctx.batch_draws, 2);
+
+    // NPCs on other layers don't add any draws
+    ctx.batch_draws = 0;
+    scene.draw_npc_layer(&mut state, &mut ctx, NPCLayer::Foreground).unwrap();
+    assert_eq!(ctx.batch_draws, 0);
+}
+
+#[test]
+fn test_refill_on_entry() {
+    use crate::framework::filesystem::TempDataDir;
+    use crate::game::weapon::WeaponLevel;
+    use crate::game::HeadlessRunner;
+
+    let mut pxe = b"PXE\x00".to_vec();
+    pxe.extend_from_slice(&0u32.to_le_bytes());
+
+    let dir = TempDataDir::new("refill-on-entry");
+    for name in ["Arena", "Plain"] {
+        dir.write(&format!("Stage/{}.pxe", name), &pxe);
+        dir.write(&format!("Stage/{}.tsc", name), b"#0100\r\n<END\r\n");
+    }
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+    dir.mount(&mut ctx);
+    state.constants.base_paths = vec!["/".to_owned()];
+    state.constants.textscript.encrypted = false;
+
+    for (name, refill_on_entry) in [("Arena", true), ("Plain", false)] {
+        let mut stage = Stage::blank(3, 2);
+        stage.data.map = name.to_owned();
+        stage.data.refill_on_entry = refill_on_entry;
+        state.stages = vec![stage.data.clone()];
+
+        let mut scene = GameScene::from_stage(&mut state, &mut ctx, stage, 0).unwrap();
+        scene.player1.max_life = 10;
+        scene.player1.life = 3;
+        scene.inventory_player1.add_weapon_data(WeaponType::MissileLauncher, 2, 10, 0, WeaponLevel::Level1);
+
+        scene.init(&mut state, &mut ctx).unwrap();
+
+        let ammo = scene.inventory_player1.get_current_weapon().unwrap().ammo;
+        if refill_on_entry {
+            assert_eq!((scene.player1.life, ammo), (10, 10));
+        } else {
+            assert_eq!((scene.player1.life, ammo), (3, 2));
+        }
+    }
+}
@@ -9,16 +9,23 @@ use crate::framework::filesystem;
 use crate::game::frame::Frame;
 use crate::game::map::Map;
 use crate::game::settings::ControllerType;
-use crate::game::shared_game_state::{SharedGameState, TileSize};
+use crate::game::shared_game_state::{DeferredAction, SharedGameState, TileSize};
 use crate::game::stage::{BackgroundType, NpcType, Stage, StageData, StageTexturePaths, Tileset};
 use crate::graphics::font::Font;
 use crate::input::combined_menu_controller::CombinedMenuController;
 use crate::scene::title_scene::TitleScene;
 use crate::scene::Scene;
 
+/// How long the current song fades out for before the newly selected one starts, in ticks.
+const SONG_SWITCH_FADE_TICKS: u16 = 25;
+
 pub struct JukeboxScene {
     selected_song: u16,
     song_list: Vec<String>,
+    /// Whether each entry of `song_list` can be played with the selected soundtrack.
+    song_available: Vec<bool>,
+    /// Index of the song waiting for the previous one to fade out and the remaining ticks.
+    pending_song: Option<(usize, u16)>,
     soundtracks: Vec<String>,
     selected_soundtrack: usize,
     controller: CombinedMenuController,
@@ -45,6 +52,9 @@ impl JukeboxScene {
                 background_color: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 },
                 npc1: NpcType::new("0"),
                 npc2: NpcType::new("0"),
+                refill_on_entry: false,
+                music: None,
+                music_area: None,
             },
         };
 
@@ -54,6 +64,8 @@ impl JukeboxScene {
         JukeboxScene {
             selected_song: 0,
             song_list: Vec::new(),
+            song_available: Vec::new(),
+            pending_song: None,
             soundtracks: Vec::new(),
             selected_soundtrack: 0,
             controller: CombinedMenuController::new(),
@@ -64,6 +76,36 @@ impl JukeboxScene {
             previous_pause_on_focus_loss_setting: true,
         }
     }
+
+    fn song_id(&self, state: &SharedGameState, index: usize) -> usize {
+        state.constants.music_table.iter().position(|song| song == &self.song_list[index]).unwrap_or(0)
+    }
+
+    fn update_song_availability(&mut self, state: &SharedGameState, ctx: &Context) {
+        self.song_available = (0..self.song_list.len())
+            .map(|i| state.sound_manager.song_exists(self.song_id(state, i), &state.constants, &state.settings, ctx))
+            .collect();
+    }
+
+    fn play_song(&mut self, state: &mut SharedGameState, ctx: &mut Context, index: usize) -> GameResult {
+        let song_id = self.song_id(state, index);
+        state.sound_manager.play_song(song_id, &state.constants, &state.settings, ctx)?;
+
+        // the files exist, but couldn't be loaded
+        if state.sound_manager.current_song() != song_id {
+            self.song_available[index] = false;
+        }
+
+        Ok(())
+    }
+
+    fn switch_soundtrack(&mut self, state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
+        state.settings.soundtrack = self.soundtracks[self.selected_soundtrack].to_string();
+        state.sound_manager.reload_songs(&state.constants, &state.settings, ctx)?;
+        self.update_song_availability(state, ctx);
+
+        Ok(())
+    }
 }
 
 impl Scene for JukeboxScene {
@@ -104,6 +146,8 @@ impl Scene for JukeboxScene {
         self.previous_pause_on_focus_loss_setting = state.settings.pause_on_focus_loss;
         state.settings.pause_on_focus_loss = false;
 
+        self.update_song_availability(state, ctx);
+
         Ok(())
     }
 
@@ -134,32 +178,44 @@ impl Scene for JukeboxScene {
 
         self.selected_song = song as u16;
 
-        if self.controller.trigger_ok() {
-            let song_id = state
-                .constants
-                .music_table
-                .iter()
-                .position(|song_comp| song_comp == &self.song_list[song as usize])
-                .unwrap_or(0);
+        if self.controller.trigger_ok() && self.song_available[song as usize] {
+            let song_id = self.song_id(state, song as usize);
+
+            if state.sound_manager.current_song() == song_id {
+                // selecting the song that's playing stops it
+                self.pending_song = None;
+                state.sound_manager.fade_out_music(SONG_SWITCH_FADE_TICKS, state.current_tps());
+            } else if state.sound_manager.current_song() == 0 {
+                self.pending_song = None;
+                self.play_song(state, ctx, song as usize)?;
+            } else {
+                self.pending_song = Some((song as usize, SONG_SWITCH_FADE_TICKS));
+                state.sound_manager.fade_out_music(SONG_SWITCH_FADE_TICKS, state.current_tps());
+            }
+        }
 
-            state.sound_manager.play_song(song_id, &state.constants, &state.settings, ctx)?;
+        if let Some((index, ticks)) = self.pending_song {
+            if ticks == 0 {
+                self.pending_song = None;
+                self.play_song(state, ctx, index)?;
+            } else {
+                self.pending_song = Some((index, ticks - 1));
+            }
         }
 
         if self.controller.trigger_shift_left() {
             self.selected_soundtrack = self.selected_soundtrack.checked_sub(1).unwrap_or(self.soundtracks.len() - 1);
-            state.settings.soundtrack = self.soundtracks[self.selected_soundtrack].to_string();
-            state.sound_manager.reload_songs(&state.constants, &state.settings, ctx)?;
+            self.switch_soundtrack(state, ctx)?;
         }
 
         if self.controller.trigger_shift_right() {
             self.selected_soundtrack = (self.selected_soundtrack + 1) % self.soundtracks.len();
-            state.settings.soundtrack = self.soundtracks[self.selected_soundtrack].to_string();
-            state.sound_manager.reload_songs(&state.constants, &state.settings, ctx)?;
+            self.switch_soundtrack(state, ctx)?;
         }
 
         if self.controller.trigger_back() {
             state.settings.pause_on_focus_loss = self.previous_pause_on_focus_loss_setting;
-            state.next_scene = Some(Box::new(TitleScene::new()));
+            state.defer(DeferredAction::ChangeScene(Box::new(TitleScene::new())));
         }
 
         // todo Touch controls
@@ -184,6 +240,12 @@ impl Scene for JukeboxScene {
             rect
         }
 
+        let current_song = state.sound_manager.current_song();
+        let playing_song = match self.pending_song {
+            Some((index, _)) => Some(index),
+            None => (0..num_songs).find(|&i| current_song != 0 && self.song_id(state, i) == current_song),
+        };
+
         // Draw Song Boxes
         let batch = state.texture_set.get_or_load_batch(ctx, &state.constants, "uimusic")?;
 
@@ -193,9 +255,17 @@ impl Scene for JukeboxScene {
             rect.right = rect.left + 24;
             rect.bottom = rect.top + 24;
 
-            batch.add_rect(
+            // songs that can't be played are greyed out
+            let color = if self.song_available.get(iter).copied().unwrap_or(true) {
+                (255, 255, 255, 255)
+            } else {
+                (80, 80, 80, 255)
+            };
+
+            batch.add_rect_tinted(
                 init_x + (iter % 8) as f32 * (block_size + buffer),
                 init_y + (iter / 8) as f32 * (block_size + buffer),
+                color,
                 &rect,
             );
         }
@@ -212,25 +282,30 @@ impl Scene for JukeboxScene {
             let bottom = top + block_size - buffer;
 
             let selected_offset = if iter == self.selected_song as usize { 16 } else { 0 };
+            let color = if playing_song == Some(iter) { (255, 208, 64, 255) } else { (255, 255, 255, 255) };
 
-            batch.add_rect(
+            batch.add_rect_tinted(
                 init_x + left,
                 init_y + top,
+                color,
                 &selected(state.constants.title.menu_left_top, selected_offset),
             );
-            batch.add_rect(
+            batch.add_rect_tinted(
                 init_x + right,
                 init_y + top,
+                color,
                 &selected(state.constants.title.menu_right_top, selected_offset),
             );
-            batch.add_rect(
+            batch.add_rect_tinted(
                 init_x + left,
                 init_y + bottom,
+                color,
                 &selected(state.constants.title.menu_left_bottom, selected_offset),
             );
-            batch.add_rect(
+            batch.add_rect_tinted(
                 init_x + right,
                 init_y + bottom,
+                color,
                 &selected(state.constants.title.menu_right_bottom, selected_offset),
             );
 
@@ -252,8 +327,13 @@ impl Scene for JukeboxScene {
                 };
                 rect2.right = rect.right;
 
-                batch.add_rect(x, y - rect.height() as f32, &selected(rect, selected_offset));
-                batch.add_rect(x, y + block_size - buffer - rect.height() as f32, &selected(rect2, selected_offset));
+                batch.add_rect_tinted(x, y - rect.height() as f32, color, &selected(rect, selected_offset));
+                batch.add_rect_tinted(
+                    x,
+                    y + block_size - buffer - rect.height() as f32,
+                    color,
+                    &selected(rect2, selected_offset),
+                );
                 x += rect.width() as f32;
             }
 
@@ -271,8 +351,8 @@ impl Scene for JukeboxScene {
                 };
                 rect2.bottom = rect.bottom;
 
-                batch.add_rect(x, y, &selected(rect, selected_offset));
-                batch.add_rect(x + block_size - buffer, y, &selected(rect2, selected_offset));
+                batch.add_rect_tinted(x, y, color, &selected(rect, selected_offset));
+                batch.add_rect_tinted(x + block_size - buffer, y, color, &selected(rect2, selected_offset));
                 y += rect.height() as f32;
             }
         }
@@ -1,17 +1,25 @@
+use crate::common::Rect;
 use crate::framework::context::Context;
 use crate::framework::error::GameResult;
+use crate::framework::gamepad;
 use crate::framework::graphics;
 use crate::game::shared_game_state::SharedGameState;
 use crate::scene::no_data_scene::NoDataScene;
 use crate::scene::Scene;
 
+/// How long the boot splash stays on screen after loading, in ticks.
+const SPLASH_DURATION: usize = 100;
+const SPLASH_FADE: usize = 25;
+
 pub struct LoadingScene {
     tick: usize,
+    /// Scene queued by the loader, held back until the boot splash is over.
+    pending_scene: Option<Box<dyn Scene>>,
 }
 
 impl LoadingScene {
     pub fn new() -> Self {
-        Self { tick: 0 }
+        Self { tick: 0, pending_scene: None }
     }
 
     fn load_stuff(&mut self, state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
@@ -36,6 +44,18 @@ impl Scene for LoadingScene {
                 log::error!("Failed to load game data: {}", err);
 
                 state.next_scene = Some(Box::new(NoDataScene::new(err)));
+            } else if state.settings.boot_splash && !ctx.headless {
+                self.pending_scene = state.next_scene.take();
+            }
+        } else if self.pending_scene.is_some() {
+            let screen = Rect::new(0, 0, state.canvas_size.0 as isize, state.canvas_size.1 as isize);
+            let skipped = !ctx.keyboard_context.pressed_keys().is_empty()
+                || (0..gamepad::get_gamepads(ctx).len() as u32)
+                    .any(|idx| !gamepad::pressed_buttons(ctx, idx).is_empty())
+                || state.touch_controls.consume_click_in(screen);
+
+            if self.tick > SPLASH_DURATION || skipped {
+                state.next_scene = self.pending_scene.take();
             }
         }
 
@@ -46,6 +66,23 @@ impl Scene for LoadingScene {
     fn draw(&self, state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
         graphics::set_vsync_mode(ctx, state.settings.vsync_mode)?;
 
+        if self.pending_scene.is_some() {
+            let ticks_left = SPLASH_DURATION.saturating_sub(self.tick);
+            let alpha = (self.tick.min(ticks_left).min(SPLASH_FADE) * 255 / SPLASH_FADE) as u8;
+
+            let logo_rect = state.constants.title.logo_rect;
+            let batch = state.texture_set.get_or_load_batch(ctx, &state.constants, "Title")?;
+            batch.add_rect_tinted(
+                ((state.canvas_size.0 - logo_rect.width() as f32) / 2.0).floor(),
+                ((state.canvas_size.1 - logo_rect.height() as f32) / 2.0).floor(),
+                (255, 255, 255, alpha),
+                &logo_rect,
+            );
+            batch.draw(ctx)?;
+
+            return Ok(());
+        }
+
         match state.texture_set.get_or_load_batch(ctx, &state.constants, "Loading") {
             Ok(batch) => {
                 batch.add(
@@ -64,3 +101,38 @@ impl Scene for LoadingScene {
         Ok(())
     }
 }
+
+#[test]
+fn test_boot_splash_skip() {
+    use downcast::Downcast;
+
+    use crate::framework::keyboard::ScanCode;
+    use crate::game::HeadlessRunner;
+    use crate::scene::title_scene::TitleScene;
+
+    let (mut ctx, mut state) = HeadlessRunner::bare_state();
+
+    // the splash is never shown headless, so start from where it holds back the loaded title screen
+    let mut scene = LoadingScene { tick: 2, pending_scene: Some(Box::new(TitleScene::new())) };
+
+    for _ in 0..10 {
+        scene.tick(&mut state, &mut ctx).unwrap();
+    }
+    assert!(state.next_scene.is_none());
+
+    // any key goes to the title right away instead of waiting for the splash to end
+    ctx.keyboard_context.set_key(ScanCode::Space, true);
+    scene.tick(&mut state, &mut ctx).unwrap();
+    assert!(scene.tick < SPLASH_DURATION);
+    assert!(scene.pending_scene.is_none());
+    assert!(state.next_scene.take().unwrap().downcast_ref::<TitleScene>().is_ok());
+
+    // without a key press it ends on its own
+    ctx.keyboard_context.set_key(ScanCode::Space, false);
+    let mut scene = LoadingScene { tick: 2, pending_scene: Some(Box::new(TitleScene::new())) };
+    while scene.pending_scene.is_some() {
+        scene.tick(&mut state, &mut ctx).unwrap();
+    }
+    assert_eq!(scene.tick, SPLASH_DURATION + 2);
+    assert!(state.next_scene.is_some());
+}
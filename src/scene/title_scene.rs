@@ -1,6 +1,7 @@
 use crate::common::{Color, VERSION_BANNER};
 use crate::components::background::Background;
 use crate::components::nikumaru::NikumaruCounter;
+use crate::components::replay::Replay;
 use crate::entity::GameEntity;
 use crate::framework::context::Context;
 use crate::framework::error::GameResult;
@@ -108,6 +109,9 @@ impl TitleScene {
                 background_color: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 },
                 npc1: NpcType::new("0"),
                 npc2: NpcType::new("0"),
+                refill_on_entry: false,
+                music: None,
+                music_area: None,
             },
         };
         let mut textures = StageTexturePaths::new();
@@ -179,6 +183,20 @@ impl TitleScene {
         self.current_menu = CurrentMenu::OptionMenu;
         Ok(())
     }
+
+    /// Replays recorded by an incompatible build stay listed, but can't be picked.
+    fn replay_entry(state: &SharedGameState, ctx: &mut Context, kind: ReplayKind) -> MenuEntry {
+        let label = state.loc.t(match kind {
+            ReplayKind::Best => "menus.challenge_menu.replay_best",
+            ReplayKind::Last => "menus.challenge_menu.replay_last",
+        });
+
+        if Replay::can_play_back(state, ctx, kind) {
+            MenuEntry::Active(label.to_owned())
+        } else {
+            MenuEntry::Disabled(state.loc.tt("menus.challenge_menu.replay_unplayable", &[("replay", label)]))
+        }
+    }
 }
 
 static COPYRIGHT_PIXEL: &str = "2004.12  Studio Pixel";
@@ -187,6 +205,9 @@ static COPYRIGHT_NICALIS: &str = "@2022 NICALIS INC."; // Nicalis font uses @ fo
 
 impl Scene for TitleScene {
     fn init(&mut self, state: &mut SharedGameState, ctx: &mut Context) -> GameResult {
+        // whatever wasn't saved is gone by now
+        state.unsaved_progress = false;
+
         if !state.mod_path.is_none() {
             state.mod_path = None;
             state.reload_resources(ctx)?;
@@ -320,6 +341,10 @@ impl Scene for TitleScene {
                 MenuSelectionResult::Selected(MainMenuEntry::Quit, _) => {
                     state.shutdown();
                 }
+                // there's nothing to go back to, so the back button jumps to Quit instead
+                MenuSelectionResult::Canceled => {
+                    self.main_menu.selected = MainMenuEntry::Quit;
+                }
                 _ => {}
             },
             CurrentMenu::OptionMenu => {
@@ -366,10 +391,8 @@ impl Scene for TitleScene {
                             self.confirm_menu.set_entry(ConfirmMenuEntry::Title, MenuEntry::Disabled(mod_name));
 
                             if state.has_replay_data(ctx, ReplayKind::Best) {
-                                self.confirm_menu.set_entry(
-                                    ConfirmMenuEntry::Replay(ReplayKind::Best),
-                                    MenuEntry::Active(state.loc.t("menus.challenge_menu.replay_best").to_owned()),
-                                );
+                                let entry = Self::replay_entry(state, ctx, ReplayKind::Best);
+                                self.confirm_menu.set_entry(ConfirmMenuEntry::Replay(ReplayKind::Best), entry);
                                 self.confirm_menu.set_entry(
                                     ConfirmMenuEntry::DeleteReplay,
                                     MenuEntry::Active(state.loc.t("menus.challenge_menu.delete_replay").to_owned()),
@@ -381,10 +404,8 @@ impl Scene for TitleScene {
                             }
 
                             if state.has_replay_data(ctx, ReplayKind::Last) {
-                                self.confirm_menu.set_entry(
-                                    ConfirmMenuEntry::Replay(ReplayKind::Last),
-                                    MenuEntry::Active(state.loc.t("menus.challenge_menu.replay_last").to_owned()),
-                                );
+                                let entry = Self::replay_entry(state, ctx, ReplayKind::Last);
+                                self.confirm_menu.set_entry(ConfirmMenuEntry::Replay(ReplayKind::Last), entry);
                             } else {
                                 self.confirm_menu
                                     .set_entry(ConfirmMenuEntry::Replay(ReplayKind::Last), MenuEntry::Hidden);
@@ -409,10 +430,17 @@ impl Scene for TitleScene {
                     self.current_menu = CurrentMenu::PlayerCountMenu;
                 }
                 MenuSelectionResult::Selected(ConfirmMenuEntry::Replay(kind), _) => {
-                    state.difficulty = GameDifficulty::Normal;
-                    state.replay_state = ReplayState::Playback(kind);
-                    state.reload_resources(ctx)?;
-                    state.start_new_game(ctx)?;
+                    // a replay that can't be played back would otherwise drop the player into a live run
+                    if Replay::can_play_back(state, ctx, kind) {
+                        state.difficulty = GameDifficulty::Normal;
+                        state.replay_state = ReplayState::Playback(kind);
+                        state.reload_resources(ctx)?;
+                        state.start_new_game(ctx)?;
+                    } else {
+                        // replaced since the menu was opened
+                        let entry = Self::replay_entry(state, ctx, kind);
+                        self.confirm_menu.set_entry(ConfirmMenuEntry::Replay(kind), entry);
+                    }
                 }
                 MenuSelectionResult::Selected(ConfirmMenuEntry::DeleteReplay, _) => {
                     state.delete_replay_data(ctx, ReplayKind::Best)?;
@@ -1,5 +1,6 @@
 use std::io;
 use std::io::{BufRead, BufReader, Lines};
+use std::mem;
 use std::str::FromStr;
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
@@ -22,19 +23,24 @@ use crate::sound::ogg_playback::{OggPlaybackEngine, SavedOggPlaybackState};
 use crate::sound::org_playback::{OrgPlaybackEngine, SavedOrganyaPlaybackState};
 use crate::sound::organya::Song;
 use crate::sound::pixtone::{PixToneParameters, PixTonePlayback};
+use crate::sound::positional::PositionalAudio;
 use crate::sound::wave_bank::SoundBank;
 
 mod fir;
 #[cfg(feature = "ogg-playback")]
 mod ogg_playback;
 mod org_playback;
-mod organya;
+pub mod organya;
 pub mod pixtone;
 mod pixtone_sfx;
+mod positional;
 mod stuff;
 mod wav;
 mod wave_bank;
 
+/// Fraction of the music volume kept while it's ducked.
+const DUCKED_SONG_VOLUME: f32 = 0.5;
+
 pub struct SoundManager {
     soundbank: Option<SoundBank>,
     tx: Sender<PlaybackMessage>,
@@ -43,6 +49,11 @@ pub struct SoundManager {
     no_audio: bool,
     load_failed: bool,
     stream: Option<cpal::Stream>,
+    master_volume: f32,
+    song_volume: f32,
+    sfx_volume: f32,
+    song_ducked: bool,
+    positional: PositionalAudio,
 }
 
 enum SongFormat {
@@ -77,6 +88,11 @@ impl SoundManager {
                 no_audio: true,
                 load_failed: false,
                 stream: None,
+                master_volume: 1.0,
+                song_volume: 1.0,
+                sfx_volume: 1.0,
+                song_ducked: false,
+                positional: PositionalAudio::new(),
             });
         }
 
@@ -97,6 +113,11 @@ impl SoundManager {
             no_audio: false,
             load_failed: false,
             stream: None,
+            master_volume: 1.0,
+            song_volume: 1.0,
+            sfx_volume: 1.0,
+            song_ducked: false,
+            positional: PositionalAudio::new(),
         };
 
         let host = cpal::default_host();
@@ -146,8 +167,19 @@ impl SoundManager {
 
         let (tx, rx): (Sender<PlaybackMessage>, Receiver<PlaybackMessage>) = mpsc::channel();
         let soundbank = self.soundbank.take().unwrap();
+        let (master_volume, song_volume, sfx_volume) = (self.master_volume, self.song_volume, self.sfx_volume);
+        let song_ducked = self.song_ducked;
+        let positional = mem::replace(&mut self.positional, PositionalAudio::new());
         *self = SoundManager::bootstrap(&soundbank, tx, rx)?;
 
+        // the new playback thread starts at full volume, carry over the user's levels
+        self.master_volume = master_volume;
+        self.song_volume = song_volume;
+        self.sfx_volume = sfx_volume;
+        self.song_ducked = song_ducked;
+        self.positional = positional;
+        self.update_volumes();
+
         Ok(())
     }
 
@@ -186,6 +218,33 @@ impl SoundManager {
         self.send(PlaybackMessage::PlaySample(id)).unwrap();
     }
 
+    /// Plays a sound effect panned and attenuated by how far the given world position (fix9) is from the camera.
+    pub fn play_sfx_at(&mut self, id: u8, x: i32, y: i32) {
+        if self.no_audio {
+            return;
+        }
+
+        let (volume, pan) = self.positional.volume_and_pan(x, y);
+        if volume <= 0.0 {
+            return;
+        }
+
+        self.send(PlaybackMessage::PlaySamplePositioned(id, volume, pan)).unwrap();
+    }
+
+    /// Updates the camera position used by `play_sfx_at`. Coordinates are the center and half size
+    /// of the visible area in fix9 units.
+    pub fn set_listener_position(&mut self, x: i32, y: i32, half_width: i32, half_height: i32) {
+        self.positional.set_listener(x, y, half_width, half_height);
+    }
+
+    /// Enables or disables positional audio, when disabled every sound plays centered at full volume.
+    /// `range` is the distance in pixels past the screen edge at which sounds become silent.
+    pub fn set_positional_audio(&mut self, enabled: bool, range: u16) {
+        self.positional.enabled = enabled;
+        self.positional.range = range as i32 * 0x200;
+    }
+
     pub fn loop_sfx(&self, id: u8) {
         if self.no_audio {
             return;
@@ -208,25 +267,67 @@ impl SoundManager {
         self.send(PlaybackMessage::StopSample(id)).unwrap();
     }
 
-    pub fn set_org_interpolation(&mut self, interpolation: InterpolationMode) {
+    /// Stops every looping sound effect, eg. so they don't leak into a freshly loaded game.
+    pub fn stop_looping_sfx(&mut self) {
         if self.no_audio {
             return;
         }
-        self.send(PlaybackMessage::SetOrgInterpolation(interpolation)).unwrap();
+        self.send(PlaybackMessage::StopLoopingSamples).unwrap();
     }
 
-    pub fn set_song_volume(&mut self, volume: f32) {
+    pub fn set_org_interpolation(&mut self, interpolation: InterpolationMode) {
         if self.no_audio {
             return;
         }
-        self.send(PlaybackMessage::SetSongVolume(volume.powf(3.0))).unwrap();
+        self.send(PlaybackMessage::SetOrgInterpolation(interpolation)).unwrap();
+    }
+
+    /// Sets the master volume (0.0 - 1.0), which scales both the music and the sound effect volume.
+    pub fn set_master_volume(&mut self, volume: f32) {
+        self.master_volume = volume.clamp(0.0, 1.0);
+        self.update_volumes();
+    }
+
+    pub fn set_song_volume(&mut self, volume: f32) {
+        self.song_volume = volume.clamp(0.0, 1.0);
+        self.update_volumes();
     }
 
     pub fn set_sfx_volume(&mut self, volume: f32) {
+        self.sfx_volume = volume.clamp(0.0, 1.0);
+        self.update_volumes();
+    }
+
+    /// Temporarily lowers the music volume, used while the game is paused.
+    pub fn set_song_ducked(&mut self, ducked: bool) {
+        if self.song_ducked != ducked {
+            self.song_ducked = ducked;
+            self.update_volumes();
+        }
+    }
+
+    pub fn master_volume(&self) -> f32 {
+        self.master_volume
+    }
+
+    pub fn song_volume(&self) -> f32 {
+        self.song_volume
+    }
+
+    pub fn sfx_volume(&self) -> f32 {
+        self.sfx_volume
+    }
+
+    fn update_volumes(&mut self) {
         if self.no_audio {
             return;
         }
-        self.send(PlaybackMessage::SetSampleVolume(volume.powf(3.0))).unwrap();
+
+        let duck = if self.song_ducked { DUCKED_SONG_VOLUME } else { 1.0 };
+        let song_volume = (self.song_volume * self.master_volume * duck).powf(3.0);
+        let sfx_volume = (self.sfx_volume * self.master_volume).powf(3.0);
+        self.send(PlaybackMessage::SetSongVolume(song_volume)).unwrap();
+        self.send(PlaybackMessage::SetSampleVolume(sfx_volume)).unwrap();
     }
 
     pub fn set_sfx_samples(&mut self, id: u8, data: Vec<i16>) {
@@ -248,6 +349,51 @@ impl SoundManager {
         Ok(())
     }
 
+    /// Lists the files a song could be loaded from, in order of preference.
+    /// Each entry is a format and the files, all of which have to exist for it to be used.
+    fn song_candidates(
+        song_id: usize,
+        constants: &EngineConstants,
+        settings: &Settings,
+    ) -> Vec<(SongFormat, Vec<String>)> {
+        let song_name = match constants.music_table.get(song_id) {
+            Some(song_name) => song_name,
+            None => return Vec::new(),
+        };
+
+        let mut paths = constants.organya_paths.clone();
+
+        paths.insert(0, "/Soundtracks/".to_owned() + &settings.soundtrack + "/");
+
+        if let Some(soundtrack) = constants.soundtracks.iter().find(|s| s.available && s.name == settings.soundtrack) {
+            paths.insert(0, soundtrack.path.clone());
+        }
+
+        let mut candidates = Vec::new();
+
+        for prefix in paths.iter() {
+            #[cfg(feature = "ogg-playback")]
+            {
+                candidates.push((
+                    SongFormat::OggMultiPart,
+                    vec![format!("{}{}_intro.ogg", prefix, song_name), format!("{}{}_loop.ogg", prefix, song_name)],
+                ));
+                candidates.push((SongFormat::OggSinglePart, vec![format!("{}{}.ogg", prefix, song_name)]));
+            }
+            candidates.push((SongFormat::Organya, vec![format!("{}{}.org", prefix, song_name)]));
+        }
+
+        candidates
+    }
+
+    /// Checks if any of the files the song can be loaded from exist for the current soundtrack.
+    /// Doesn't guarantee the song will load, `current_song` can be compared afterwards to be sure.
+    pub fn song_exists(&self, song_id: usize, constants: &EngineConstants, settings: &Settings, ctx: &Context) -> bool {
+        Self::song_candidates(song_id, constants, settings)
+            .iter()
+            .any(|(_, paths)| paths.iter().all(|path| filesystem::exists(ctx, path)))
+    }
+
     pub fn play_song(
         &mut self,
         song_id: usize,
@@ -268,117 +414,93 @@ impl SoundManager {
             self.send(PlaybackMessage::SetOrgInterpolation(settings.organya_interpolation)).unwrap();
             self.send(PlaybackMessage::SaveState).unwrap();
             self.send(PlaybackMessage::Stop).unwrap();
-        } else if let Some(song_name) = constants.music_table.get(song_id) {
-            let mut paths = constants.organya_paths.clone();
-
-            paths.insert(0, "/Soundtracks/".to_owned() + &settings.soundtrack + "/");
-
-            if let Some(soundtrack) =
-            constants.soundtracks.iter().find(|s| s.available && s.name == settings.soundtrack)
+        } else {
+            for (format, paths) in Self::song_candidates(song_id, constants, settings)
+                .iter()
+                .filter(|(_, paths)| paths.iter().all(|path| filesystem::exists(ctx, path)))
             {
-                paths.insert(0, soundtrack.path.clone());
-            }
-
-            let songs_paths = paths.iter().map(|prefix| {
-                [
-                        #[cfg(feature = "ogg-playback")]
-                    (
-                        SongFormat::OggMultiPart,
-                        vec![format!("{}{}_intro.ogg", prefix, song_name), format!("{}{}_loop.ogg", prefix, song_name)],
-                    ),
-                        #[cfg(feature = "ogg-playback")]
-                    (SongFormat::OggSinglePart, vec![format!("{}{}.ogg", prefix, song_name)]),
-                    (SongFormat::Organya, vec![format!("{}{}.org", prefix, song_name)]),
-                ]
-            });
-
-            for songs in songs_paths {
-                for (format, paths) in
-                songs.iter().filter(|(_, paths)| paths.iter().all(|path| filesystem::exists(ctx, path)))
-                {
-                    match format {
-                        SongFormat::Organya => {
-                            // we're sure that there's one element
-                            let path = unsafe { paths.get_unchecked(0) };
-
-                            match filesystem::open(ctx, path).map(organya::Song::load_from) {
-                                Ok(Ok(org)) => {
-                                    log::info!("Playing Organya BGM: {} {}", song_id, path);
-
-                                    self.prev_song_id = self.current_song_id;
-                                    self.current_song_id = song_id;
-                                    let _ = self
-                                        .send(PlaybackMessage::SetOrgInterpolation(settings.organya_interpolation))
-                                        .unwrap();
-                                    self.send(PlaybackMessage::SaveState).unwrap();
-                                    self.send(PlaybackMessage::PlayOrganyaSong(Box::new(org))).unwrap();
-
-                                    return Ok(());
-                                }
-                                Ok(Err(err)) | Err(err) => {
-                                    log::warn!("Failed to load Organya BGM {}: {}", song_id, err);
-                                }
+                match format {
+                    SongFormat::Organya => {
+                        // we're sure that there's one element
+                        let path = unsafe { paths.get_unchecked(0) };
+
+                        match filesystem::open(ctx, path).map(organya::Song::load_from) {
+                            Ok(Ok(org)) => {
+                                log::info!("Playing Organya BGM: {} {}", song_id, path);
+
+                                self.prev_song_id = self.current_song_id;
+                                self.current_song_id = song_id;
+                                let _ = self
+                                    .send(PlaybackMessage::SetOrgInterpolation(settings.organya_interpolation))
+                                    .unwrap();
+                                self.send(PlaybackMessage::SaveState).unwrap();
+                                self.send(PlaybackMessage::PlayOrganyaSong(Box::new(org))).unwrap();
+
+                                return Ok(());
+                            }
+                            Ok(Err(err)) | Err(err) => {
+                                log::warn!("Failed to load Organya BGM {}: {}", song_id, err);
                             }
                         }
-                        #[cfg(feature = "ogg-playback")]
-                        SongFormat::OggSinglePart => {
-                            // we're sure that there's one element
-                            let path = unsafe { paths.get_unchecked(0) };
+                    }
+                    #[cfg(feature = "ogg-playback")]
+                    SongFormat::OggSinglePart => {
+                        // we're sure that there's one element
+                        let path = unsafe { paths.get_unchecked(0) };
+
+                        match filesystem::open(ctx, path).map(|f| {
+                            OggStreamReader::new(f).map_err(|e| GameError::ResourceLoadError(e.to_string()))
+                        }) {
+                            Ok(Ok(song)) => {
+                                log::info!("Playing single part Ogg BGM: {} {}", song_id, path);
+
+                                self.prev_song_id = self.current_song_id;
+                                self.current_song_id = song_id;
+                                self.send(PlaybackMessage::SaveState).unwrap();
+                                self.send(PlaybackMessage::PlayOggSongSinglePart(Box::new(song))).unwrap();
+
+                                return Ok(());
+                            }
+                            Ok(Err(err)) | Err(err) => {
+                                log::warn!("Failed to load single part Ogg BGM {}: {}", song_id, err);
+                            }
+                        }
+                    }
+                    #[cfg(feature = "ogg-playback")]
+                    SongFormat::OggMultiPart => {
+                        // we're sure that there are two elements
+                        let path_intro = unsafe { paths.get_unchecked(0) };
+                        let path_loop = unsafe { paths.get_unchecked(1) };
 
-                            match filesystem::open(ctx, path).map(|f| {
+                        match (
+                            filesystem::open(ctx, path_intro).map(|f| {
                                 OggStreamReader::new(f).map_err(|e| GameError::ResourceLoadError(e.to_string()))
-                            }) {
-                                Ok(Ok(song)) => {
-                                    log::info!("Playing single part Ogg BGM: {} {}", song_id, path);
-
-                                    self.prev_song_id = self.current_song_id;
-                                    self.current_song_id = song_id;
-                                    self.send(PlaybackMessage::SaveState).unwrap();
-                                    self.send(PlaybackMessage::PlayOggSongSinglePart(Box::new(song))).unwrap();
-
-                                    return Ok(());
-                                }
-                                Ok(Err(err)) | Err(err) => {
-                                    log::warn!("Failed to load single part Ogg BGM {}: {}", song_id, err);
-                                }
+                            }),
+                            filesystem::open(ctx, path_loop).map(|f| {
+                                OggStreamReader::new(f).map_err(|e| GameError::ResourceLoadError(e.to_string()))
+                            }),
+                        ) {
+                            (Ok(Ok(song_intro)), Ok(Ok(song_loop))) => {
+                                log::info!(
+                                    "Playing multi part Ogg BGM: {} {} + {}",
+                                    song_id,
+                                    path_intro,
+                                    path_loop
+                                );
+
+                                self.prev_song_id = self.current_song_id;
+                                self.current_song_id = song_id;
+                                self.send(PlaybackMessage::SaveState).unwrap();
+                                self.send(PlaybackMessage::PlayOggSongMultiPart(
+                                    Box::new(song_intro),
+                                    Box::new(song_loop),
+                                ))
+                                    .unwrap();
+
+                                return Ok(());
                             }
-                        }
-                        #[cfg(feature = "ogg-playback")]
-                        SongFormat::OggMultiPart => {
-                            // we're sure that there are two elements
-                            let path_intro = unsafe { paths.get_unchecked(0) };
-                            let path_loop = unsafe { paths.get_unchecked(1) };
-
-                            match (
-                                filesystem::open(ctx, path_intro).map(|f| {
-                                    OggStreamReader::new(f).map_err(|e| GameError::ResourceLoadError(e.to_string()))
-                                }),
-                                filesystem::open(ctx, path_loop).map(|f| {
-                                    OggStreamReader::new(f).map_err(|e| GameError::ResourceLoadError(e.to_string()))
-                                }),
-                            ) {
-                                (Ok(Ok(song_intro)), Ok(Ok(song_loop))) => {
-                                    log::info!(
-                                        "Playing multi part Ogg BGM: {} {} + {}",
-                                        song_id,
-                                        path_intro,
-                                        path_loop
-                                    );
-
-                                    self.prev_song_id = self.current_song_id;
-                                    self.current_song_id = song_id;
-                                    self.send(PlaybackMessage::SaveState).unwrap();
-                                    self.send(PlaybackMessage::PlayOggSongMultiPart(
-                                        Box::new(song_intro),
-                                        Box::new(song_loop),
-                                    ))
-                                        .unwrap();
-
-                                    return Ok(());
-                                }
-                                (Ok(Err(err)), _) | (Err(err), _) | (_, Ok(Err(err))) | (_, Err(err)) => {
-                                    log::warn!("Failed to load multi part Ogg BGM {}: {}", song_id, err);
-                                }
+                            (Ok(Err(err)), _) | (Err(err), _) | (_, Ok(Err(err))) | (_, Err(err)) => {
+                                log::warn!("Failed to load multi part Ogg BGM {}: {}", song_id, err);
                             }
                         }
                     }
@@ -389,6 +511,21 @@ impl SoundManager {
         Ok(())
     }
 
+    /// Fades the current song out over given amount of game ticks, running at `tps` ticks per second,
+    /// and stops it afterwards. The playback state is saved beforehand, so it can be resumed through `restore_state`.
+    /// Starting another song while the fade is in progress cancels it.
+    pub fn fade_out_music(&mut self, ticks: u16, tps: f64) {
+        if self.no_audio || self.current_song_id == 0 {
+            return;
+        }
+
+        self.prev_song_id = self.current_song_id;
+        self.current_song_id = 0;
+
+        self.send(PlaybackMessage::SaveState).unwrap();
+        self.send(PlaybackMessage::FadeOut(fade_duration(ticks, tps))).unwrap();
+    }
+
     pub fn save_state(&mut self) -> GameResult {
         if self.no_audio {
             return Ok(());
@@ -543,12 +680,15 @@ pub(in crate::sound) enum PlaybackMessage {
     #[cfg(feature = "ogg-playback")]
     PlayOggSongMultiPart(Box<OggStreamReader<File>>, Box<OggStreamReader<File>>),
     PlaySample(u8),
+    PlaySamplePositioned(u8, f32, f32),
     LoopSample(u8),
     LoopSampleFreq(u8, f32),
     StopSample(u8),
+    StopLoopingSamples,
     SetSpeed(f32),
     SetSongVolume(f32),
     SetSampleVolume(f32),
+    FadeOut(f32),
     SaveState,
     RestoreState,
     SetSampleParams(u8, PixToneParameters),
@@ -607,12 +747,18 @@ fn run<T>(
 
     let buf_size = sample_rate as usize * 10 / 1000;
     let mut bgm_buf = vec![0x8080; buf_size * 2];
-    let mut pxt_buf = vec![0x8000; buf_size];
+    let mut pxt_buf = vec![0x8000; buf_size * 2];
     let mut bgm_index = 0;
     let mut pxt_index = 0;
     let mut samples = 0;
     let mut bgm_vol = 1.0_f32;
     let mut sfx_vol = 1.0_f32;
+    let mut bgm_vol_target = 1.0_f32;
+    let mut sfx_vol_target = 1.0_f32;
+    // volume changes are ramped over ~10ms to avoid audible clicks
+    let vol_step = 1.0 / (sample_rate * 0.01);
+    let mut fade_vol = 1.0_f32;
+    let mut fade_step = 0.0_f32;
     pixtone.mix(&mut pxt_buf, sample_rate);
 
     let err_fn = |err| eprintln!("an error occurred on stream: {}", err);
@@ -627,6 +773,8 @@ fn run<T>(
                             saved_state = PlaybackStateType::None;
                         }
 
+                        fade_vol = 1.0;
+                        fade_step = 0.0;
                         org_engine.start_song(*song, &bank);
 
                         for i in &mut bgm_buf[0..samples] {
@@ -643,6 +791,8 @@ fn run<T>(
                             saved_state = PlaybackStateType::None;
                         }
 
+                        fade_vol = 1.0;
+                        fade_step = 0.0;
                         ogg_engine.start_single(data);
 
                         for i in &mut bgm_buf[0..samples] {
@@ -659,6 +809,8 @@ fn run<T>(
                             saved_state = PlaybackStateType::None;
                         }
 
+                        fade_vol = 1.0;
+                        fade_step = 0.0;
                         ogg_engine.start_multi(data_intro, data_loop);
 
                         for i in &mut bgm_buf[0..samples] {
@@ -672,6 +824,9 @@ fn run<T>(
                     Ok(PlaybackMessage::PlaySample(id)) => {
                         pixtone.play_sfx(id);
                     }
+                    Ok(PlaybackMessage::PlaySamplePositioned(id, volume, pan)) => {
+                        pixtone.play_sfx_panned(id, volume, pan);
+                    }
 
                     Ok(PlaybackMessage::LoopSample(id)) => {
                         pixtone.loop_sfx(id);
@@ -682,11 +837,16 @@ fn run<T>(
                     Ok(PlaybackMessage::StopSample(id)) => {
                         pixtone.stop_sfx(id);
                     }
+                    Ok(PlaybackMessage::StopLoopingSamples) => {
+                        pixtone.stop_looping_sfx();
+                    }
                     Ok(PlaybackMessage::Stop) => {
                         if state == PlaybackState::Stopped {
                             saved_state = PlaybackStateType::None;
                         }
 
+                        fade_vol = 1.0;
+                        fade_step = 0.0;
                         state = PlaybackState::Stopped;
                     }
                     Ok(PlaybackMessage::SetSpeed(new_speed)) => {
@@ -697,12 +857,15 @@ fn run<T>(
                         org_engine.set_sample_rate((sample_rate / new_speed) as usize);
                     }
                     Ok(PlaybackMessage::SetSongVolume(new_volume)) => {
-                        assert!(bgm_vol >= 0.0);
-                        bgm_vol = new_volume;
+                        assert!(new_volume >= 0.0);
+                        bgm_vol_target = new_volume;
                     }
                     Ok(PlaybackMessage::SetSampleVolume(new_volume)) => {
-                        assert!(sfx_vol >= 0.0);
-                        sfx_vol = new_volume;
+                        assert!(new_volume >= 0.0);
+                        sfx_vol_target = new_volume;
+                    }
+                    Ok(PlaybackMessage::FadeOut(duration)) => {
+                        fade_step = if duration > 0.0 { 1.0 / (sample_rate * duration) } else { 1.0 };
                     }
                     Ok(PlaybackMessage::SaveState) => {
                         saved_state = match state {
@@ -714,6 +877,8 @@ fn run<T>(
                     }
                     Ok(PlaybackMessage::RestoreState) => {
                         let saved_state_loc = std::mem::take(&mut saved_state);
+                        fade_vol = 1.0;
+                        fade_step = 0.0;
 
                         match saved_state_loc {
                             PlaybackStateType::None => {
@@ -768,6 +933,21 @@ fn run<T>(
             }
 
             for frame in data.chunks_mut(channels) {
+                bgm_vol = ramp_volume(bgm_vol, bgm_vol_target, vol_step);
+                sfx_vol = ramp_volume(sfx_vol, sfx_vol_target, vol_step);
+
+                if fade_step > 0.0 {
+                    fade_vol -= fade_step;
+
+                    if fade_vol <= 0.0 {
+                        fade_vol = 1.0;
+                        fade_step = 0.0;
+                        state = PlaybackState::Stopped;
+                    }
+                }
+
+                let bgm_out_vol = bgm_vol * fade_vol;
+
                 let (bgm_sample_l, bgm_sample_r): (u16, u16) = {
                     if state == PlaybackState::Stopped {
                         (0x8000, 0x8000)
@@ -795,10 +975,10 @@ fn run<T>(
                     }
                 };
 
-                let pxt_sample: u16 = pxt_buf[pxt_index];
+                let (pxt_sample_l, pxt_sample_r) = (pxt_buf[pxt_index], pxt_buf[pxt_index + 1]);
 
-                if pxt_index < (pxt_buf.len() - 1) {
-                    pxt_index += 1;
+                if pxt_index < (pxt_buf.len() - 2) {
+                    pxt_index += 2;
                 } else {
                     pxt_index = 0;
                     pxt_buf.fill(0x8000);
@@ -807,15 +987,15 @@ fn run<T>(
 
                 if frame.len() >= 2 {
                     let sample_l = clamp(
-                        (((bgm_sample_l ^ 0x8000) as i16) as f32 * bgm_vol) as isize
-                            + (((pxt_sample ^ 0x8000) as i16) as f32 * sfx_vol) as isize,
+                        (((bgm_sample_l ^ 0x8000) as i16) as f32 * bgm_out_vol) as isize
+                            + (((pxt_sample_l ^ 0x8000) as i16) as f32 * sfx_vol) as isize,
                         -0x7fff,
                         0x7fff,
                     ) as u16
                         ^ 0x8000;
                     let sample_r = clamp(
-                        (((bgm_sample_r ^ 0x8000) as i16) as f32 * bgm_vol) as isize
-                            + (((pxt_sample ^ 0x8000) as i16) as f32 * sfx_vol) as isize,
+                        (((bgm_sample_r ^ 0x8000) as i16) as f32 * bgm_out_vol) as isize
+                            + (((pxt_sample_r ^ 0x8000) as i16) as f32 * sfx_vol) as isize,
                         -0x7fff,
                         0x7fff,
                     ) as u16
@@ -825,9 +1005,11 @@ fn run<T>(
                     frame[1] = Sample::from::<u16>(&sample_r);
                 } else {
                     let sample = clamp(
-                        ((((bgm_sample_l ^ 0x8000) as i16) + ((bgm_sample_r ^ 0x8000) as i16)) as f32 * bgm_vol / 2.0)
-                            as isize
-                            + (((pxt_sample ^ 0x8000) as i16) as f32 * sfx_vol) as isize,
+                        ((((bgm_sample_l ^ 0x8000) as i16) + ((bgm_sample_r ^ 0x8000) as i16)) as f32 * bgm_out_vol
+                            / 2.0) as isize
+                            + ((((pxt_sample_l ^ 0x8000) as i16) as f32 + ((pxt_sample_r ^ 0x8000) as i16) as f32)
+                                * sfx_vol
+                                / 2.0) as isize,
                         -0x7fff,
                         0x7fff,
                     ) as u16
@@ -849,3 +1031,29 @@ fn run<T>(
 
     Ok(stream)
 }
+
+#[inline]
+fn ramp_volume(current: f32, target: f32, step: f32) -> f32 {
+    if current < target {
+        (current + step).min(target)
+    } else {
+        (current - step).max(target)
+    }
+}
+
+/// Length of a fade over `ticks` game ticks in seconds. Frame synchronized timing has no fixed tick rate,
+/// the original 50 ticks per second are assumed then.
+fn fade_duration(ticks: u16, tps: f64) -> f32 {
+    let tps = if tps > 0.0 { tps } else { 50.0 };
+
+    (ticks as f64 / tps) as f32
+}
+
+#[test]
+fn test_fade_duration() {
+    assert_eq!(fade_duration(100, 50.0), 2.0);
+    assert_eq!(fade_duration(120, 60.0), 2.0);
+    // twice the game speed, twice as fast a fade
+    assert_eq!(fade_duration(100, 100.0), 1.0);
+    assert_eq!(fade_duration(100, 0.0), 2.0);
+}
@@ -1,3 +1,5 @@
+//! Parser for Organya (`.org`) songs, usable on its own for inspecting song files.
+
 use std::io;
 
 use byteorder::{LE, ReadBytesExt};
@@ -23,9 +25,12 @@ pub struct LoopRange {
     pub end: i32,
 }
 
+/// How the song is laid out in the editor, doesn't affect playback.
 #[derive(Debug, Copy, Clone)]
 pub struct Display {
+    /// Beats per bar.
     pub beats: u8,
+    /// Steps per beat.
     pub steps: u8,
 }
 
@@ -78,6 +83,8 @@ pub struct Note {
 pub struct Song {
     pub version: Version,
     pub time: Timing,
+    pub display: Display,
+    /// 8 melody tracks followed by 8 percussion tracks.
     pub tracks: [Track; 16],
 }
 
@@ -86,6 +93,7 @@ impl Clone for Song {
         Song {
             version: self.version,
             time: self.time,
+            display: self.display,
             tracks: self.tracks.clone(),
         }
     }
@@ -96,6 +104,7 @@ impl Song {
         Song {
             version: Version::Main,
             time: Timing { wait: 8, loop_range: LoopRange { start: 0, end: 1 } },
+            display: Display { beats: 4, steps: 4 },
             tracks: [
                 Track { inst: Instrument { freq: 1000, inst: 0, pipi: 0, notes: 0 }, notes: vec![] },
                 Track { inst: Instrument { freq: 1000, inst: 0, pipi: 0, notes: 0 }, notes: vec![] },
@@ -117,6 +126,11 @@ impl Song {
         }
     }
 
+    /// Number of tracks that have any notes.
+    pub fn track_count(&self) -> usize {
+        self.tracks.iter().filter(|track| !track.notes.is_empty()).count()
+    }
+
     pub fn load_from<R: io::Read>(mut f: R) -> GameResult<Song> {
         let mut magic = [0; 6];
 
@@ -127,12 +141,17 @@ impl Song {
                 b"Org-01" => Version::Beta,
                 b"Org-02" => Version::Main,
                 b"Org-03" => Version::Extended,
-                _ => return Err(GameError::ResourceLoadError("Invalid magic number".to_string()))
+                _ => {
+                    return Err(GameError::ResourceLoadError(format!(
+                        "Invalid Organya magic number: {:?}",
+                        String::from_utf8_lossy(&magic)
+                    )))
+                }
             };
 
         let wait = f.read_u16::<LE>()?;
-        let _bpm = f.read_u8()?;
-        let _spb = f.read_u8()?;
+        let beats = f.read_u8()?;
+        let steps = f.read_u8()?;
         let start = f.read_i32::<LE>()?;
         let end = f.read_i32::<LE>()?;
 
@@ -220,9 +239,64 @@ impl Song {
                     end,
                 },
             },
+            display: Display { beats, steps },
             tracks,
         };
 
         Ok(song)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_song(magic: &[u8; 6]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(magic);
+        data.extend_from_slice(&125u16.to_le_bytes()); // wait
+        data.push(4); // beats
+        data.push(4); // steps
+        data.extend_from_slice(&0i32.to_le_bytes()); // loop start
+        data.extend_from_slice(&64i32.to_le_bytes()); // loop end
+
+        for i in 0..16 {
+            data.extend_from_slice(&1000u16.to_le_bytes());
+            data.push(i);
+            data.push(0);
+            data.extend_from_slice(&(if i == 0 { 2u16 } else { 0u16 }).to_le_bytes());
+        }
+
+        // two notes on the first track, each property stored together for all notes
+        data.extend_from_slice(&0i32.to_le_bytes());
+        data.extend_from_slice(&8i32.to_le_bytes());
+        data.extend_from_slice(&[36, 48]);
+        data.extend_from_slice(&[4, 2]);
+        data.extend_from_slice(&[200, 100]);
+        data.extend_from_slice(&[6, 6]);
+
+        data
+    }
+
+    #[test]
+    fn test_load_song() {
+        for (magic, version) in [(b"Org-02", Version::Main), (b"Org-03", Version::Extended)] {
+            let song = Song::load_from(&sample_song(magic)[..]).unwrap();
+
+            assert_eq!(song.version, version);
+            assert_eq!(song.time.wait, 125);
+            assert_eq!(song.time.loop_range.end, 64);
+            assert_eq!(song.display.beats, 4);
+            assert_eq!(song.track_count(), 1);
+            assert_eq!(song.tracks[0].notes[1].pos, 8);
+            assert_eq!(song.tracks[0].notes[1].key, 48);
+            assert_eq!(song.tracks[0].notes[1].vol, 100);
+        }
+    }
+
+    #[test]
+    fn test_invalid_magic() {
+        assert!(Song::load_from(&sample_song(b"Org-09")[..]).is_err());
+        assert!(Song::load_from(&b"Org"[..]).is_err());
+    }
+}
@@ -183,6 +183,8 @@ pub struct PlaybackState {
     pos: f32,
     tag: u32,
     freq: f32,
+    vol_l: f32,
+    vol_r: f32,
 }
 
 pub struct PixTonePlayback {
@@ -219,15 +221,25 @@ impl PixTonePlayback {
     }
 
     pub fn play_sfx(&mut self, id: u8) {
+        self.play_sfx_panned(id, 1.0, 0.0);
+    }
+
+    /// Plays a sound effect at given volume (0.0 - 1.0) and pan (-1.0 left - 1.0 right).
+    pub fn play_sfx_panned(&mut self, id: u8, volume: f32, pan: f32) {
+        let vol_l = volume * (1.0 - pan).min(1.0);
+        let vol_r = volume * (1.0 + pan).min(1.0);
+
         for state in &mut self.playback_state {
             if state.id == id && state.tag == 0 {
                 state.pos = 0.0;
                 state.looping = false;
+                state.vol_l = vol_l;
+                state.vol_r = vol_r;
                 return;
             }
         }
 
-        self.playback_state.push(PlaybackState { id, pos: 0.0, tag: 0, looping: false, freq: 1.0 });
+        self.playback_state.push(PlaybackState { id, pos: 0.0, tag: 0, looping: false, freq: 1.0, vol_l, vol_r });
     }
 
     pub fn loop_sfx(&mut self, id: u8) {
@@ -238,7 +250,15 @@ impl PixTonePlayback {
             }
         }
 
-        self.playback_state.push(PlaybackState { id, pos: 0.0, tag: 0, looping: true, freq: 1.0 });
+        self.playback_state.push(PlaybackState {
+            id,
+            pos: 0.0,
+            tag: 0,
+            looping: true,
+            freq: 1.0,
+            vol_l: 1.0,
+            vol_r: 1.0,
+        });
     }
 
     pub fn loop_sfx_freq(&mut self, id: u8, freq: f32) {
@@ -249,7 +269,7 @@ impl PixTonePlayback {
             }
         }
 
-        self.playback_state.push(PlaybackState { id, pos: 0.0, tag: 0, looping: true, freq });
+        self.playback_state.push(PlaybackState { id, pos: 0.0, tag: 0, looping: true, freq, vol_l: 1.0, vol_r: 1.0 });
     }
 
     pub fn stop_sfx(&mut self, id: u8) {
@@ -258,10 +278,23 @@ impl PixTonePlayback {
         }
     }
 
+    pub fn stop_looping_sfx(&mut self) {
+        self.playback_state.retain(|s| !s.looping);
+    }
+
     pub fn play_concurrent(&mut self, id: u8, tag: u32) {
-        self.playback_state.push(PlaybackState { id, pos: 0.0, tag, looping: false, freq: 1.0 });
+        self.playback_state.push(PlaybackState {
+            id,
+            pos: 0.0,
+            tag,
+            looping: false,
+            freq: 1.0,
+            vol_l: 1.0,
+            vol_r: 1.0,
+        });
     }
 
+    /// Mixes the playing sounds into `dst`, which holds interleaved stereo samples.
     pub fn mix(&mut self, dst: &mut [u16], sample_rate: f32) {
         let mut scan = VecMutScan::new(&mut self.playback_state);
         let delta = 22050.0 / sample_rate;
@@ -276,7 +309,7 @@ impl PixTonePlayback {
                     continue;
                 };
 
-                for result in dst.iter_mut() {
+                for result in dst.chunks_exact_mut(2) {
                     if state.pos >= sample.len() as f32 {
                         if state.looping {
                             state.pos = 0.0;
@@ -294,8 +327,10 @@ impl PixTonePlayback {
 
                     let s = cubic_interp(s1, s2, s4, s3, state.pos.fract()) * 32768.0;
                     // let s = sample[pos] as f32;
-                    let sam = (*result ^ 0x8000) as i16;
-                    *result = sam.saturating_add(s as i16) as u16 ^ 0x8000;
+                    let sam_l = (result[0] ^ 0x8000) as i16;
+                    let sam_r = (result[1] ^ 0x8000) as i16;
+                    result[0] = sam_l.saturating_add((s * state.vol_l) as i16) as u16 ^ 0x8000;
+                    result[1] = sam_r.saturating_add((s * state.vol_r) as i16) as u16 ^ 0x8000;
 
                     state.pos += delta * state.freq;
                 }
@@ -0,0 +1,68 @@
+/// How far a sound may be panned to either side, so sounds coming from just off the edge
+/// of the screen don't end up playing only in one ear.
+const MAX_PAN: f32 = 0.6;
+
+/// Tracks the listener (the camera) and turns world positions into sound effect volume and panning.
+pub struct PositionalAudio {
+    pub enabled: bool,
+    /// Distance past the edge of the screen at which sounds become silent, in fix9 units.
+    pub range: i32,
+    x: i32,
+    y: i32,
+    half_width: i32,
+    half_height: i32,
+}
+
+impl PositionalAudio {
+    pub fn new() -> PositionalAudio {
+        PositionalAudio { enabled: true, range: 160 * 0x200, x: 0, y: 0, half_width: 0, half_height: 0 }
+    }
+
+    /// Sets the center and half size of the visible area, in fix9 units.
+    pub fn set_listener(&mut self, x: i32, y: i32, half_width: i32, half_height: i32) {
+        self.x = x;
+        self.y = y;
+        self.half_width = half_width.max(1);
+        self.half_height = half_height.max(1);
+    }
+
+    /// Returns the volume (0.0 - 1.0) and pan (-1.0 left - 1.0 right) of a sound played at the given world position.
+    /// Sounds on screen play at full volume, past the edge of the screen they fade out linearly over `range`.
+    pub fn volume_and_pan(&self, x: i32, y: i32) -> (f32, f32) {
+        if !self.enabled {
+            return (1.0, 0.0);
+        }
+
+        let dx = x - self.x;
+        let dy = y - self.y;
+        let outside_x = (dx.abs() - self.half_width).max(0);
+        let outside_y = (dy.abs() - self.half_height).max(0);
+        let distance = outside_x.max(outside_y);
+
+        let volume = 1.0 - distance as f32 / self.range.max(1) as f32;
+        let pan = (dx as f32 / self.half_width as f32).clamp(-1.0, 1.0) * MAX_PAN;
+
+        (volume.clamp(0.0, 1.0), pan)
+    }
+}
+
+#[test]
+fn test_positional_falloff() {
+    let mut audio = PositionalAudio::new();
+    audio.range = 100 * 0x200;
+    audio.set_listener(0, 0, 200 * 0x200, 120 * 0x200);
+
+    // on screen at full volume, centered or panned towards the side it's coming from
+    assert_eq!(audio.volume_and_pan(0, 0), (1.0, 0.0));
+    assert_eq!(audio.volume_and_pan(-100 * 0x200, 0), (1.0, -MAX_PAN / 2.0));
+    assert_eq!(audio.volume_and_pan(200 * 0x200, 120 * 0x200), (1.0, MAX_PAN));
+
+    // fading out past the edge of the screen, silent once out of range
+    assert_eq!(audio.volume_and_pan(250 * 0x200, 0), (0.5, MAX_PAN));
+    assert_eq!(audio.volume_and_pan(0, -145 * 0x200).0, 0.75);
+    assert_eq!(audio.volume_and_pan(300 * 0x200, 0).0, 0.0);
+    assert_eq!(audio.volume_and_pan(-1000 * 0x200, 0), (0.0, -MAX_PAN));
+
+    audio.enabled = false;
+    assert_eq!(audio.volume_and_pan(-1000 * 0x200, 0), (1.0, 0.0));
+}
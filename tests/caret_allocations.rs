@@ -0,0 +1,80 @@
+//! Counts the heap allocations the caret pool makes per frame against the `Vec` it replaced. It's a separate test
+//! binary since counting needs its own global allocator. Run with `--nocapture` to see the numbers.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use doukutsu_rs::game::caret::CaretWorkload;
+
+struct CountingAllocator;
+
+thread_local! {
+    // counted per thread, so tests running in parallel don't skew each other's numbers
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|count| count.get())
+}
+
+const FRAMES: i32 = 600;
+
+/// Returns the total and the highest per frame allocation count, and the time spent.
+fn run(pooled: bool) -> (usize, usize, Duration) {
+    let mut workload = CaretWorkload::new(pooled);
+    let (mut total, mut max, mut time) = (0, 0, Duration::ZERO);
+
+    for frame in 0..FRAMES {
+        let start = (allocations(), Instant::now());
+        workload.run_frame(frame);
+        let count = allocations() - start.0;
+        time += start.1.elapsed();
+        total += count;
+        max = max.max(count);
+    }
+
+    if pooled {
+        // boss deaths spawn more than the pool holds, the oldest carets make room for them
+        assert!(workload.alive() <= 64);
+    }
+
+    (total, max, time)
+}
+
+#[test]
+fn bench_allocations_per_frame() {
+    let (pool_total, pool_max, pool_time) = run(true);
+    let (vec_total, vec_max, vec_time) = run(false);
+
+    println!(
+        "CaretPool: {} allocations over {} frames, at most {} per frame, {:?} total",
+        pool_total, FRAMES, pool_max, pool_time
+    );
+    println!(
+        "Vec<Caret>: {} allocations over {} frames, at most {} per frame, {:?} total",
+        vec_total, FRAMES, vec_max, vec_time
+    );
+
+    assert_eq!(pool_total, 0);
+    assert!(vec_total > 0);
+}